@@ -0,0 +1,11 @@
+//! Cluster audits that evaluate resources against security and hygiene policies
+
+mod env_secrets;
+mod security;
+
+pub(crate) use env_secrets::is_likely_secret;
+pub use env_secrets::{EnvSecretFinding, SecretConfidence, scan_pod_env_secrets};
+pub use security::{
+    NamespaceSecuritySummary, PodSecurityLevel, PodSecurityReport, SecurityFinding,
+    audit_pod_security, summarize_namespaces,
+};
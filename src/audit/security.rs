@@ -0,0 +1,703 @@
+use k8s_openapi::api::core::v1::{
+    AppArmorProfile, ContainerPort, Namespace, Pod, PodSecurityContext, SELinuxOptions,
+    SeccompProfile, SecurityContext, WindowsSecurityContextOptions,
+};
+use std::collections::BTreeMap;
+
+/// Capabilities that may be added under the baseline Pod Security Standard
+const BASELINE_CAPABILITIES: [&str; 13] = [
+    "AUDIT_WRITE",
+    "CHOWN",
+    "DAC_OVERRIDE",
+    "FOWNER",
+    "FSETID",
+    "KILL",
+    "MKNOD",
+    "NET_BIND_SERVICE",
+    "SETFCAP",
+    "SETGID",
+    "SETPCAP",
+    "SETUID",
+    "SYS_CHROOT",
+];
+
+/// Volume types allowed under the restricted Pod Security Standard
+const RESTRICTED_VOLUME_TYPES: [&str; 8] = [
+    "configMap",
+    "csi",
+    "downwardAPI",
+    "emptyDir",
+    "ephemeral",
+    "persistentVolumeClaim",
+    "projected",
+    "secret",
+];
+
+/// Sysctls that may be set under the baseline Pod Security Standard
+const BASELINE_SYSCTLS: [&str; 10] = [
+    "kernel.shm_rmid_forced",
+    "net.ipv4.ip_local_port_range",
+    "net.ipv4.ip_local_reserved_ports",
+    "net.ipv4.ip_unprivileged_port_start",
+    "net.ipv4.ping_group_range",
+    "net.ipv4.tcp_fin_timeout",
+    "net.ipv4.tcp_keepalive_intvl",
+    "net.ipv4.tcp_keepalive_probes",
+    "net.ipv4.tcp_keepalive_time",
+    "net.ipv4.tcp_syncookies",
+];
+
+/// SELinux types that may be set under the baseline Pod Security Standard
+const BASELINE_SELINUX_TYPES: [&str; 4] = [
+    "container_t",
+    "container_init_t",
+    "container_kvm_t",
+    "container_engine_t",
+];
+
+/// Annotation prefix setting the AppArmor profile of a container before the `appArmorProfile`
+/// field existed
+const APPARMOR_ANNOTATION_PREFIX: &str = "container.apparmor.security.beta.kubernetes.io/";
+
+/// Namespace label prefix used by the Pod Security admission controller
+const POD_SECURITY_LABEL_PREFIX: &str = "pod-security.kubernetes.io/";
+
+/// Pod Security Standards levels, from least to most restrictive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PodSecurityLevel {
+    /// Unrestricted
+    Privileged,
+    /// Prevents known privilege escalations
+    Baseline,
+    /// Follows current pod hardening best practices
+    Restricted,
+}
+
+impl std::fmt::Display for PodSecurityLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PodSecurityLevel::Privileged => write!(f, "privileged"),
+            PodSecurityLevel::Baseline => write!(f, "baseline"),
+            PodSecurityLevel::Restricted => write!(f, "restricted"),
+        }
+    }
+}
+
+impl std::str::FromStr for PodSecurityLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "privileged" => Ok(PodSecurityLevel::Privileged),
+            "baseline" => Ok(PodSecurityLevel::Baseline),
+            "restricted" => Ok(PodSecurityLevel::Restricted),
+            _ => Err(format!("unknown pod security level '{}'", s)),
+        }
+    }
+}
+
+/// A single security issue found in a pod spec
+#[derive(Debug, Clone)]
+pub struct SecurityFinding {
+    /// Container the finding applies to, or None for pod-level findings
+    pub container: Option<String>,
+    /// Human-readable description of the issue
+    pub message: String,
+    /// The Pod Security Standards level the issue violates, or None for hardening advice
+    /// outside of the standards (e.g. a writable root filesystem)
+    pub violates: Option<PodSecurityLevel>,
+}
+
+/// Result of auditing a single pod
+#[derive(Debug, Clone)]
+pub struct PodSecurityReport {
+    /// Namespace of the pod
+    pub namespace: String,
+    /// Name of the pod
+    pub name: String,
+    /// Most restrictive Pod Security Standards level the pod satisfies
+    pub level: PodSecurityLevel,
+    /// Issues found in the pod spec
+    pub findings: Vec<SecurityFinding>,
+}
+
+/// Pod Security Standards compliance of all audited pods in a namespace
+#[derive(Debug, Clone)]
+pub struct NamespaceSecuritySummary {
+    /// Name of the namespace
+    pub namespace: String,
+    /// Number of audited pods per level they satisfy
+    pub pods_per_level: BTreeMap<PodSecurityLevel, usize>,
+    /// Level enforced by the `pod-security.kubernetes.io/enforce` label, if any
+    pub enforce: Option<String>,
+    /// Level audited by the `pod-security.kubernetes.io/audit` label, if any
+    pub audit: Option<String>,
+    /// Level warned about by the `pod-security.kubernetes.io/warn` label, if any
+    pub warn: Option<String>,
+    /// Number of pods that do not satisfy the enforced level (e.g. admitted before it was set)
+    pub violating_enforce: usize,
+}
+
+/// Uniform view over init, regular and ephemeral containers
+struct ContainerView<'a> {
+    name: &'a str,
+    security_context: Option<&'a SecurityContext>,
+    ports: Option<&'a Vec<ContainerPort>>,
+}
+
+fn containers(pod: &Pod) -> Vec<ContainerView<'_>> {
+    let Some(spec) = &pod.spec else {
+        return Vec::new();
+    };
+    let mut views = Vec::new();
+    for c in spec
+        .init_containers
+        .iter()
+        .flatten()
+        .chain(&spec.containers)
+    {
+        views.push(ContainerView {
+            name: &c.name,
+            security_context: c.security_context.as_ref(),
+            ports: c.ports.as_ref(),
+        });
+    }
+    for c in spec.ephemeral_containers.iter().flatten() {
+        views.push(ContainerView {
+            name: &c.name,
+            security_context: c.security_context.as_ref(),
+            ports: c.ports.as_ref(),
+        });
+    }
+    views
+}
+
+fn seccomp_type(profile: Option<&SeccompProfile>) -> Option<&str> {
+    profile.map(|p| p.type_.as_str())
+}
+
+/// Describe the SELinux options that are not allowed under the baseline Pod Security Standard
+fn disallowed_selinux_options(options: Option<&SELinuxOptions>) -> Vec<String> {
+    let Some(options) = options else {
+        return Vec::new();
+    };
+    let mut disallowed = Vec::new();
+    if let Some(type_) = options.type_.as_deref().filter(|t| !t.is_empty())
+        && !BASELINE_SELINUX_TYPES.contains(&type_)
+    {
+        disallowed.push(format!("SELinux type {}", type_));
+    }
+    for (value, field) in [(&options.user, "user"), (&options.role, "role")] {
+        if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+            disallowed.push(format!("SELinux {} {}", field, value));
+        }
+    }
+    disallowed
+}
+
+/// Check if an AppArmor profile is not allowed under the baseline Pod Security Standard, i.e.
+/// neither RuntimeDefault nor Localhost
+fn is_unconfined_apparmor(profile: Option<&AppArmorProfile>) -> bool {
+    profile.is_some_and(|p| p.type_ != "RuntimeDefault" && p.type_ != "Localhost")
+}
+
+/// Check if Windows options run the container as a HostProcess with access to the host
+fn is_host_process(options: Option<&WindowsSecurityContextOptions>) -> bool {
+    options.and_then(|o| o.host_process).unwrap_or(false)
+}
+
+/// Audit a pod spec against the Pod Security Standards and common hardening practices
+///
+/// # Arguments
+///
+/// * `pod` - The pod to audit
+///
+/// # Returns
+///
+/// * `PodSecurityReport` - The findings and the most restrictive level the pod satisfies
+pub fn audit_pod_security(pod: &Pod) -> PodSecurityReport {
+    let mut findings = Vec::new();
+    let mut finding = |container: Option<&str>, message: String, violates| {
+        findings.push(SecurityFinding {
+            container: container.map(str::to_string),
+            message,
+            violates,
+        });
+    };
+
+    let spec = pod.spec.as_ref();
+    let pod_context: Option<&PodSecurityContext> = spec.and_then(|s| s.security_context.as_ref());
+
+    // Host namespaces
+    if let Some(spec) = spec {
+        for (enabled, name) in [
+            (spec.host_network, "hostNetwork"),
+            (spec.host_pid, "hostPID"),
+            (spec.host_ipc, "hostIPC"),
+        ] {
+            if enabled.unwrap_or(false) {
+                finding(
+                    None,
+                    format!("{} is enabled", name),
+                    Some(PodSecurityLevel::Baseline),
+                );
+            }
+        }
+
+        for volume in spec.volumes.iter().flatten() {
+            if let Some(host_path) = &volume.host_path {
+                finding(
+                    None,
+                    format!("volume {} mounts hostPath {}", volume.name, host_path.path),
+                    Some(PodSecurityLevel::Baseline),
+                );
+                continue;
+            }
+
+            // Volumes serialize as {"name": ..., "<type>": {...}}
+            let volume_type = serde_json::to_value(volume).ok().and_then(|value| {
+                value
+                    .as_object()
+                    .and_then(|fields| fields.keys().find(|k| k.as_str() != "name").cloned())
+            });
+            if let Some(volume_type) = volume_type
+                && !RESTRICTED_VOLUME_TYPES.contains(&volume_type.as_str())
+            {
+                finding(
+                    None,
+                    format!(
+                        "volume {} uses disallowed type {}",
+                        volume.name, volume_type
+                    ),
+                    Some(PodSecurityLevel::Restricted),
+                );
+            }
+        }
+    }
+
+    let pod_seccomp = seccomp_type(pod_context.and_then(|c| c.seccomp_profile.as_ref()));
+    if pod_seccomp == Some("Unconfined") {
+        finding(
+            None,
+            "seccomp profile is Unconfined".to_string(),
+            Some(PodSecurityLevel::Baseline),
+        );
+    }
+    for sysctl in pod_context
+        .and_then(|c| c.sysctls.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        if !BASELINE_SYSCTLS.contains(&sysctl.name.as_str()) {
+            finding(
+                None,
+                format!("sets unsafe sysctl {}", sysctl.name),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+    }
+    for option in disallowed_selinux_options(pod_context.and_then(|c| c.se_linux_options.as_ref()))
+    {
+        finding(
+            None,
+            format!("sets {}", option),
+            Some(PodSecurityLevel::Baseline),
+        );
+    }
+    if is_unconfined_apparmor(pod_context.and_then(|c| c.app_armor_profile.as_ref())) {
+        finding(
+            None,
+            "AppArmor profile is Unconfined".to_string(),
+            Some(PodSecurityLevel::Baseline),
+        );
+    }
+    if is_host_process(pod_context.and_then(|c| c.windows_options.as_ref())) {
+        finding(
+            None,
+            "runs as a Windows HostProcess".to_string(),
+            Some(PodSecurityLevel::Baseline),
+        );
+    }
+    let pod_run_as_non_root = pod_context.and_then(|c| c.run_as_non_root);
+    let pod_run_as_user = pod_context.and_then(|c| c.run_as_user);
+    if pod_run_as_user == Some(0) {
+        finding(
+            None,
+            "runAsUser is 0 (root)".to_string(),
+            Some(PodSecurityLevel::Restricted),
+        );
+    }
+
+    for container in containers(pod) {
+        let name = Some(container.name);
+        let context = container.security_context;
+
+        if context.and_then(|c| c.privileged).unwrap_or(false) {
+            finding(
+                name,
+                "runs privileged".to_string(),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+
+        for port in container.ports.into_iter().flatten() {
+            if let Some(host_port) = port.host_port.filter(|p| *p != 0) {
+                finding(
+                    name,
+                    format!("uses hostPort {}", host_port),
+                    Some(PodSecurityLevel::Baseline),
+                );
+            }
+        }
+
+        let capabilities = context.and_then(|c| c.capabilities.as_ref());
+        for added in capabilities
+            .and_then(|c| c.add.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            if !BASELINE_CAPABILITIES.contains(&added.as_str()) {
+                finding(
+                    name,
+                    format!("adds capability {}", added),
+                    Some(PodSecurityLevel::Baseline),
+                );
+            } else if added != "NET_BIND_SERVICE" {
+                finding(
+                    name,
+                    format!("adds capability {}", added),
+                    Some(PodSecurityLevel::Restricted),
+                );
+            }
+        }
+        let drops_all = capabilities
+            .and_then(|c| c.drop.as_ref())
+            .is_some_and(|drop| drop.iter().any(|d| d == "ALL"));
+        if !drops_all {
+            finding(
+                name,
+                "does not drop ALL capabilities".to_string(),
+                Some(PodSecurityLevel::Restricted),
+            );
+        }
+
+        for option in disallowed_selinux_options(context.and_then(|c| c.se_linux_options.as_ref()))
+        {
+            finding(
+                name,
+                format!("sets {}", option),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+
+        let apparmor_annotation = pod
+            .metadata
+            .annotations
+            .as_ref()
+            .and_then(|a| a.get(&format!("{}{}", APPARMOR_ANNOTATION_PREFIX, container.name)))
+            .filter(|p| *p != "runtime/default" && !p.starts_with("localhost/"));
+        if let Some(profile) = apparmor_annotation {
+            finding(
+                name,
+                format!("AppArmor profile annotation is {}", profile),
+                Some(PodSecurityLevel::Baseline),
+            );
+        } else if is_unconfined_apparmor(context.and_then(|c| c.app_armor_profile.as_ref())) {
+            finding(
+                name,
+                "AppArmor profile is Unconfined".to_string(),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+
+        if is_host_process(context.and_then(|c| c.windows_options.as_ref())) {
+            finding(
+                name,
+                "runs as a Windows HostProcess".to_string(),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+
+        if context
+            .and_then(|c| c.proc_mount.as_deref())
+            .is_some_and(|m| m != "Default")
+        {
+            finding(
+                name,
+                "uses a non-default procMount".to_string(),
+                Some(PodSecurityLevel::Baseline),
+            );
+        }
+
+        let seccomp = seccomp_type(context.and_then(|c| c.seccomp_profile.as_ref()));
+        match seccomp.or(pod_seccomp) {
+            Some("Unconfined") => {
+                if seccomp == Some("Unconfined") {
+                    finding(
+                        name,
+                        "seccomp profile is Unconfined".to_string(),
+                        Some(PodSecurityLevel::Baseline),
+                    );
+                }
+            }
+            Some("RuntimeDefault") | Some("Localhost") => {}
+            _ => finding(
+                name,
+                "has no seccomp profile (RuntimeDefault or Localhost required)".to_string(),
+                Some(PodSecurityLevel::Restricted),
+            ),
+        }
+
+        if context.and_then(|c| c.allow_privilege_escalation) != Some(false) {
+            finding(
+                name,
+                "does not set allowPrivilegeEscalation=false".to_string(),
+                Some(PodSecurityLevel::Restricted),
+            );
+        }
+
+        let run_as_user = context.and_then(|c| c.run_as_user);
+        if run_as_user == Some(0) {
+            finding(
+                name,
+                "runAsUser is 0 (root)".to_string(),
+                Some(PodSecurityLevel::Restricted),
+            );
+        }
+        let run_as_non_root = context
+            .and_then(|c| c.run_as_non_root)
+            .or(pod_run_as_non_root);
+        if run_as_non_root != Some(true) {
+            finding(
+                name,
+                "may run as root (runAsNonRoot is not true)".to_string(),
+                Some(PodSecurityLevel::Restricted),
+            );
+        }
+
+        if context.and_then(|c| c.read_only_root_filesystem) != Some(true) {
+            finding(
+                name,
+                "root filesystem is writable (readOnlyRootFilesystem is not true)".to_string(),
+                None,
+            );
+        }
+    }
+
+    let level = if findings
+        .iter()
+        .any(|f| f.violates == Some(PodSecurityLevel::Baseline))
+    {
+        PodSecurityLevel::Privileged
+    } else if findings
+        .iter()
+        .any(|f| f.violates == Some(PodSecurityLevel::Restricted))
+    {
+        PodSecurityLevel::Baseline
+    } else {
+        PodSecurityLevel::Restricted
+    };
+
+    PodSecurityReport {
+        namespace: pod.metadata.namespace.clone().unwrap_or_default(),
+        name: pod.metadata.name.clone().unwrap_or_default(),
+        level,
+        findings,
+    }
+}
+
+/// Summarise pod reports per namespace and compare them with the namespace's Pod Security
+/// admission labels
+///
+/// # Arguments
+///
+/// * `reports` - The per-pod audit reports
+/// * `namespaces` - The namespaces the pods were listed from (used for their labels)
+///
+/// # Returns
+///
+/// * `Vec<NamespaceSecuritySummary>` - One summary per namespace, sorted by name
+pub fn summarize_namespaces(
+    reports: &[PodSecurityReport],
+    namespaces: &[Namespace],
+) -> Vec<NamespaceSecuritySummary> {
+    let mut summaries: BTreeMap<String, NamespaceSecuritySummary> = BTreeMap::new();
+
+    for namespace in namespaces {
+        let name = namespace.metadata.name.clone().unwrap_or_default();
+        let labels = namespace.metadata.labels.clone().unwrap_or_default();
+        let label = |mode: &str| {
+            labels
+                .get(&format!("{}{}", POD_SECURITY_LABEL_PREFIX, mode))
+                .cloned()
+        };
+        summaries.insert(
+            name.clone(),
+            NamespaceSecuritySummary {
+                namespace: name,
+                pods_per_level: BTreeMap::new(),
+                enforce: label("enforce"),
+                audit: label("audit"),
+                warn: label("warn"),
+                violating_enforce: 0,
+            },
+        );
+    }
+
+    for report in reports {
+        let summary = summaries
+            .entry(report.namespace.clone())
+            .or_insert_with(|| NamespaceSecuritySummary {
+                namespace: report.namespace.clone(),
+                pods_per_level: BTreeMap::new(),
+                enforce: None,
+                audit: None,
+                warn: None,
+                violating_enforce: 0,
+            });
+        *summary.pods_per_level.entry(report.level).or_default() += 1;
+
+        let enforced = summary
+            .enforce
+            .as_deref()
+            .and_then(|level| level.parse::<PodSecurityLevel>().ok());
+        if enforced.is_some_and(|enforced| report.level < enforced) {
+            summary.violating_enforce += 1;
+        }
+    }
+
+    summaries.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    /// Build a pod with one container, with the given pod and container securityContext and
+    /// annotations
+    fn pod(pod_context: Value, container_context: Value, annotations: Value) -> Pod {
+        serde_json::from_value(json!({
+            "metadata": {"name": "app", "namespace": "shop", "annotations": annotations},
+            "spec": {
+                "securityContext": pod_context,
+                "containers": [{"name": "app", "image": "app:1", "securityContext": container_context}]
+            }
+        }))
+        .unwrap()
+    }
+
+    /// Get the findings of a pod that violate the baseline level
+    fn baseline_violations(pod: &Pod) -> Vec<(Option<String>, String)> {
+        audit_pod_security(pod)
+            .findings
+            .into_iter()
+            .filter(|f| f.violates == Some(PodSecurityLevel::Baseline))
+            .map(|f| (f.container, f.message))
+            .collect()
+    }
+
+    #[test]
+    fn unsafe_sysctls_violate_baseline() {
+        let pod = pod(
+            json!({"sysctls": [
+                {"name": "net.ipv4.tcp_syncookies", "value": "1"},
+                {"name": "kernel.msgmax", "value": "65536"}
+            ]}),
+            json!({}),
+            json!({}),
+        );
+        assert_eq!(
+            baseline_violations(&pod),
+            [(None, "sets unsafe sysctl kernel.msgmax".to_string())]
+        );
+        assert_eq!(audit_pod_security(&pod).level, PodSecurityLevel::Privileged);
+    }
+
+    #[test]
+    fn custom_selinux_types_violate_baseline() {
+        let pod = pod(
+            json!({"seLinuxOptions": {"type": "container_t", "level": "s0:c123,c456"}}),
+            json!({"seLinuxOptions": {"type": "spc_t"}}),
+            json!({}),
+        );
+        assert_eq!(
+            baseline_violations(&pod),
+            [(
+                Some("app".to_string()),
+                "sets SELinux type spc_t".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn selinux_users_and_roles_violate_baseline() {
+        let pod = pod(
+            json!({"seLinuxOptions": {"user": "system_u", "role": "system_r"}}),
+            json!({}),
+            json!({}),
+        );
+        assert_eq!(
+            baseline_violations(&pod),
+            [
+                (None, "sets SELinux user system_u".to_string()),
+                (None, "sets SELinux role system_r".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn unconfined_apparmor_profiles_violate_baseline() {
+        let pod = pod(
+            json!({"appArmorProfile": {"type": "RuntimeDefault"}}),
+            json!({"appArmorProfile": {"type": "Unconfined"}}),
+            json!({}),
+        );
+        assert_eq!(
+            baseline_violations(&pod),
+            [(
+                Some("app".to_string()),
+                "AppArmor profile is Unconfined".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn unconfined_apparmor_annotations_violate_baseline() {
+        let annotation = "container.apparmor.security.beta.kubernetes.io/app";
+        let unconfined = pod(json!({}), json!({}), json!({annotation: "unconfined"}));
+        assert_eq!(
+            baseline_violations(&unconfined),
+            [(
+                Some("app".to_string()),
+                "AppArmor profile annotation is unconfined".to_string()
+            )]
+        );
+
+        let localhost = pod(
+            json!({}),
+            json!({}),
+            json!({annotation: "localhost/deny-write"}),
+        );
+        assert!(baseline_violations(&localhost).is_empty());
+    }
+
+    #[test]
+    fn windows_host_processes_violate_baseline() {
+        let pod = pod(
+            json!({"windowsOptions": {"hostProcess": true}}),
+            json!({"windowsOptions": {"hostProcess": true}}),
+            json!({}),
+        );
+        assert_eq!(
+            baseline_violations(&pod),
+            [
+                (None, "runs as a Windows HostProcess".to_string()),
+                (
+                    Some("app".to_string()),
+                    "runs as a Windows HostProcess".to_string()
+                )
+            ]
+        );
+    }
+}
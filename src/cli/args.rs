@@ -1,7 +1,10 @@
 use crate::cli::Commands;
-use crate::cli::formats::LogFormat;
+use crate::cli::formats::{LogFormat, parse_duration, parse_secret};
+use crate::k8s::{ClientOptions, RateLimit, RetryPolicy};
 use clap::Parser;
+use secrecy::SecretString;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Command line arguments for the Kimspect application
 #[derive(Parser, Debug)]
@@ -16,6 +19,147 @@ pub struct Args {
     #[arg(long = "kubeconfig", global = true)]
     pub kubeconfig: Option<PathBuf>,
 
+    /// Use the ServiceAccount of the pod fakos runs in instead of a kubeconfig (the default
+    /// inside a pod without a kubeconfig)
+    #[arg(
+        long = "in-cluster",
+        global = true,
+        conflicts_with_all = ["kubeconfig", "from_snapshot", "replay"]
+    )]
+    pub in_cluster: bool,
+
+    /// Run commands against a snapshot file (see `fakos snapshot save`) instead of a live cluster
+    #[arg(long = "from-snapshot", global = true, value_name = "FILE")]
+    pub from_snapshot: Option<PathBuf>,
+
+    /// Record all API requests and responses to a new directory, with credentials scrubbed, to
+    /// reproduce issues with --replay
+    #[arg(
+        long = "record",
+        global = true,
+        value_name = "DIR",
+        conflicts_with_all = ["from_snapshot", "replay"]
+    )]
+    pub record: Option<PathBuf>,
+
+    /// Run commands against API traffic recorded with --record instead of a live cluster
+    #[arg(
+        long = "replay",
+        global = true,
+        value_name = "DIR",
+        conflicts_with = "from_snapshot"
+    )]
+    pub replay: Option<PathBuf>,
+
+    /// Username to impersonate for the command, e.g. system:serviceaccount:NAMESPACE:NAME
+    #[arg(long = "as", global = true, value_name = "USER")]
+    pub impersonate: Option<String>,
+
+    /// Group to impersonate for the command, can be repeated (requires --as)
+    #[arg(
+        long = "as-group",
+        global = true,
+        value_name = "GROUP",
+        requires = "impersonate"
+    )]
+    pub impersonate_groups: Vec<String>,
+
+    /// UID to impersonate for the command (requires --as)
+    #[arg(
+        long = "as-uid",
+        global = true,
+        value_name = "UID",
+        requires = "impersonate"
+    )]
+    pub impersonate_uid: Option<String>,
+
+    /// Bearer token for authentication to the API server, instead of the kubeconfig credentials
+    #[arg(long = "token", global = true, value_name = "TOKEN", value_parser = parse_secret)]
+    pub token: Option<SecretString>,
+
+    /// The address and port of the Kubernetes API server
+    #[arg(long = "server", short = 's', global = true, value_name = "URL")]
+    pub server: Option<String>,
+
+    /// Path to a PEM file with the certificate authorities of the API server
+    #[arg(
+        long = "certificate-authority",
+        global = true,
+        value_name = "FILE",
+        conflicts_with = "insecure_skip_tls_verify"
+    )]
+    pub certificate_authority: Option<PathBuf>,
+
+    /// Do not verify the certificate of the API server. This makes the connection insecure
+    #[arg(long = "insecure-skip-tls-verify", global = true)]
+    pub insecure_skip_tls_verify: bool,
+
+    /// Time to wait for each API request before giving up (e.g. 30s, 1m); 0 waits forever
+    #[arg(
+        long = "request-timeout",
+        global = true,
+        value_name = "DURATION",
+        value_parser = parse_duration
+    )]
+    pub request_timeout: Option<Duration>,
+
+    /// Times to retry a read request that failed because the API server was overloaded or
+    /// unreachable; 0 disables retries. The wait between attempts starts at 250ms and doubles up
+    /// to 10s, also when the API server asks for longer with Retry-After; these bounds are fixed
+    #[arg(
+        long = "max-retries",
+        global = true,
+        value_name = "N",
+        default_value_t = RetryPolicy::default().max_retries
+    )]
+    pub max_retries: u32,
+
+    /// Average requests per second to the API server; 0 disables the limit
+    #[arg(
+        long = "qps",
+        global = true,
+        value_name = "QPS",
+        default_value_t = RateLimit::default().qps
+    )]
+    pub qps: f32,
+
+    /// Requests that may be sent at once before --qps applies
+    #[arg(
+        long = "burst",
+        global = true,
+        value_name = "N",
+        default_value_t = RateLimit::default().burst
+    )]
+    pub burst: u32,
+
+    /// Requests to the API server that may be in flight at the same time, until their response
+    /// is read (watches until their response starts); 0 disables the limit
+    #[arg(
+        long = "max-concurrent-requests",
+        global = true,
+        value_name = "N",
+        default_value_t = RateLimit::default().max_concurrent_requests
+    )]
+    pub max_concurrent_requests: usize,
+
+    /// Time to wait for the whole command, including retries, before giving up (e.g. 5m)
+    #[arg(
+        long = "command-timeout",
+        global = true,
+        value_name = "DURATION",
+        value_parser = parse_duration
+    )]
+    pub command_timeout: Option<Duration>,
+
+    /// URL of an HTTP or SOCKS5 proxy to connect to the API server through
+    #[arg(long = "proxy-url", global = true, value_name = "URL")]
+    pub proxy_url: Option<String>,
+
+    /// Skip the check that the API server is reachable and accepts the credentials before
+    /// running the command
+    #[arg(long = "no-preflight", global = true)]
+    pub no_preflight: bool,
+
     /// Enable verbose logging. Use multiple v's for increased verbosity:
     /// -v: WARN level
     /// -vv: INFO level
@@ -49,4 +193,37 @@ impl Args {
             .clone()
             .or_else(|| std::env::var("KUBECONFIG").ok().map(PathBuf::from))
     }
+
+    /// Get the options for connecting to the cluster given by the global flags
+    ///
+    /// # Returns
+    ///
+    /// * `ClientOptions` - The options, for the current context of the kubeconfig
+    pub fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            kubeconfig: self.kubeconfig.clone(),
+            in_cluster: self.in_cluster,
+            context: None,
+            record: self.record.clone(),
+            impersonate: self.impersonate.clone(),
+            impersonate_groups: self.impersonate_groups.clone(),
+            impersonate_uid: self.impersonate_uid.clone(),
+            token: self.token.clone(),
+            server: self.server.clone(),
+            certificate_authority: self.certificate_authority.clone(),
+            insecure_skip_tls_verify: self.insecure_skip_tls_verify,
+            request_timeout: self.request_timeout,
+            retry: RetryPolicy {
+                max_retries: self.max_retries,
+                ..Default::default()
+            },
+            rate_limit: RateLimit {
+                qps: self.qps,
+                burst: self.burst,
+                max_concurrent_requests: self.max_concurrent_requests,
+            },
+            proxy_url: self.proxy_url.clone(),
+            skip_preflight: self.no_preflight,
+        }
+    }
 }
@@ -1,5 +1,5 @@
-use crate::cli::formats::OutputFormat;
-use clap::Subcommand;
+use crate::cli::formats::{DryRunMode, LintOutputFormat, MetadataTarget, OutputFormat};
+use clap::{Args, Subcommand};
 use std::path::PathBuf;
 
 /// CLI command structure for Kimspect
@@ -11,6 +11,337 @@ pub enum Commands {
         #[command(subcommand)]
         resource: GetResources,
     },
+
+    /// Show a detailed report of a single Kubernetes resource
+    Describe {
+        /// The resource type to describe
+        #[command(subcommand)]
+        resource: DescribeResources,
+    },
+
+    /// Show the ownership tree of a resource, from its top-most controller down to all owned objects
+    Tree {
+        /// Resource to show the tree for, as KIND/NAME (e.g. pod/my-pod, deploy/my-app,
+        /// postgrescluster/main)
+        #[arg(value_name = "KIND/NAME")]
+        resource: crate::ResourceRef,
+
+        /// Kubernetes namespace of the resource (defaults to "default")
+        #[arg(short, long, default_value = "default")]
+        namespace: String,
+    },
+
+    /// Show a timeline of events for a resource
+    Events {
+        /// Resource to show events for, as KIND/NAME (e.g. pod/my-pod, deploy/my-app, node/worker-1)
+        /// If not specified, all events in the namespace are shown
+        #[arg(value_name = "KIND/NAME")]
+        resource: Option<crate::ResourceRef>,
+
+        /// Kubernetes namespace to query (defaults to "default")
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Query events across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+
+        /// Also show events of pods owned by the resource (or scheduled on it for nodes)
+        #[arg(long = "include-pods", requires = "resource")]
+        include_pods: bool,
+
+        /// Keep watching for new events after listing the existing ones
+        #[arg(short = 'w', long = "watch")]
+        watch: bool,
+    },
+
+    /// Audit cluster resources against security and hygiene policies
+    Audit {
+        /// The audit to run
+        #[command(subcommand)]
+        check: AuditChecks,
+    },
+
+    /// Check resources against user-defined conventions
+    Lint {
+        /// What to lint
+        #[command(subcommand)]
+        target: LintTargets,
+    },
+
+    /// Compare labels, annotations and environment variables (or taints for nodes) of two objects
+    Diff {
+        /// First object, as KIND/NAME (pod/NAME or node/NAME)
+        #[arg(value_name = "KIND/NAME")]
+        left: crate::ResourceRef,
+
+        /// Second object, as KIND/NAME (pod/NAME or node/NAME)
+        #[arg(value_name = "KIND/NAME")]
+        right: crate::ResourceRef,
+
+        /// Kubernetes namespace of the pods (defaults to "default")
+        #[arg(short, long, default_value = "default")]
+        namespace: String,
+
+        /// Kubernetes namespace of the second pod (defaults to --namespace)
+        #[arg(long = "other-namespace")]
+        other_namespace: Option<String>,
+
+        /// Kubeconfig context to get the second object from (defaults to the current context)
+        #[arg(long = "other-context")]
+        other_context: Option<String>,
+    },
+
+    /// Report pods, nodes and workloads added, removed or changed between two snapshots
+    Drift {
+        /// Snapshot of the old state
+        #[arg(value_name = "OLD")]
+        old: std::path::PathBuf,
+
+        /// Snapshot of the new state (defaults to the live cluster)
+        #[arg(value_name = "NEW")]
+        new: Option<std::path::PathBuf>,
+
+        /// Kubernetes namespace to compare pods and workloads in (defaults to "default")
+        #[arg(short, long, default_value = "default")]
+        namespace: String,
+
+        /// Compare pods and workloads across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+    },
+
+    /// Cordon, uncordon or drain nodes
+    Node {
+        /// The action to perform
+        #[command(subcommand)]
+        action: NodeActions,
+    },
+
+    /// Check what the current user (or the user given with --as) is allowed to do
+    Auth {
+        /// The auth action to perform
+        #[command(subcommand)]
+        action: AuthActions,
+    },
+
+    /// Show information about the cluster and the current user
+    Cluster {
+        /// The cluster action to perform
+        #[command(subcommand)]
+        action: ClusterActions,
+    },
+
+    /// Save cluster state to a file for offline use with --from-snapshot
+    Snapshot {
+        /// The snapshot action to perform
+        #[command(subcommand)]
+        action: SnapshotActions,
+    },
+
+    /// Add, overwrite or remove labels on all pods or nodes matching a selector
+    Label {
+        #[command(flatten)]
+        update: MetadataUpdateArgs,
+    },
+
+    /// Add, overwrite or remove annotations on all pods or nodes matching a selector
+    Annotate {
+        #[command(flatten)]
+        update: MetadataUpdateArgs,
+    },
+}
+
+/// Actions that can be performed on nodes
+#[derive(Subcommand, Debug)]
+pub enum NodeActions {
+    /// Mark nodes as unschedulable
+    Cordon {
+        #[command(flatten)]
+        nodes: NodeSelection,
+
+        /// Only show the nodes that would be changed (client) or also validate the change with
+        /// the API server (server)
+        #[arg(
+            long = "dry-run",
+            default_value = "none",
+            num_args = 0..=1,
+            default_missing_value = "client"
+        )]
+        dry_run: DryRunMode,
+    },
+
+    /// Mark nodes as schedulable
+    Uncordon {
+        #[command(flatten)]
+        nodes: NodeSelection,
+
+        /// Only show the nodes that would be changed (client) or also validate the change with
+        /// the API server (server)
+        #[arg(
+            long = "dry-run",
+            default_value = "none",
+            num_args = 0..=1,
+            default_missing_value = "client"
+        )]
+        dry_run: DryRunMode,
+    },
+
+    /// Cordon nodes and evict their pods, respecting PodDisruptionBudgets
+    Drain {
+        #[command(flatten)]
+        nodes: NodeSelection,
+
+        /// Seconds each pod is given to terminate (defaults to the pod's own grace period)
+        #[arg(long = "grace-period", value_name = "SECONDS")]
+        grace_period: Option<u32>,
+
+        /// Maximum time to wait for the pods of each node to be evicted (e.g. 90s, 5m)
+        #[arg(long = "timeout", default_value = "5m", value_parser = crate::cli::formats::parse_duration)]
+        timeout: std::time::Duration,
+
+        /// Also evict pods not managed by a controller (they will not be recreated)
+        #[arg(long = "force")]
+        force: bool,
+
+        /// Also evict pods using emptyDir volumes (their data is lost)
+        #[arg(long = "delete-emptydir-data")]
+        delete_emptydir_data: bool,
+
+        /// Only show the pods that would be evicted (client) or also validate the evictions with
+        /// the API server (server)
+        #[arg(
+            long = "dry-run",
+            default_value = "none",
+            num_args = 0..=1,
+            default_missing_value = "client"
+        )]
+        dry_run: DryRunMode,
+    },
+}
+
+/// Actions on authorization
+#[derive(Subcommand, Debug)]
+pub enum AuthActions {
+    /// Show which of the permissions needed by fakos commands are allowed, per namespace
+    #[command(name = "can-i")]
+    CanI {
+        /// Kubernetes namespaces to check, comma separated (defaults to "default")
+        #[arg(
+            short,
+            long = "namespace",
+            value_delimiter = ',',
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespaces: Vec<String>,
+
+        /// Check permissions across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespaces")]
+        all_namespaces: bool,
+    },
+}
+
+/// Actions on the cluster itself
+#[derive(Subcommand, Debug)]
+pub enum ClusterActions {
+    /// Show the server version, endpoint, authentication method, current identity and API
+    /// groups of the cluster
+    Info,
+}
+
+/// Actions on cluster snapshots
+#[derive(Subcommand, Debug)]
+pub enum SnapshotActions {
+    /// Save pods, nodes, namespaces, events, workloads, services, ConfigMaps and PVCs to a file
+    /// (Secrets are never saved)
+    Save {
+        /// Path of the snapshot file to write
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Kubernetes namespace to save (defaults to "default")
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Save objects across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+    },
+}
+
+/// Nodes to act on, by name or label selector
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct NodeSelection {
+    /// Name of the node
+    #[arg(value_name = "NODE")]
+    pub node_name: Option<String>,
+
+    /// Label selector to select nodes by (e.g. pool=gpu)
+    #[arg(short = 'l', long = "selector")]
+    pub selector: Option<String>,
+}
+
+/// Arguments shared by the label and annotate commands
+#[derive(Args, Debug)]
+pub struct MetadataUpdateArgs {
+    /// Resource type to update (pods or nodes)
+    #[arg(value_name = "RESOURCE")]
+    pub resource: MetadataTarget,
+
+    /// Changes to apply: KEY=VALUE to add or overwrite a key, KEY- to remove it
+    #[arg(value_name = "KEY=VALUE|KEY-", required = true)]
+    pub changes: Vec<crate::MetadataChange>,
+
+    /// Label selector to filter objects by (e.g. pool=gpu,!spot). All objects are updated if
+    /// not specified
+    #[arg(short = 'l', long = "selector")]
+    pub selector: Option<String>,
+
+    /// Kubernetes namespace of the pods (defaults to "default", ignored for nodes)
+    #[arg(
+        short,
+        long,
+        default_value = "default",
+        conflicts_with = "all_namespaces"
+    )]
+    pub namespace: String,
+
+    /// Update pods across all namespaces
+    #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+    pub all_namespaces: bool,
+
+    /// Allow changing the value of keys that are already set
+    #[arg(long = "overwrite")]
+    pub overwrite: bool,
+
+    /// Only show the changes (client) or also validate them with the API server (server)
+    #[arg(
+        long = "dry-run",
+        default_value = "none",
+        num_args = 0..=1,
+        default_missing_value = "client"
+    )]
+    pub dry_run: DryRunMode,
+
+    /// Ask for confirmation before updating more than this many objects
+    #[arg(long = "confirm-threshold", default_value = "10")]
+    pub confirm_threshold: usize,
+
+    /// Do not ask for confirmation
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
 }
 
 /// Resource types that can be queried in the Kubernetes cluster
@@ -56,6 +387,50 @@ pub enum GetResources {
         #[arg(long = "env-vars", num_args(0..=1), default_missing_value = ".*")]
         env_vars: Option<crate::EnvVarsFilter>,
 
+        /// Display recent warning events for each pod
+        #[arg(long = "events")]
+        events: bool,
+
+        /// Group pods into sections by node, namespace, owner or label:<key>
+        /// Grouping by node also reports pod counts and resource requests against node capacity
+        #[arg(long = "group-by", value_name = "GROUP")]
+        group_by: Option<crate::GroupBy>,
+
+        /// Read pods from manifest files instead of the cluster: a YAML or JSON file, a
+        /// directory, or - for stdin. Deployments, StatefulSets, DaemonSets and Jobs are shown
+        /// as the pod their template describes
+        #[arg(
+            short = 'f',
+            long = "filename",
+            value_name = "PATH",
+            conflicts_with_all = ["node", "events"]
+        )]
+        filename: Option<PathBuf>,
+
+        /// Path to kubeconfig file (default: ~/.kube/config)
+        #[arg(long = "kubeconfig")]
+        kubeconfig: Option<PathBuf>,
+    },
+
+    /// List container images used by pods, with resolved digests and pod counts
+    Images {
+        /// Kubernetes namespace to query (defaults to "default")
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Query images across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+
+        /// Output format (default: normal, wide: shows additional columns)
+        #[arg(short = 'o', long = "output", default_value = "normal")]
+        output: OutputFormat,
+
         /// Path to kubeconfig file (default: ~/.kube/config)
         #[arg(long = "kubeconfig")]
         kubeconfig: Option<PathBuf>,
@@ -79,12 +454,107 @@ pub enum GetResources {
         #[arg(long = "annotations")]
         annotations: bool,
 
+        /// Display recent warning events for each node
+        #[arg(long = "events")]
+        events: bool,
+
         /// Path to kubeconfig file (default: ~/.kube/config)
         #[arg(long = "kubeconfig")]
         kubeconfig: Option<PathBuf>,
     },
 }
 
+/// Resource types that can be described in detail
+#[derive(Subcommand, Debug)]
+pub enum DescribeResources {
+    /// Describe a pod
+    Pod {
+        /// Name of the pod to describe
+        #[arg(value_name = "POD")]
+        pod_name: String,
+
+        /// Kubernetes namespace of the pod (defaults to "default")
+        #[arg(short, long, default_value = "default")]
+        namespace: String,
+    },
+
+    /// Describe a node
+    Node {
+        /// Name of the node to describe
+        #[arg(value_name = "NODE")]
+        node_name: String,
+    },
+}
+
+/// Audits that can be run against the Kubernetes cluster
+#[derive(Subcommand, Debug)]
+pub enum AuditChecks {
+    /// Evaluate pods against the Pod Security Standards (privileged, baseline, restricted)
+    Security {
+        /// Kubernetes namespace to audit (defaults to "default")
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Audit pods across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+    },
+
+    /// Find likely credentials in literal environment variable values (masked in the report)
+    EnvSecrets {
+        /// Kubernetes namespace to audit (defaults to "default")
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Audit pods across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+    },
+}
+
+/// Targets that can be linted
+#[derive(Subcommand, Debug)]
+pub enum LintTargets {
+    /// Check labels and annotations against a policy file
+    Labels {
+        /// Path to the YAML policy file
+        #[arg(long = "policy", value_name = "FILE")]
+        policy: PathBuf,
+
+        /// Kinds to lint, comma separated (e.g. pods,nodes,deploy,postgrescluster).
+        /// Defaults to the kinds named in the policy, or pods and nodes
+        #[arg(short = 'k', long = "kind", value_delimiter = ',')]
+        kinds: Vec<String>,
+
+        /// Kubernetes namespace to lint (defaults to "default", ignored for cluster-scoped kinds)
+        #[arg(
+            short,
+            long,
+            default_value = "default",
+            conflicts_with = "all_namespaces"
+        )]
+        namespace: String,
+
+        /// Lint objects across all namespaces
+        #[arg(short = 'A', long = "all-namespaces", conflicts_with = "namespace")]
+        all_namespaces: bool,
+
+        /// Output format (table, json or sarif)
+        #[arg(short = 'o', long = "output", default_value = "table")]
+        output: LintOutputFormat,
+    },
+}
+
 impl GetResources {
     /// Get the kubeconfig path for this command
     ///
@@ -94,6 +564,7 @@ impl GetResources {
     pub fn get_kubeconfig_path(&self) -> Option<PathBuf> {
         match self {
             GetResources::Pods { kubeconfig, .. } => kubeconfig.clone(),
+            GetResources::Images { kubeconfig, .. } => kubeconfig.clone(),
             GetResources::Nodes { kubeconfig, .. } => kubeconfig.clone(),
         }
     }
@@ -106,6 +577,7 @@ impl GetResources {
     pub fn get_namespace(&self) -> &str {
         match self {
             GetResources::Pods { namespace, .. } => namespace,
+            GetResources::Images { namespace, .. } => namespace,
             GetResources::Nodes { .. } => "default",
         }
     }
@@ -118,6 +590,7 @@ impl GetResources {
     pub fn is_all_namespaces(&self) -> bool {
         match self {
             GetResources::Pods { all_namespaces, .. } => *all_namespaces,
+            GetResources::Images { all_namespaces, .. } => *all_namespaces,
             GetResources::Nodes { .. } => false,
         }
     }
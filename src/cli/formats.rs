@@ -47,3 +47,157 @@ impl OutputFormat {
         matches!(self, OutputFormat::Wide)
     }
 }
+
+/// Output format options for lint reports
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum LintOutputFormat {
+    /// Human-readable table
+    Table,
+    /// JSON array of violations
+    Json,
+    /// SARIF 2.1.0 log, for code scanning integrations
+    Sarif,
+}
+
+impl fmt::Display for LintOutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintOutputFormat::Table => write!(f, "table"),
+            LintOutputFormat::Json => write!(f, "json"),
+            LintOutputFormat::Sarif => write!(f, "sarif"),
+        }
+    }
+}
+
+/// Dry-run modes for commands that modify resources
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum DryRunMode {
+    /// Apply the changes
+    None,
+    /// Only show the changes that would be made
+    Client,
+    /// Show the changes and have the API server validate them without persisting
+    Server,
+}
+
+impl fmt::Display for DryRunMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DryRunMode::None => write!(f, "none"),
+            DryRunMode::Client => write!(f, "client"),
+            DryRunMode::Server => write!(f, "server"),
+        }
+    }
+}
+
+/// Resource types whose labels and annotations can be updated in bulk
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum MetadataTarget {
+    /// Pods
+    #[value(alias = "pod", alias = "po")]
+    Pods,
+    /// Nodes
+    #[value(alias = "node", alias = "no")]
+    Nodes,
+}
+
+impl MetadataTarget {
+    /// Get the kind of the resources
+    ///
+    /// # Returns
+    ///
+    /// * `&str` - The Kubernetes kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MetadataTarget::Pods => "Pod",
+            MetadataTarget::Nodes => "Node",
+        }
+    }
+}
+
+/// Parse a duration given as seconds or with a unit suffix (e.g. `90`, `30s`, `5m`, `1h`)
+///
+/// # Arguments
+///
+/// * `value` - The duration to parse
+///
+/// # Returns
+///
+/// * `Result<Duration, String>` - The parsed duration or an error message
+pub fn parse_duration(value: &str) -> Result<std::time::Duration, String> {
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => value.split_at(i),
+        None => (value, "s"),
+    };
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", value))?;
+    let seconds = match unit {
+        "s" => number,
+        "m" => number * 60,
+        "h" => number * 3600,
+        _ => {
+            return Err(format!(
+                "invalid duration unit in '{}' (use s, m or h)",
+                value
+            ));
+        }
+    };
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+/// Parse a secret, such as a token, that must not show up in debug output
+///
+/// # Arguments
+///
+/// * `value` - The secret
+///
+/// # Returns
+///
+/// * `Result<SecretString, String>` - The secret, which is never invalid
+pub fn parse_secret(value: &str) -> Result<secrecy::SecretString, String> {
+    Ok(secrecy::SecretString::from(value))
+}
+
+/// Grouping options for pod listings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Group pods by the node they are scheduled on
+    Node,
+    /// Group pods by namespace
+    Namespace,
+    /// Group pods by the value of a label
+    Label(String),
+    /// Group pods by the workload controlling them
+    Owner,
+}
+
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "node" => Ok(GroupBy::Node),
+            "namespace" => Ok(GroupBy::Namespace),
+            "owner" => Ok(GroupBy::Owner),
+            _ => match s.strip_prefix("label:") {
+                Some(key) if !key.is_empty() => Ok(GroupBy::Label(key.to_string())),
+                _ => Err(format!(
+                    "invalid grouping '{}', expected one of: node, namespace, owner, label:<key>",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupBy::Node => write!(f, "node"),
+            GroupBy::Namespace => write!(f, "namespace"),
+            GroupBy::Label(key) => write!(f, "label:{}", key),
+            GroupBy::Owner => write!(f, "owner"),
+        }
+    }
+}
@@ -3,5 +3,8 @@ mod commands;
 mod formats;
 
 pub use args::Args;
-pub use commands::{Commands, GetResources};
-pub use formats::{LogFormat, OutputFormat};
+pub use commands::{
+    AuditChecks, AuthActions, ClusterActions, Commands, DescribeResources, GetResources,
+    LintTargets, MetadataUpdateArgs, NodeActions, NodeSelection, SnapshotActions,
+};
+pub use formats::{DryRunMode, GroupBy, LintOutputFormat, LogFormat, MetadataTarget, OutputFormat};
@@ -0,0 +1,281 @@
+//! Drift reports comparing the state of a cluster at two points in time
+
+use crate::k8s::{ClusterSource, ContainerKind, FarosNode, FarosPod, FarosWorkload, REDACTED};
+use crate::utils::{DiffEntry, diff_maps};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The compared fields of an object, as named sections of key-value pairs
+#[derive(Debug, Clone)]
+pub struct DriftObject {
+    /// Kind of the object (Pod, Node, Deployment, ...)
+    pub kind: String,
+    /// Namespace of the object, or None for cluster-scoped objects
+    pub namespace: Option<String>,
+    /// Name of the object
+    pub name: String,
+    /// Compared fields (e.g. `Labels`, `Images`, `Env (container app)`)
+    pub sections: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+/// How an object changed between two inventories
+#[derive(Debug, Clone)]
+pub enum DriftChange {
+    /// The object only exists in the new inventory
+    Added,
+    /// The object only exists in the old inventory
+    Removed,
+    /// The object exists in both inventories with different fields
+    Changed(Vec<FieldChange>),
+}
+
+/// A changed key in one section of an object
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    /// Section the key belongs to (e.g. `Labels`)
+    pub section: String,
+    /// The key with its old and new values
+    pub entry: DiffEntry,
+}
+
+/// Drift of a single object
+#[derive(Debug, Clone)]
+pub struct ObjectDrift {
+    /// Kind of the object
+    pub kind: String,
+    /// Namespace of the object, or None for cluster-scoped objects
+    pub namespace: Option<String>,
+    /// Name of the object
+    pub name: String,
+    /// How the object changed
+    pub change: DriftChange,
+}
+
+fn container_sections(
+    sections: &mut BTreeMap<String, BTreeMap<String, String>>,
+    images: BTreeMap<String, String>,
+    container_env_vars: &BTreeMap<String, BTreeMap<String, String>>,
+) {
+    sections.insert("Images".to_string(), images);
+    for (container, env) in container_env_vars {
+        sections.insert(format!("Env (container {})", container), env.clone());
+    }
+}
+
+impl From<&FarosPod> for DriftObject {
+    fn from(pod: &FarosPod) -> Self {
+        let mut sections = BTreeMap::from([
+            ("Labels".to_string(), pod.labels.clone()),
+            ("Annotations".to_string(), pod.annotations.clone()),
+        ]);
+        // Ephemeral containers are added by `kubectl debug` and are not part of the desired state
+        let images = pod
+            .containers
+            .iter()
+            .filter(|c| c.kind != ContainerKind::Ephemeral)
+            .map(|c| (c.name.clone(), c.image.clone()))
+            .collect();
+        container_sections(&mut sections, images, &pod.container_env_vars);
+        Self {
+            kind: "Pod".to_string(),
+            namespace: Some(pod.namespace.clone()),
+            name: pod.name.clone(),
+            sections,
+        }
+    }
+}
+
+impl From<&FarosNode> for DriftObject {
+    fn from(node: &FarosNode) -> Self {
+        let sections = BTreeMap::from([
+            ("Labels".to_string(), node.labels.clone()),
+            ("Annotations".to_string(), node.annotations.clone()),
+            ("Taints".to_string(), node.taints_by_key()),
+            (
+                "System Info".to_string(),
+                node.system_info.iter().cloned().collect(),
+            ),
+        ]);
+        Self {
+            kind: "Node".to_string(),
+            namespace: None,
+            name: node.name.clone(),
+            sections,
+        }
+    }
+}
+
+impl From<&FarosWorkload> for DriftObject {
+    fn from(workload: &FarosWorkload) -> Self {
+        let mut sections = BTreeMap::from([
+            ("Labels".to_string(), workload.labels.clone()),
+            ("Annotations".to_string(), workload.annotations.clone()),
+        ]);
+        container_sections(
+            &mut sections,
+            workload.images.clone(),
+            &workload.container_env_vars,
+        );
+        Self {
+            kind: workload.kind.clone(),
+            namespace: Some(workload.namespace.clone()),
+            name: workload.name.clone(),
+            sections,
+        }
+    }
+}
+
+/// Collect the nodes, workloads and pods to compare from a cluster or snapshot
+///
+/// # Arguments
+///
+/// * `client` - The client to read from
+/// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
+/// * `all_namespaces` - If true, collect workloads and pods across all namespaces
+///
+/// # Returns
+///
+/// * `Result<Vec<DriftObject>>` - The collected objects
+pub async fn collect_inventory(
+    client: &dyn ClusterSource,
+    namespace: &str,
+    all_namespaces: bool,
+) -> Result<Vec<DriftObject>> {
+    let mut objects: Vec<DriftObject> = client
+        .get_nodes(None)
+        .await?
+        .iter()
+        .map(DriftObject::from)
+        .collect();
+    objects.extend(
+        client
+            .get_workloads(namespace, all_namespaces)
+            .await?
+            .iter()
+            .map(DriftObject::from),
+    );
+    objects.extend(
+        client
+            .get_pods(namespace, all_namespaces, None, None)
+            .await?
+            .iter()
+            .map(DriftObject::from),
+    );
+    Ok(objects)
+}
+
+/// Compare two inventories object by object
+///
+/// Objects are matched by kind, namespace and name. Unchanged objects are omitted. Values that a
+/// snapshot scrubbed as credentials are not compared, so only added or removed keys are reported
+/// for them.
+///
+/// # Arguments
+///
+/// * `old` - The objects before the change
+/// * `new` - The objects after the change
+///
+/// # Returns
+///
+/// * `Vec<ObjectDrift>` - The added, removed and changed objects, sorted by kind, namespace and
+///   name
+pub fn compare_inventories(old: &[DriftObject], new: &[DriftObject]) -> Vec<ObjectDrift> {
+    let index = |objects: &[DriftObject]| -> BTreeMap<(String, Option<String>, String), usize> {
+        objects
+            .iter()
+            .enumerate()
+            .map(|(i, o)| ((o.kind.clone(), o.namespace.clone(), o.name.clone()), i))
+            .collect()
+    };
+    let (old_index, new_index) = (index(old), index(new));
+    let keys: BTreeSet<&(String, Option<String>, String)> =
+        old_index.keys().chain(new_index.keys()).collect();
+
+    let empty = BTreeMap::new();
+    let mut drifts = Vec::new();
+    for key in keys {
+        let change = match (old_index.get(key), new_index.get(key)) {
+            (Some(_), None) => DriftChange::Removed,
+            (None, Some(_)) => DriftChange::Added,
+            (Some(&o), Some(&n)) => {
+                let (old_sections, new_sections) = (&old[o].sections, &new[n].sections);
+                let titles: BTreeSet<&String> =
+                    old_sections.keys().chain(new_sections.keys()).collect();
+                let changes: Vec<FieldChange> = titles
+                    .into_iter()
+                    .flat_map(|title| {
+                        let (entries, _) = diff_maps(
+                            old_sections.get(title).unwrap_or(&empty),
+                            new_sections.get(title).unwrap_or(&empty),
+                        );
+                        entries
+                            .into_iter()
+                            .filter(|entry| !is_scrubbed(entry))
+                            .map(|entry| FieldChange {
+                                section: title.clone(),
+                                entry,
+                            })
+                    })
+                    .collect();
+                if changes.is_empty() {
+                    continue;
+                }
+                DriftChange::Changed(changes)
+            }
+            (None, None) => continue,
+        };
+        let (kind, namespace, name) = key.clone();
+        drifts.push(ObjectDrift {
+            kind,
+            namespace,
+            name,
+            change,
+        });
+    }
+    drifts
+}
+
+/// Whether a changed value was scrubbed on either side, so the change cannot be judged
+fn is_scrubbed(entry: &DiffEntry) -> bool {
+    match (&entry.left, &entry.right) {
+        (Some(left), Some(right)) => left.contains(REDACTED) || right.contains(REDACTED),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pod(env: &[(&str, &str)]) -> DriftObject {
+        let env = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        DriftObject {
+            kind: "Pod".to_string(),
+            namespace: Some("default".to_string()),
+            name: "api-0".to_string(),
+            sections: BTreeMap::from([("Env (container api)".to_string(), env)]),
+        }
+    }
+
+    #[test]
+    fn scrubbed_values_are_not_compared() {
+        let old = pod(&[("DB_PASSWORD", REDACTED), ("LOG_LEVEL", "info")]);
+        let new = pod(&[("DB_PASSWORD", "hunter2"), ("LOG_LEVEL", "info")]);
+        assert!(compare_inventories(&[old], &[new]).is_empty());
+    }
+
+    #[test]
+    fn scrubbed_keys_are_reported_when_added_or_removed() {
+        let old = pod(&[("DB_PASSWORD", REDACTED), ("LOG_LEVEL", "info")]);
+        let new = pod(&[("LOG_LEVEL", "debug")]);
+        let drifts = compare_inventories(&[old], &[new]);
+        let DriftChange::Changed(changes) = &drifts[0].change else {
+            panic!("expected a changed pod, got {:?}", drifts);
+        };
+        let keys: Vec<&str> = changes.iter().map(|c| c.entry.key.as_str()).collect();
+        assert_eq!(keys, ["DB_PASSWORD", "LOG_LEVEL"]);
+    }
+}
@@ -0,0 +1,329 @@
+use super::{ClusterSource, K8sClient};
+use anyhow::{Context, Result};
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use kube::Api;
+use kube::api::PostParams;
+use tracing::{debug, instrument};
+
+/// A permission that fakos needs for some of its commands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FarosPermission {
+    /// The verb (e.g. list, patch)
+    pub verb: &'static str,
+    /// The API group of the resource, empty for the core group
+    pub group: &'static str,
+    /// The resource (e.g. pods)
+    pub resource: &'static str,
+    /// The subresource (e.g. eviction), if any
+    pub subresource: Option<&'static str>,
+    /// Whether the resource is cluster-scoped
+    pub cluster_scoped: bool,
+    /// The commands that need the permission
+    pub commands: &'static [&'static str],
+}
+
+impl std::fmt::Display for FarosPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.verb, self.resource)?;
+        if let Some(subresource) = self.subresource {
+            write!(f, "/{}", subresource)?;
+        }
+        if !self.group.is_empty() {
+            write!(f, ".{}", self.group)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build a permission of the table of required permissions
+const fn permission(
+    verb: &'static str,
+    group: &'static str,
+    resource: &'static str,
+    cluster_scoped: bool,
+    commands: &'static [&'static str],
+) -> FarosPermission {
+    FarosPermission {
+        verb,
+        group,
+        resource,
+        subresource: None,
+        cluster_scoped,
+        commands,
+    }
+}
+
+/// The permissions needed by the fakos commands, with the commands that need them
+///
+/// Derived from the API requests each command sends: owners are read with `get` when resolving
+/// owner chains (`describe pod`, `tree`), and ReplicaSets and Jobs are listed to find the pods of
+/// a workload (`events --include-pods`). `--events` lists warnings and `--group-by node` lists
+/// nodes next to the resources `get` shows, and `drift` lists what it compares.
+const REQUIRED_PERMISSIONS: &[FarosPermission] = &[
+    permission(
+        "list",
+        "",
+        "pods",
+        false,
+        &[
+            "get pods",
+            "get images",
+            "describe pod",
+            "tree",
+            "events",
+            "audit",
+            "lint",
+            "diff",
+            "label",
+            "annotate",
+            "node drain",
+            "snapshot",
+            "drift",
+        ],
+    ),
+    permission("get", "", "pods", false, &["tree", "node drain"]),
+    permission("patch", "", "pods", false, &["label", "annotate"]),
+    FarosPermission {
+        subresource: Some("eviction"),
+        ..permission("create", "", "pods", false, &["node drain"])
+    },
+    permission("get", "", "nodes", true, &["node"]),
+    permission(
+        "list",
+        "",
+        "nodes",
+        true,
+        &[
+            "get nodes",
+            "get pods --group-by node",
+            "describe node",
+            "node",
+            "lint",
+            "diff",
+            "label",
+            "annotate",
+            "snapshot",
+            "drift",
+        ],
+    ),
+    permission("patch", "", "nodes", true, &["node", "label", "annotate"]),
+    permission("get", "", "namespaces", true, &["audit"]),
+    permission("list", "", "namespaces", true, &["audit -A", "snapshot"]),
+    permission(
+        "list",
+        "events.k8s.io",
+        "events",
+        false,
+        &[
+            "get pods --events",
+            "get nodes --events",
+            "describe",
+            "events",
+            "snapshot",
+        ],
+    ),
+    permission(
+        "watch",
+        "events.k8s.io",
+        "events",
+        false,
+        &["events --watch"],
+    ),
+    permission("get", "", "configmaps", false, &["describe pod"]),
+    permission(
+        "get",
+        "apps",
+        "replicasets",
+        false,
+        &["describe pod", "tree"],
+    ),
+    permission(
+        "get",
+        "apps",
+        "deployments",
+        false,
+        &["describe pod", "tree"],
+    ),
+    permission(
+        "get",
+        "apps",
+        "statefulsets",
+        false,
+        &["describe pod", "tree"],
+    ),
+    permission(
+        "get",
+        "apps",
+        "daemonsets",
+        false,
+        &["describe pod", "tree"],
+    ),
+    permission("get", "batch", "jobs", false, &["describe pod", "tree"]),
+    permission("get", "batch", "cronjobs", false, &["describe pod", "tree"]),
+    permission(
+        "list",
+        "apps",
+        "deployments",
+        false,
+        &["tree", "lint", "snapshot", "drift"],
+    ),
+    permission(
+        "list",
+        "apps",
+        "statefulsets",
+        false,
+        &["tree", "lint", "snapshot", "drift"],
+    ),
+    permission(
+        "list",
+        "apps",
+        "daemonsets",
+        false,
+        &["tree", "lint", "snapshot", "drift"],
+    ),
+    permission(
+        "list",
+        "apps",
+        "replicasets",
+        false,
+        &["tree", "events", "snapshot"],
+    ),
+    permission(
+        "list",
+        "batch",
+        "cronjobs",
+        false,
+        &["tree", "lint", "snapshot", "drift"],
+    ),
+    permission(
+        "list",
+        "batch",
+        "jobs",
+        false,
+        &["tree", "events", "snapshot"],
+    ),
+    permission("list", "", "services", false, &["tree", "snapshot"]),
+    permission("list", "", "configmaps", false, &["tree", "snapshot"]),
+    permission(
+        "list",
+        "",
+        "persistentvolumeclaims",
+        false,
+        &["tree", "snapshot"],
+    ),
+];
+
+/// Get the permissions needed by the fakos commands
+///
+/// # Returns
+///
+/// * `&[FarosPermission]` - The permissions, with the commands that need each of them
+pub fn required_permissions() -> &'static [FarosPermission] {
+    REQUIRED_PERMISSIONS
+}
+
+/// Represents whether the current user has a permission in a namespace
+#[derive(Debug, Clone)]
+pub struct FarosAccess {
+    /// The permission that was checked
+    pub permission: FarosPermission,
+    /// The namespace the permission was checked in, or None for all namespaces and
+    /// cluster-scoped resources
+    pub namespace: Option<String>,
+    /// Whether the permission is granted
+    pub allowed: bool,
+    /// Why the permission is granted or denied, if the authorizer reported it
+    pub reason: Option<String>,
+}
+
+impl K8sClient {
+    /// Check whether the current user has a permission, with a SelfSubjectAccessReview
+    ///
+    /// # Arguments
+    ///
+    /// * `permission` - The permission to check
+    /// * `namespace` - The namespace to check the permission in, or None for all namespaces
+    ///   (ignored for cluster-scoped resources)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FarosAccess>` - Whether the permission is granted
+    #[instrument(skip(self, permission), fields(permission = %permission), level = "debug")]
+    pub async fn check_access(
+        &self,
+        permission: &FarosPermission,
+        namespace: Option<&str>,
+    ) -> Result<FarosAccess> {
+        let namespace = namespace.filter(|_| !permission.cluster_scoped);
+        let review = SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(ResourceAttributes {
+                    verb: Some(permission.verb.to_string()),
+                    group: Some(permission.group.to_string()),
+                    resource: Some(permission.resource.to_string()),
+                    subresource: permission.subresource.map(str::to_string),
+                    namespace: namespace.map(str::to_string),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let api: Api<SelfSubjectAccessReview> = Api::all(self.client.clone());
+        let status = api
+            .create(&PostParams::default(), &review)
+            .await
+            .with_context(|| format!("Failed to check permission to {}", permission))?
+            .status
+            .unwrap_or_default();
+        debug!(allowed = status.allowed, reason = ?status.reason, "Reviewed access");
+
+        Ok(FarosAccess {
+            permission: permission.clone(),
+            namespace: namespace.map(str::to_string),
+            allowed: status.allowed,
+            reason: status
+                .reason
+                .or(status.evaluation_error)
+                .filter(|r| !r.is_empty()),
+        })
+    }
+}
+
+/// Check all permissions needed by the fakos commands
+///
+/// Cluster-scoped permissions are checked once; namespaced permissions are checked in each
+/// namespace.
+///
+/// # Arguments
+///
+/// * `source` - The cluster to check the permissions of the current user in
+/// * `namespaces` - The namespaces to check, with None for all namespaces
+///
+/// # Returns
+///
+/// * `Result<Vec<FarosAccess>>` - The result of each check, in the order of
+///   [`required_permissions`]
+pub async fn review_access(
+    source: &dyn ClusterSource,
+    namespaces: &[Option<String>],
+) -> Result<Vec<FarosAccess>> {
+    let checks = required_permissions().iter().flat_map(|permission| {
+        let namespaces = if permission.cluster_scoped {
+            &[None][..]
+        } else {
+            namespaces
+        };
+        namespaces
+            .iter()
+            .map(move |namespace| source.check_access(permission, namespace.as_deref()))
+    });
+
+    futures::future::join_all(checks)
+        .await
+        .into_iter()
+        .collect()
+}
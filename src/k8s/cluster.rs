@@ -0,0 +1,180 @@
+use super::options::SERVICE_ACCOUNT_TOKEN;
+use super::{K8sClient, K8sError};
+use anyhow::{Context, Result};
+use k8s_openapi::api::authentication::v1::SelfSubjectReview;
+use k8s_openapi::api::authorization::v1::{SelfSubjectRulesReview, SelfSubjectRulesReviewSpec};
+use kube::api::PostParams;
+use kube::config::AuthInfo;
+use kube::{Api, Config};
+use tracing::{debug, instrument, warn};
+
+/// How a client connects to its cluster, as configured in the kubeconfig
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectionInfo {
+    /// URL of the API server
+    pub(crate) endpoint: String,
+    /// How the client authenticates (e.g. bearer token, exec plugin)
+    pub(crate) auth_method: String,
+}
+
+impl ConnectionInfo {
+    /// Describe the connection of a client configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration the client is created from
+    ///
+    /// # Returns
+    ///
+    /// * `ConnectionInfo` - The endpoint and authentication method of the configuration
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self {
+            endpoint: config.cluster_url.to_string(),
+            auth_method: auth_method(&config.auth_info),
+        }
+    }
+}
+
+/// Name the way a kubeconfig user authenticates
+fn auth_method(auth: &AuthInfo) -> String {
+    if let Some(exec) = &auth.exec {
+        format!(
+            "exec plugin ({})",
+            exec.command.as_deref().unwrap_or("<none>")
+        )
+    } else if let Some(provider) = &auth.auth_provider {
+        format!("auth provider ({})", provider.name)
+    } else if auth.token.is_none() && auth.token_file.as_deref() == Some(SERVICE_ACCOUNT_TOKEN) {
+        "in-cluster service account".to_string()
+    } else if auth.token.is_some() || auth.token_file.is_some() {
+        "bearer token".to_string()
+    } else if auth.client_certificate.is_some() || auth.client_certificate_data.is_some() {
+        "client certificate".to_string()
+    } else if auth.username.is_some() {
+        "basic auth".to_string()
+    } else {
+        "none".to_string()
+    }
+}
+
+/// Represents the version, identity and API groups of a cluster, as seen by the current user
+#[derive(Debug, Clone, Default)]
+pub struct FarosClusterInfo {
+    /// Version of the API server (e.g. v1.32.0)
+    pub server_version: Option<String>,
+    /// Platform of the API server (e.g. linux/amd64)
+    pub platform: Option<String>,
+    /// URL of the API server, or None if the client is not connected to a cluster
+    pub endpoint: Option<String>,
+    /// How the client authenticates, or None if the client is not connected to a cluster
+    pub auth_method: Option<String>,
+    /// Name of the current user, if the API server could report it
+    pub username: Option<String>,
+    /// Groups of the current user
+    pub groups: Vec<String>,
+    /// Group versions served by the API server (e.g. v1, apps/v1), preferred versions only
+    pub api_groups: Vec<String>,
+}
+
+impl K8sClient {
+    /// Check that the API server is reachable and accepts the credentials
+    ///
+    /// Reads the server version, then reviews the permissions of the current user in the default
+    /// namespace of the kubeconfig. Unlike listing resources, a rules review needs no RBAC
+    /// permission, so only rejected credentials fail the check.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the cluster is usable, or the error of the failed check
+    #[instrument(skip(self))]
+    pub async fn preflight(&self) -> Result<()> {
+        debug!("Checking cluster connectivity");
+        let version = self
+            .client
+            .apiserver_version()
+            .await
+            .context("Failed to reach the Kubernetes API server")?;
+        debug!(version = %version.git_version, "API server is reachable");
+
+        let namespace = self.client.default_namespace().to_string();
+        let review = SelfSubjectRulesReview {
+            spec: SelfSubjectRulesReviewSpec {
+                namespace: Some(namespace.clone()),
+            },
+            ..Default::default()
+        };
+        let api: Api<SelfSubjectRulesReview> = Api::all(self.client.clone());
+        match api.create(&PostParams::default(), &review).await {
+            Ok(review) => {
+                let rules = review.status.map(|s| s.resource_rules.len()).unwrap_or(0);
+                debug!(namespace = %namespace, rules, "Credentials accepted");
+                Ok(())
+            }
+            Err(kube::Error::Api(response)) if response.code == 401 => {
+                Err(K8sError::from(&response)).context("The API server rejected the credentials")
+            }
+            Err(e) => {
+                // Some clusters disable or restrict rules reviews; the version check suffices
+                debug!(error = %e, "Could not review permissions");
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the version, identity and API groups of the cluster
+    ///
+    /// The identity is read from a SelfSubjectReview (Kubernetes 1.28+); if it is not
+    /// available, the user is left unknown.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FarosClusterInfo>` - Information about the cluster and the current user
+    #[instrument(skip(self), level = "debug")]
+    pub async fn cluster_info(&self) -> Result<FarosClusterInfo> {
+        let version = self
+            .client
+            .apiserver_version()
+            .await
+            .context("Failed to get the API server version")?;
+
+        let api: Api<SelfSubjectReview> = Api::all(self.client.clone());
+        let user = match api
+            .create(&PostParams::default(), &SelfSubjectReview::default())
+            .await
+        {
+            Ok(review) => review.status.and_then(|s| s.user_info),
+            Err(e) => {
+                warn!(error = %e, "Failed to get the current identity");
+                None
+            }
+        };
+
+        let mut api_groups = self
+            .client
+            .list_core_api_versions()
+            .await
+            .context("Failed to list API versions")?
+            .versions;
+        let groups = self
+            .client
+            .list_api_groups()
+            .await
+            .context("Failed to list API groups")?;
+        api_groups.extend(groups.groups.into_iter().filter_map(|group| {
+            group
+                .preferred_version
+                .or_else(|| group.versions.into_iter().next())
+                .map(|version| version.group_version)
+        }));
+
+        Ok(FarosClusterInfo {
+            server_version: Some(version.git_version).filter(|v| !v.is_empty()),
+            platform: Some(version.platform).filter(|p| !p.is_empty()),
+            endpoint: self.connection.as_ref().map(|c| c.endpoint.clone()),
+            auth_method: self.connection.as_ref().map(|c| c.auth_method.clone()),
+            username: user.as_ref().and_then(|u| u.username.clone()),
+            groups: user.and_then(|u| u.groups).unwrap_or_default(),
+            api_groups,
+        })
+    }
+}
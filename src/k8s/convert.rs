@@ -0,0 +1,315 @@
+use super::{FarosNode, FarosOwner, FarosPod, details};
+use k8s_openapi::api::core::v1::{EnvVar, Node, Pod};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use std::collections::BTreeMap;
+
+/// Options controlling which fields are extracted when converting Kubernetes objects
+///
+/// The defaults extract everything, as used by the fakos commands.
+#[derive(Debug, Clone)]
+pub struct ExtractOptions {
+    /// Extract the environment variables of each container
+    pub env_vars: bool,
+    /// Resolve environment variables referring to fields of the pod itself (e.g.
+    /// `metadata.name`) instead of showing them as `fieldRef[...]`
+    pub resolve_field_refs: bool,
+    /// Extract containers, conditions, tolerations, affinity and volumes of pods, and addresses,
+    /// capacity, allocatable resources, system information and conditions of nodes
+    pub details: bool,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self {
+            env_vars: true,
+            resolve_field_refs: true,
+            details: true,
+        }
+    }
+}
+
+/// Determine the workload controlling a pod from its direct controller without extra API calls
+///
+/// ReplicaSets created by a Deployment are named `<deployment>-<pod-template-hash>`, so a pod
+/// whose ReplicaSet matches its `pod-template-hash` label is attributed to the Deployment.
+fn owning_workload(controller: &FarosOwner, labels: &BTreeMap<String, String>) -> FarosOwner {
+    if controller.kind == "ReplicaSet"
+        && let Some(hash) = labels.get("pod-template-hash")
+        && let Some(deployment) = controller.name.strip_suffix(&format!("-{}", hash))
+    {
+        return FarosOwner {
+            api_version: "apps/v1".to_string(),
+            kind: "Deployment".to_string(),
+            name: deployment.to_string(),
+        };
+    }
+    controller.clone()
+}
+
+/// Resolve a `fieldRef` environment variable from the pod it belongs to
+fn resolve_field_ref(pod: &Pod, field_path: &str) -> Option<String> {
+    let spec = pod.spec.as_ref();
+    let status = pod.status.as_ref();
+    let value = match field_path {
+        "metadata.name" => pod.metadata.name.clone(),
+        "metadata.namespace" => pod.metadata.namespace.clone(),
+        "metadata.uid" => pod.metadata.uid.clone(),
+        "spec.nodeName" => spec.and_then(|s| s.node_name.clone()),
+        "spec.serviceAccountName" => spec.and_then(|s| s.service_account_name.clone()),
+        "status.hostIP" => status.and_then(|s| s.host_ip.clone()),
+        "status.podIP" => status.and_then(|s| s.pod_ip.clone()),
+        _ => return None,
+    };
+    Some(value.unwrap_or_default())
+}
+
+/// Describe the value of an environment variable
+///
+/// # Arguments
+///
+/// * `var` - The environment variable
+/// * `pod` - The pod to resolve `fieldRef` values from, if any
+///
+/// # Returns
+///
+/// * `String` - The literal value, the resolved `fieldRef` value, or a description of the
+///   referenced object (e.g. `secret[name:key]`)
+pub(crate) fn format_env_value(var: &EnvVar, pod: Option<&Pod>) -> String {
+    if let Some(value) = &var.value {
+        return value.clone();
+    }
+    let Some(value_from) = &var.value_from else {
+        return String::new();
+    };
+    if let Some(field_ref) = &value_from.field_ref {
+        pod.and_then(|pod| resolve_field_ref(pod, &field_ref.field_path))
+            .unwrap_or_else(|| format!("fieldRef[{}]", field_ref.field_path))
+    } else if let Some(secret_ref) = &value_from.secret_key_ref {
+        format!("secret[{}:{}]", secret_ref.name, secret_ref.key)
+    } else if let Some(cm_ref) = &value_from.config_map_key_ref {
+        format!("configmap[{}:{}]", cm_ref.name, cm_ref.key)
+    } else if let Some(res_ref) = &value_from.resource_field_ref {
+        format!(
+            "resource[{}:{}]",
+            res_ref.resource,
+            res_ref.container_name.clone().unwrap_or_default()
+        )
+    } else {
+        "<value-from>".to_string()
+    }
+}
+
+impl FarosPod {
+    /// Convert a Kubernetes pod into a FarosPod
+    ///
+    /// References to Secrets, ConfigMaps and resources in environment variables are described,
+    /// not resolved, so no API calls are made.
+    ///
+    /// # Arguments
+    ///
+    /// * `pod` - The pod to convert
+    /// * `options` - Which fields to extract
+    ///
+    /// # Returns
+    ///
+    /// * `FarosPod` - The converted pod, without events
+    pub fn from_pod(pod: &Pod, options: &ExtractOptions) -> Self {
+        let spec = pod.spec.as_ref();
+        let status = pod.status.as_ref();
+        let labels = pod.metadata.labels.clone().unwrap_or_default();
+
+        let mut container_env_vars = BTreeMap::new();
+        if options.env_vars {
+            let field_source = options.resolve_field_refs.then_some(pod);
+            // Init and ephemeral containers are distinct types in the API, so only their names
+            // and environment are collected
+            let containers = spec.into_iter().flat_map(|s| {
+                let init = s
+                    .init_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| (&c.name, &c.env));
+                let regular = s.containers.iter().map(|c| (&c.name, &c.env));
+                let ephemeral = s
+                    .ephemeral_containers
+                    .iter()
+                    .flatten()
+                    .map(|c| (&c.name, &c.env));
+                init.chain(regular).chain(ephemeral)
+            });
+            for (name, env) in containers {
+                let env_vars = env
+                    .iter()
+                    .flatten()
+                    .map(|var| (var.name.clone(), format_env_value(var, field_source)))
+                    .collect();
+                container_env_vars.insert(name.clone(), env_vars);
+            }
+        }
+
+        let owner_chain: Vec<FarosOwner> = pod
+            .metadata
+            .owner_references
+            .iter()
+            .flatten()
+            .filter(|o| o.controller.unwrap_or(false))
+            .map(FarosOwner::from)
+            .collect();
+        let controller = owner_chain
+            .first()
+            .map(|owner| owning_workload(owner, &labels));
+
+        let mut faros_pod = FarosPod {
+            name: pod.metadata.name.clone().unwrap_or_default(),
+            namespace: pod.metadata.namespace.clone().unwrap_or_default(),
+            node: spec.and_then(|s| s.node_name.clone()),
+            labels,
+            annotations: pod.metadata.annotations.clone().unwrap_or_default(),
+            container_env_vars,
+            phase: status
+                .and_then(|s| s.phase.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            pod_ip: status.and_then(|s| s.pod_ip.clone()),
+            service_account: spec.and_then(|s| s.service_account_name.clone()),
+            qos_class: status.and_then(|s| s.qos_class.clone()),
+            created: pod.metadata.creation_timestamp.as_ref().map(|t| t.0),
+            node_selector: spec
+                .and_then(|s| s.node_selector.clone())
+                .unwrap_or_default(),
+            controller,
+            owner_chain,
+            containers: Vec::new(),
+            conditions: Vec::new(),
+            tolerations: Vec::new(),
+            affinity: Vec::new(),
+            volumes: Vec::new(),
+            events: Vec::new(),
+        };
+
+        if options.details {
+            faros_pod.containers = details::extract_containers(pod);
+            faros_pod.conditions = details::extract_pod_conditions(pod);
+            faros_pod.tolerations = spec
+                .and_then(|s| s.tolerations.as_ref())
+                .map(|t| t.iter().map(details::format_toleration).collect())
+                .unwrap_or_default();
+            faros_pod.affinity = spec
+                .and_then(|s| s.affinity.as_ref())
+                .map(details::format_affinity)
+                .unwrap_or_default();
+            faros_pod.volumes = spec
+                .and_then(|s| s.volumes.as_ref())
+                .map(|v| v.iter().map(details::format_volume).collect())
+                .unwrap_or_default();
+        }
+
+        faros_pod
+    }
+}
+
+impl From<&Pod> for FarosPod {
+    fn from(pod: &Pod) -> Self {
+        Self::from_pod(pod, &ExtractOptions::default())
+    }
+}
+
+impl FarosNode {
+    /// Convert a Kubernetes node into a FarosNode
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - The node to convert
+    /// * `options` - Which fields to extract
+    ///
+    /// # Returns
+    ///
+    /// * `FarosNode` - The converted node, without events
+    pub fn from_node(node: &Node, options: &ExtractOptions) -> Self {
+        let spec = node.spec.as_ref();
+        let status = node.status.as_ref();
+
+        let ready = status
+            .and_then(|s| s.conditions.as_ref())
+            .and_then(|conditions| {
+                conditions.iter().find(|c| c.type_ == "Ready").map(|c| {
+                    if c.status == "True" {
+                        "Ready".to_string()
+                    } else {
+                        "NotReady".to_string()
+                    }
+                })
+            })
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let mut faros_node = FarosNode {
+            name: node.metadata.name.clone().unwrap_or_default(),
+            labels: node.metadata.labels.clone().unwrap_or_default(),
+            annotations: node.metadata.annotations.clone().unwrap_or_default(),
+            status: ready,
+            unschedulable: spec.and_then(|s| s.unschedulable).unwrap_or(false),
+            created: node.metadata.creation_timestamp.as_ref().map(|t| t.0),
+            addresses: Vec::new(),
+            taints: spec
+                .and_then(|s| s.taints.as_ref())
+                .map(|taints| {
+                    taints
+                        .iter()
+                        .map(|t| match &t.value {
+                            Some(value) => format!("{}={}:{}", t.key, value, t.effect),
+                            None => format!("{}:{}", t.key, t.effect),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            capacity: BTreeMap::new(),
+            allocatable: BTreeMap::new(),
+            system_info: Vec::new(),
+            conditions: Vec::new(),
+            events: Vec::new(),
+        };
+
+        if options.details {
+            let quantities = |map: Option<&BTreeMap<String, Quantity>>| {
+                map.into_iter()
+                    .flatten()
+                    .map(|(k, v)| (k.clone(), v.0.clone()))
+                    .collect()
+            };
+            faros_node.addresses = status
+                .and_then(|s| s.addresses.as_ref())
+                .map(|addresses| {
+                    addresses
+                        .iter()
+                        .map(|a| format!("{}: {}", a.type_, a.address))
+                        .collect()
+                })
+                .unwrap_or_default();
+            faros_node.capacity = quantities(status.and_then(|s| s.capacity.as_ref()));
+            faros_node.allocatable = quantities(status.and_then(|s| s.allocatable.as_ref()));
+            faros_node.system_info = status
+                .and_then(|s| s.node_info.as_ref())
+                .map(|info| {
+                    vec![
+                        ("OS Image".to_string(), info.os_image.clone()),
+                        ("Kernel Version".to_string(), info.kernel_version.clone()),
+                        ("Architecture".to_string(), info.architecture.clone()),
+                        (
+                            "Container Runtime".to_string(),
+                            info.container_runtime_version.clone(),
+                        ),
+                        ("Kubelet Version".to_string(), info.kubelet_version.clone()),
+                    ]
+                })
+                .unwrap_or_default();
+            faros_node.conditions = details::extract_node_conditions(node);
+        }
+
+        faros_node
+    }
+}
+
+impl From<&Node> for FarosNode {
+    fn from(node: &Node) -> Self {
+        Self::from_node(node, &ExtractOptions::default())
+    }
+}
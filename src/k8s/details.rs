@@ -0,0 +1,549 @@
+use k8s_openapi::api::core::v1::{
+    Affinity, ContainerPort, ContainerState, ContainerStatus, EnvFromSource, EnvVar, Node,
+    NodeSelectorTerm, Pod, PodAffinityTerm, Probe, ResourceRequirements, Toleration, Volume,
+    VolumeMount,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, OwnerReference};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use k8s_openapi::chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Kind of container within a pod
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContainerKind {
+    /// Init container, run to completion before the regular containers start
+    Init,
+    /// Regular application container
+    Regular,
+    /// Ephemeral debug container
+    Ephemeral,
+}
+
+impl std::fmt::Display for ContainerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerKind::Init => write!(f, "init"),
+            ContainerKind::Regular => write!(f, "container"),
+            ContainerKind::Ephemeral => write!(f, "ephemeral"),
+        }
+    }
+}
+
+/// Represents a single container of a pod together with its status
+#[derive(Debug, Clone)]
+pub struct FarosContainer {
+    /// Name of the container
+    pub name: String,
+    /// Kind of the container (init, regular or ephemeral)
+    pub kind: ContainerKind,
+    /// Image reference from the pod spec
+    pub image: String,
+    /// Resolved image ID reported by the container runtime, if the container has started
+    pub image_id: Option<String>,
+    /// State of the container (e.g. Running, Waiting: CrashLoopBackOff)
+    pub state: String,
+    /// Reason the container is waiting (e.g. CrashLoopBackOff), if it is waiting
+    pub waiting_reason: Option<String>,
+    /// Whether the container passes its readiness probe
+    pub ready: bool,
+    /// Number of times the container has been restarted
+    pub restart_count: i32,
+    /// Exposed ports (e.g. 8080/TCP (http))
+    pub ports: Vec<String>,
+    /// Liveness probe description
+    pub liveness_probe: Option<String>,
+    /// Readiness probe description
+    pub readiness_probe: Option<String>,
+    /// Startup probe description
+    pub startup_probe: Option<String>,
+    /// Resource requests (Resource Name -> Quantity)
+    pub requests: BTreeMap<String, String>,
+    /// Resource limits (Resource Name -> Quantity)
+    pub limits: BTreeMap<String, String>,
+    /// Volume mounts (e.g. config -> /etc/config (ro))
+    pub mounts: Vec<String>,
+    /// Sources that environment variables are bulk-loaded from (e.g. configmap[app-config])
+    pub env_from: Vec<String>,
+    /// Environment variables as declared in the spec, with their `valueFrom` sources
+    pub env: Vec<EnvVar>,
+    /// Sources that environment variables are bulk-loaded from, as declared in the spec
+    pub env_from_sources: Vec<EnvFromSource>,
+}
+
+/// Represents a condition of a pod or node
+#[derive(Debug, Clone)]
+pub struct FarosCondition {
+    /// Type of the condition (e.g. Ready, MemoryPressure)
+    pub condition_type: String,
+    /// Status of the condition (True, False, Unknown)
+    pub status: String,
+    /// Machine-readable reason for the last transition
+    pub reason: Option<String>,
+    /// Human-readable details about the last transition
+    pub message: Option<String>,
+    /// When the condition last changed status
+    pub last_transition: Option<DateTime<Utc>>,
+}
+
+/// Represents an owner of a Kubernetes object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FarosOwner {
+    /// API version of the owner (e.g. apps/v1)
+    pub api_version: String,
+    /// Kind of the owner (e.g. ReplicaSet)
+    pub kind: String,
+    /// Name of the owner
+    pub name: String,
+}
+
+impl From<&OwnerReference> for FarosOwner {
+    fn from(owner: &OwnerReference) -> Self {
+        Self {
+            api_version: owner.api_version.clone(),
+            kind: owner.kind.clone(),
+            name: owner.name.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for FarosOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind, self.name)
+    }
+}
+
+/// Build a [`FarosContainer`] from any container-like spec; regular and ephemeral containers
+/// are distinct types in the API but share the relevant fields
+macro_rules! faros_container {
+    ($container:expr, $kind:expr, $statuses:expr) => {{
+        let container = $container;
+        let status = $statuses.and_then(|statuses: &Vec<ContainerStatus>| {
+            statuses.iter().find(|s| s.name == container.name)
+        });
+        let (requests, limits) = format_resources(container.resources.as_ref());
+
+        FarosContainer {
+            name: container.name.clone(),
+            kind: $kind,
+            image: container.image.clone().unwrap_or_default(),
+            image_id: status
+                .map(|s| s.image_id.clone())
+                .filter(|id| !id.is_empty()),
+            state: status
+                .and_then(|s| s.state.as_ref())
+                .map(format_container_state)
+                .unwrap_or_else(|| "Unknown".to_string()),
+            waiting_reason: status
+                .and_then(|s| s.state.as_ref())
+                .and_then(|state| state.waiting.as_ref())
+                .and_then(|waiting| waiting.reason.clone()),
+            ready: status.is_some_and(|s| s.ready),
+            restart_count: status.map(|s| s.restart_count).unwrap_or_default(),
+            ports: format_ports(container.ports.as_ref()),
+            liveness_probe: container.liveness_probe.as_ref().map(format_probe),
+            readiness_probe: container.readiness_probe.as_ref().map(format_probe),
+            startup_probe: container.startup_probe.as_ref().map(format_probe),
+            requests,
+            limits,
+            mounts: format_mounts(container.volume_mounts.as_ref()),
+            env_from: format_env_from(container.env_from.as_ref()),
+            env: container.env.clone().unwrap_or_default(),
+            env_from_sources: container.env_from.clone().unwrap_or_default(),
+        }
+    }};
+}
+
+/// Extract all init, regular and ephemeral containers of a pod
+///
+/// # Arguments
+///
+/// * `pod` - The pod to extract containers from
+///
+/// # Returns
+///
+/// * `Vec<FarosContainer>` - The containers in init, regular, ephemeral order
+pub(crate) fn extract_containers(pod: &Pod) -> Vec<FarosContainer> {
+    let Some(spec) = &pod.spec else {
+        return Vec::new();
+    };
+    let status = pod.status.as_ref();
+
+    let mut containers = Vec::new();
+    for container in spec.init_containers.iter().flatten() {
+        let statuses = status.and_then(|s| s.init_container_statuses.as_ref());
+        containers.push(faros_container!(container, ContainerKind::Init, statuses));
+    }
+    for container in &spec.containers {
+        let statuses = status.and_then(|s| s.container_statuses.as_ref());
+        containers.push(faros_container!(
+            container,
+            ContainerKind::Regular,
+            statuses
+        ));
+    }
+    for container in spec.ephemeral_containers.iter().flatten() {
+        let statuses = status.and_then(|s| s.ephemeral_container_statuses.as_ref());
+        containers.push(faros_container!(
+            container,
+            ContainerKind::Ephemeral,
+            statuses
+        ));
+    }
+    containers
+}
+
+/// Extract the conditions of a pod
+pub(crate) fn extract_pod_conditions(pod: &Pod) -> Vec<FarosCondition> {
+    pod.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .map(|conditions| {
+            conditions
+                .iter()
+                .map(|c| FarosCondition {
+                    condition_type: c.type_.clone(),
+                    status: c.status.clone(),
+                    reason: c.reason.clone(),
+                    message: c.message.clone(),
+                    last_transition: c.last_transition_time.as_ref().map(|t| t.0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Extract the conditions of a node
+pub(crate) fn extract_node_conditions(node: &Node) -> Vec<FarosCondition> {
+    node.status
+        .as_ref()
+        .and_then(|s| s.conditions.as_ref())
+        .map(|conditions| {
+            conditions
+                .iter()
+                .map(|c| FarosCondition {
+                    condition_type: c.type_.clone(),
+                    status: c.status.clone(),
+                    reason: c.reason.clone(),
+                    message: c.message.clone(),
+                    last_transition: c.last_transition_time.as_ref().map(|t| t.0),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Format a container state as a short description (e.g. `Waiting: CrashLoopBackOff`)
+fn format_container_state(state: &ContainerState) -> String {
+    if let Some(waiting) = &state.waiting {
+        format!(
+            "Waiting: {}",
+            waiting.reason.as_deref().unwrap_or("Unknown")
+        )
+    } else if let Some(terminated) = &state.terminated {
+        format!(
+            "Terminated: {} (exit code {})",
+            terminated.reason.as_deref().unwrap_or("Unknown"),
+            terminated.exit_code
+        )
+    } else if state.running.is_some() {
+        "Running".to_string()
+    } else {
+        "Unknown".to_string()
+    }
+}
+
+fn format_ports(ports: Option<&Vec<ContainerPort>>) -> Vec<String> {
+    ports
+        .into_iter()
+        .flatten()
+        .map(|port| {
+            let mut entry = format!(
+                "{}/{}",
+                port.container_port,
+                port.protocol.as_deref().unwrap_or("TCP")
+            );
+            if let Some(host_port) = port.host_port {
+                entry.push_str(&format!(" (host {})", host_port));
+            }
+            if let Some(name) = &port.name {
+                entry.push_str(&format!(" ({})", name));
+            }
+            entry
+        })
+        .collect()
+}
+
+fn format_int_or_string(value: &IntOrString) -> String {
+    match value {
+        IntOrString::Int(i) => i.to_string(),
+        IntOrString::String(s) => s.clone(),
+    }
+}
+
+/// Format a probe in the same shape as `kubectl describe`
+/// (e.g. `http-get http://:8080/healthz delay=0s timeout=1s period=10s #success=1 #failure=3`)
+fn format_probe(probe: &Probe) -> String {
+    let action = if let Some(http) = &probe.http_get {
+        format!(
+            "http-get {}://{}:{}{}",
+            http.scheme.as_deref().unwrap_or("HTTP").to_lowercase(),
+            http.host.as_deref().unwrap_or_default(),
+            format_int_or_string(&http.port),
+            http.path.as_deref().unwrap_or("/")
+        )
+    } else if let Some(tcp) = &probe.tcp_socket {
+        format!("tcp-socket :{}", format_int_or_string(&tcp.port))
+    } else if let Some(grpc) = &probe.grpc {
+        format!("grpc :{}", grpc.port)
+    } else if let Some(exec) = &probe.exec {
+        format!(
+            "exec [{}]",
+            exec.command.clone().unwrap_or_default().join(" ")
+        )
+    } else {
+        "<unknown>".to_string()
+    };
+
+    format!(
+        "{} delay={}s timeout={}s period={}s #success={} #failure={}",
+        action,
+        probe.initial_delay_seconds.unwrap_or(0),
+        probe.timeout_seconds.unwrap_or(1),
+        probe.period_seconds.unwrap_or(10),
+        probe.success_threshold.unwrap_or(1),
+        probe.failure_threshold.unwrap_or(3)
+    )
+}
+
+fn format_resources(
+    resources: Option<&ResourceRequirements>,
+) -> (BTreeMap<String, String>, BTreeMap<String, String>) {
+    let to_strings = |map: Option<&BTreeMap<String, _>>| {
+        map.into_iter()
+            .flatten()
+            .map(
+                |(k, v): (
+                    &String,
+                    &k8s_openapi::apimachinery::pkg::api::resource::Quantity,
+                )| { (k.clone(), v.0.clone()) },
+            )
+            .collect()
+    };
+    (
+        to_strings(resources.and_then(|r| r.requests.as_ref())),
+        to_strings(resources.and_then(|r| r.limits.as_ref())),
+    )
+}
+
+fn format_mounts(mounts: Option<&Vec<VolumeMount>>) -> Vec<String> {
+    mounts
+        .into_iter()
+        .flatten()
+        .map(|mount| {
+            let mut entry = format!("{} -> {}", mount.name, mount.mount_path);
+            if let Some(sub_path) = mount.sub_path.as_ref().filter(|p| !p.is_empty()) {
+                entry.push_str(&format!(" (subPath {})", sub_path));
+            }
+            entry.push_str(if mount.read_only.unwrap_or(false) {
+                " (ro)"
+            } else {
+                " (rw)"
+            });
+            entry
+        })
+        .collect()
+}
+
+fn format_env_from(sources: Option<&Vec<EnvFromSource>>) -> Vec<String> {
+    sources
+        .into_iter()
+        .flatten()
+        .filter_map(|source| {
+            let reference = if let Some(cm) = &source.config_map_ref {
+                format!("configmap[{}]", cm.name)
+            } else if let Some(secret) = &source.secret_ref {
+                format!("secret[{}]", secret.name)
+            } else {
+                return None;
+            };
+            Some(match &source.prefix {
+                Some(prefix) => format!("{} (prefix {})", reference, prefix),
+                None => reference,
+            })
+        })
+        .collect()
+}
+
+/// Format a toleration (e.g. `node.kubernetes.io/not-ready:NoExecute op=Exists for 300s`)
+pub(crate) fn format_toleration(toleration: &Toleration) -> String {
+    let mut entry = toleration.key.clone().unwrap_or_default();
+    if let Some(value) = toleration.value.as_ref().filter(|v| !v.is_empty()) {
+        entry.push('=');
+        entry.push_str(value);
+    }
+    if let Some(effect) = &toleration.effect {
+        entry.push(':');
+        entry.push_str(effect);
+    }
+    if toleration.operator.as_deref() == Some("Exists") {
+        entry.push_str(" op=Exists");
+    }
+    if let Some(seconds) = toleration.toleration_seconds {
+        entry.push_str(&format!(" for {}s", seconds));
+    }
+    entry
+}
+
+/// Format a volume as `name: type(source)`
+pub(crate) fn format_volume(volume: &Volume) -> String {
+    let source = if let Some(cm) = &volume.config_map {
+        format!("ConfigMap({})", cm.name)
+    } else if let Some(secret) = &volume.secret {
+        format!(
+            "Secret({})",
+            secret.secret_name.as_deref().unwrap_or_default()
+        )
+    } else if let Some(pvc) = &volume.persistent_volume_claim {
+        format!("PersistentVolumeClaim({})", pvc.claim_name)
+    } else if let Some(host_path) = &volume.host_path {
+        format!("HostPath({})", host_path.path)
+    } else if let Some(empty_dir) = &volume.empty_dir {
+        match empty_dir.medium.as_deref().filter(|m| !m.is_empty()) {
+            Some(medium) => format!("EmptyDir({})", medium),
+            None => "EmptyDir".to_string(),
+        }
+    } else if volume.projected.is_some() {
+        "Projected".to_string()
+    } else if volume.downward_api.is_some() {
+        "DownwardAPI".to_string()
+    } else if let Some(csi) = &volume.csi {
+        format!("CSI({})", csi.driver)
+    } else if volume.ephemeral.is_some() {
+        "Ephemeral".to_string()
+    } else if let Some(nfs) = &volume.nfs {
+        format!("NFS({}:{})", nfs.server, nfs.path)
+    } else {
+        "Other".to_string()
+    };
+    format!("{}: {}", volume.name, source)
+}
+
+fn format_label_selector(selector: Option<&LabelSelector>) -> String {
+    let Some(selector) = selector else {
+        return "<all>".to_string();
+    };
+    let mut parts: Vec<String> = selector
+        .match_labels
+        .iter()
+        .flatten()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    for expr in selector.match_expressions.iter().flatten() {
+        parts.push(format!(
+            "{} {} [{}]",
+            expr.key,
+            expr.operator,
+            expr.values.clone().unwrap_or_default().join(",")
+        ));
+    }
+    if parts.is_empty() {
+        "<all>".to_string()
+    } else {
+        parts.join(",")
+    }
+}
+
+fn format_node_selector_term(term: &NodeSelectorTerm) -> String {
+    term.match_expressions
+        .iter()
+        .flatten()
+        .chain(term.match_fields.iter().flatten())
+        .map(|expr| {
+            format!(
+                "{} {} [{}]",
+                expr.key,
+                expr.operator,
+                expr.values.clone().unwrap_or_default().join(",")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_pod_affinity_term(term: &PodAffinityTerm) -> String {
+    format!(
+        "{} topologyKey={}",
+        format_label_selector(term.label_selector.as_ref()),
+        term.topology_key
+    )
+}
+
+/// Format the affinity rules of a pod, one rule per line
+pub(crate) fn format_affinity(affinity: &Affinity) -> Vec<String> {
+    let mut rules = Vec::new();
+
+    if let Some(node) = &affinity.node_affinity {
+        if let Some(required) = &node.required_during_scheduling_ignored_during_execution {
+            for term in &required.node_selector_terms {
+                rules.push(format!(
+                    "nodeAffinity required: {}",
+                    format_node_selector_term(term)
+                ));
+            }
+        }
+        for preferred in node
+            .preferred_during_scheduling_ignored_during_execution
+            .iter()
+            .flatten()
+        {
+            rules.push(format!(
+                "nodeAffinity preferred (weight {}): {}",
+                preferred.weight,
+                format_node_selector_term(&preferred.preference)
+            ));
+        }
+    }
+
+    let pod_rules = [
+        (
+            "podAffinity",
+            affinity.pod_affinity.as_ref().map(|a| {
+                (
+                    &a.required_during_scheduling_ignored_during_execution,
+                    &a.preferred_during_scheduling_ignored_during_execution,
+                )
+            }),
+        ),
+        (
+            "podAntiAffinity",
+            affinity.pod_anti_affinity.as_ref().map(|a| {
+                (
+                    &a.required_during_scheduling_ignored_during_execution,
+                    &a.preferred_during_scheduling_ignored_during_execution,
+                )
+            }),
+        ),
+    ];
+    for (name, terms) in pod_rules {
+        let Some((required, preferred)) = terms else {
+            continue;
+        };
+        for term in required.iter().flatten() {
+            rules.push(format!(
+                "{} required: {}",
+                name,
+                format_pod_affinity_term(term)
+            ));
+        }
+        for weighted in preferred.iter().flatten() {
+            rules.push(format!(
+                "{} preferred (weight {}): {}",
+                name,
+                weighted.weight,
+                format_pod_affinity_term(&weighted.pod_affinity_term)
+            ));
+        }
+    }
+
+    rules
+}
@@ -0,0 +1,480 @@
+use super::{K8sClient, K8sError};
+use anyhow::{Context, Result};
+use futures::StreamExt;
+use futures::channel::mpsc;
+use k8s_openapi::api::core::v1::{Node, Pod};
+use kube::api::{DeleteParams, EvictParams, ListParams, Patch, PatchParams, PostParams};
+use kube::{Api, ResourceExt};
+use serde_json::json;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, instrument};
+
+/// Annotation set on mirror pods created by the kubelet for static pods
+const MIRROR_POD_ANNOTATION: &str = "kubernetes.io/config.mirror";
+
+/// Time to wait before retrying an eviction refused by a PodDisruptionBudget
+const EVICTION_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time between checks whether an evicted pod is gone
+const DELETION_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// State of a pod while its node is drained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvictionState {
+    /// The pod will be evicted
+    Pending,
+    /// The pod is left on the node (e.g. DaemonSet and mirror pods)
+    Skipped(String),
+    /// The pod prevents the node from being drained without extra flags
+    Blocked(String),
+    /// The eviction request is being sent
+    Evicting,
+    /// The eviction was refused by a PodDisruptionBudget and is being retried
+    DisruptionBudget,
+    /// The eviction was accepted and the pod is shutting down
+    Terminating,
+    /// The pod is gone (or the API server accepted the eviction in a server dry run)
+    Evicted,
+    /// The pod could not be evicted
+    Failed(String),
+}
+
+impl std::fmt::Display for EvictionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvictionState::Pending => write!(f, "Pending"),
+            EvictionState::Skipped(reason) => write!(f, "Skipped: {}", reason),
+            EvictionState::Blocked(reason) => write!(f, "Blocked: {}", reason),
+            EvictionState::Evicting => write!(f, "Evicting"),
+            EvictionState::DisruptionBudget => {
+                write!(f, "Waiting for PodDisruptionBudget")
+            }
+            EvictionState::Terminating => write!(f, "Terminating"),
+            EvictionState::Evicted => write!(f, "Evicted"),
+            EvictionState::Failed(reason) => write!(f, "Failed: {}", reason),
+        }
+    }
+}
+
+/// A pod on a node being drained
+#[derive(Debug, Clone)]
+pub struct PodEviction {
+    /// Namespace of the pod
+    pub namespace: String,
+    /// Name of the pod
+    pub name: String,
+    /// UID of the pod, used to tell it apart from a replacement with the same name
+    pub uid: Option<String>,
+    /// Current state of the eviction
+    pub state: EvictionState,
+}
+
+/// Receives the state of all pods of a drain whenever one of them changes
+pub trait EvictionObserver: Send {
+    /// Called with the state of all pods after any of them changed
+    fn on_progress(&mut self, pods: &[PodEviction]);
+
+    /// Called with the final state of all pods once no eviction is pending
+    fn on_finish(&mut self, _pods: &[PodEviction]) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&[PodEviction]) + Send> EvictionObserver for F {
+    fn on_progress(&mut self, pods: &[PodEviction]) {
+        self(pods)
+    }
+}
+
+/// Options controlling how pods are evicted during a drain
+#[derive(Debug, Clone)]
+pub struct DrainOptions {
+    /// Grace period given to each pod to terminate, or None for the pod's own setting
+    pub grace_period: Option<u32>,
+    /// Maximum time to wait for all pods of a node to be evicted
+    pub timeout: Duration,
+    /// Evict pods not managed by a controller, which will not be recreated
+    pub force: bool,
+    /// Evict pods using emptyDir volumes, whose data is lost
+    pub delete_emptydir_data: bool,
+    /// Have the API server validate evictions without performing them
+    pub server_dry_run: bool,
+}
+
+/// Decide what happens to a pod when its node is drained, following `kubectl drain`
+fn eviction_state(pod: &Pod, options: &DrainOptions) -> EvictionState {
+    if pod.annotations().contains_key(MIRROR_POD_ANNOTATION) {
+        return EvictionState::Skipped("mirror pod".to_string());
+    }
+
+    let controller = pod
+        .owner_references()
+        .iter()
+        .find(|owner| owner.controller == Some(true));
+    if controller.is_some_and(|owner| owner.kind == "DaemonSet") {
+        return EvictionState::Skipped("DaemonSet pod".to_string());
+    }
+
+    let phase = pod.status.as_ref().and_then(|s| s.phase.as_deref());
+    if matches!(phase, Some("Succeeded") | Some("Failed")) {
+        return EvictionState::Skipped("completed".to_string());
+    }
+
+    if controller.is_none() && !options.force {
+        return EvictionState::Blocked("not managed by a controller (use --force)".to_string());
+    }
+
+    let uses_empty_dir = pod
+        .spec
+        .iter()
+        .flat_map(|spec| spec.volumes.iter().flatten())
+        .any(|volume| volume.empty_dir.is_some());
+    if uses_empty_dir && !options.delete_emptydir_data {
+        return EvictionState::Blocked("uses emptyDir (use --delete-emptydir-data)".to_string());
+    }
+
+    EvictionState::Pending
+}
+
+impl K8sClient {
+    /// Get the names of nodes selected by name or label selector
+    ///
+    /// # Arguments
+    ///
+    /// * `node_name` - Name of a single node
+    /// * `label_selector` - Label selector to filter nodes by (used if no name is given)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<String>>` - The names of the selected nodes
+    #[instrument(skip(self), level = "debug")]
+    pub async fn select_nodes(
+        &self,
+        node_name: Option<&str>,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let api: Api<Node> = Api::all(self.client.clone());
+
+        if let Some(name) = node_name {
+            api.get_opt(name)
+                .await
+                .context("Failed to get node from Kubernetes API")?
+                .ok_or_else(|| K8sError::ResourceNotFound(format!("node/{}", name)))?;
+            return Ok(vec![name.to_string()]);
+        }
+
+        let mut list_params = ListParams::default();
+        if let Some(selector) = label_selector {
+            list_params = list_params.labels(selector);
+        }
+        let nodes = api
+            .list(&list_params)
+            .await
+            .context("Failed to list nodes from Kubernetes API")?;
+        Ok(nodes.items.iter().map(|node| node.name_any()).collect())
+    }
+
+    /// Mark a node as schedulable or unschedulable
+    ///
+    /// # Arguments
+    ///
+    /// * `node_name` - Name of the node
+    /// * `unschedulable` - True to cordon the node, false to uncordon it
+    /// * `server_dry_run` - If true, the API server validates the change without persisting it
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - True if the node was changed, false if it already was in that state
+    #[instrument(skip(self), level = "debug")]
+    pub async fn set_unschedulable(
+        &self,
+        node_name: &str,
+        unschedulable: bool,
+        server_dry_run: bool,
+    ) -> Result<bool> {
+        let api: Api<Node> = Api::all(self.client.clone());
+        let node = api
+            .get(node_name)
+            .await
+            .context("Failed to get node from Kubernetes API")?;
+        let current = node
+            .spec
+            .as_ref()
+            .and_then(|s| s.unschedulable)
+            .unwrap_or(false);
+        if current == unschedulable {
+            return Ok(false);
+        }
+
+        let params = PatchParams {
+            dry_run: server_dry_run,
+            ..Default::default()
+        };
+        let patch = json!({ "spec": { "unschedulable": unschedulable } });
+        api.patch(node_name, &params, &Patch::Merge(&patch))
+            .await
+            .context("Failed to patch node")?;
+        Ok(true)
+    }
+
+    /// Determine which pods on a node would be evicted by a drain
+    ///
+    /// # Arguments
+    ///
+    /// * `node_name` - Name of the node
+    /// * `options` - Drain options deciding which pods may be evicted
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PodEviction>>` - All pods on the node, either pending eviction, skipped or
+    ///   blocking the drain
+    #[instrument(skip(self, options), level = "debug")]
+    pub async fn plan_drain(
+        &self,
+        node_name: &str,
+        options: &DrainOptions,
+    ) -> Result<Vec<PodEviction>> {
+        let pods = self.list_pods("default", true, Some(node_name)).await?;
+        Ok(pods
+            .iter()
+            .map(|pod| PodEviction {
+                namespace: pod.namespace().unwrap_or_default(),
+                name: pod.name_any(),
+                uid: pod.uid(),
+                state: eviction_state(pod, options),
+            })
+            .collect())
+    }
+
+    /// Evict all pending pods of a drain plan concurrently using the Eviction API
+    ///
+    /// Evictions refused by a PodDisruptionBudget are retried until the timeout. Accepted
+    /// evictions are followed until the pod is gone.
+    ///
+    /// # Arguments
+    ///
+    /// * `pods` - The drain plan from `plan_drain`
+    /// * `options` - Drain options (grace period, timeout, dry run)
+    /// * `on_progress` - Called with the state of all pods whenever one of them changes
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<PodEviction>>` - The final state of all pods
+    #[instrument(skip_all, level = "debug")]
+    pub async fn evict_pods<F>(
+        &self,
+        mut pods: Vec<PodEviction>,
+        options: &DrainOptions,
+        mut on_progress: F,
+    ) -> Result<Vec<PodEviction>>
+    where
+        F: FnMut(&[PodEviction]),
+    {
+        if let Some(pod) = pods
+            .iter()
+            .find(|p| matches!(p.state, EvictionState::Blocked(_)))
+        {
+            return Err(anyhow::anyhow!(
+                "pod {}/{} blocks the drain: {}",
+                pod.namespace,
+                pod.name,
+                pod.state
+            ));
+        }
+
+        let deadline = Instant::now() + options.timeout;
+        let (sender, mut updates) = mpsc::unbounded();
+        let evictions = futures::future::join_all(
+            pods.iter()
+                .enumerate()
+                .filter(|(_, pod)| pod.state == EvictionState::Pending)
+                .map(|(index, pod)| {
+                    let sender = sender.clone();
+                    let report = move |state| {
+                        // The receiver outlives all evictions, so sending cannot fail
+                        let _ = sender.unbounded_send((index, state));
+                    };
+                    self.evict_pod(pod.clone(), options, deadline, report)
+                })
+                .collect::<Vec<_>>(),
+        );
+        drop(sender);
+
+        on_progress(&pods);
+        let (_, pods) = futures::join!(evictions, async move {
+            while let Some((index, state)) = updates.next().await {
+                pods[index].state = state;
+                on_progress(&pods);
+            }
+            pods
+        });
+        Ok(pods)
+    }
+
+    /// Evict a single pod and wait for it to be gone, reporting every state change
+    async fn evict_pod<R>(
+        &self,
+        pod: PodEviction,
+        options: &DrainOptions,
+        deadline: Instant,
+        report: R,
+    ) where
+        R: Fn(EvictionState),
+    {
+        let api: Api<Pod> = Api::namespaced(self.client.clone(), &pod.namespace);
+        let params = EvictParams {
+            delete_options: Some(DeleteParams {
+                grace_period_seconds: options.grace_period,
+                dry_run: options.server_dry_run,
+                ..Default::default()
+            }),
+            post_options: PostParams {
+                dry_run: options.server_dry_run,
+                ..Default::default()
+            },
+        };
+
+        report(EvictionState::Evicting);
+        loop {
+            match api.evict(&pod.name, &params).await {
+                Ok(_) => break,
+                Err(kube::Error::Api(e)) if e.code == 404 => {
+                    return report(EvictionState::Evicted);
+                }
+                Err(kube::Error::Api(e)) if e.code == 429 => {
+                    debug!(pod = %pod.name, message = %e.message, "Eviction refused, retrying");
+                    report(EvictionState::DisruptionBudget);
+                    if Instant::now() + EVICTION_RETRY_INTERVAL > deadline {
+                        return report(EvictionState::Failed(
+                            "timed out waiting for PodDisruptionBudget".to_string(),
+                        ));
+                    }
+                    tokio::time::sleep(EVICTION_RETRY_INTERVAL).await;
+                }
+                Err(e) => return report(EvictionState::Failed(e.to_string())),
+            }
+        }
+
+        if options.server_dry_run {
+            return report(EvictionState::Evicted);
+        }
+
+        report(EvictionState::Terminating);
+        loop {
+            match api.get_opt(&pod.name).await {
+                Ok(None) => return report(EvictionState::Evicted),
+                Ok(Some(current)) if current.uid() != pod.uid => {
+                    return report(EvictionState::Evicted);
+                }
+                Ok(Some(_)) => {}
+                Err(e) => return report(EvictionState::Failed(e.to_string())),
+            }
+            if Instant::now() >= deadline {
+                return report(EvictionState::Failed(
+                    "timed out waiting for termination".to_string(),
+                ));
+            }
+            tokio::time::sleep(DELETION_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn options(force: bool, delete_emptydir_data: bool) -> DrainOptions {
+        DrainOptions {
+            grace_period: None,
+            timeout: Duration::from_secs(60),
+            force,
+            delete_emptydir_data,
+            server_dry_run: false,
+        }
+    }
+
+    /// Build a running pod controlled by an object of `controller_kind`, if any
+    fn pod(controller_kind: Option<&str>, metadata: Value, spec: Value) -> Pod {
+        let mut pod = json!({
+            "metadata": metadata,
+            "spec": spec,
+            "status": {"phase": "Running"}
+        });
+        pod["metadata"]["name"] = json!("app");
+        if let Some(kind) = controller_kind {
+            pod["metadata"]["ownerReferences"] = json!([{
+                "apiVersion": "apps/v1",
+                "kind": kind,
+                "name": "app",
+                "uid": "owner-uid",
+                "controller": true
+            }]);
+        }
+        pod["spec"]["containers"] = json!([{"name": "app", "image": "app:1"}]);
+        serde_json::from_value(pod).unwrap()
+    }
+
+    #[test]
+    fn mirror_pods_are_skipped() {
+        let pod = pod(
+            Some("Node"),
+            json!({"annotations": {MIRROR_POD_ANNOTATION: "abc"}}),
+            json!({}),
+        );
+        assert_eq!(
+            eviction_state(&pod, &options(true, true)),
+            EvictionState::Skipped("mirror pod".to_string())
+        );
+    }
+
+    #[test]
+    fn daemon_set_pods_are_skipped() {
+        let pod = pod(Some("DaemonSet"), json!({}), json!({}));
+        assert_eq!(
+            eviction_state(&pod, &options(false, false)),
+            EvictionState::Skipped("DaemonSet pod".to_string())
+        );
+    }
+
+    #[test]
+    fn unmanaged_pods_need_force() {
+        let pod = pod(None, json!({}), json!({}));
+        assert_eq!(
+            eviction_state(&pod, &options(false, false)),
+            EvictionState::Blocked("not managed by a controller (use --force)".to_string())
+        );
+        assert_eq!(
+            eviction_state(&pod, &options(true, false)),
+            EvictionState::Pending
+        );
+    }
+
+    #[test]
+    fn pods_with_empty_dir_need_delete_emptydir_data() {
+        let pod = pod(
+            Some("ReplicaSet"),
+            json!({}),
+            json!({"volumes": [{"name": "cache", "emptyDir": {}}]}),
+        );
+        assert_eq!(
+            eviction_state(&pod, &options(false, false)),
+            EvictionState::Blocked("uses emptyDir (use --delete-emptydir-data)".to_string())
+        );
+        assert_eq!(
+            eviction_state(&pod, &options(false, true)),
+            EvictionState::Pending
+        );
+    }
+
+    #[test]
+    fn completed_pods_are_skipped() {
+        let mut pod = pod(None, json!({}), json!({}));
+        pod.status.as_mut().unwrap().phase = Some("Succeeded".to_string());
+        assert_eq!(
+            eviction_state(&pod, &options(false, false)),
+            EvictionState::Skipped("completed".to_string())
+        );
+    }
+}
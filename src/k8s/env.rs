@@ -0,0 +1,94 @@
+use super::{FarosPod, K8sClient};
+use anyhow::Result;
+use k8s_openapi::api::core::v1::ConfigMap;
+use kube::Api;
+use std::collections::BTreeMap;
+use tracing::{debug, instrument};
+
+impl K8sClient {
+    /// Resolve environment variables of a pod that are loaded from ConfigMaps
+    ///
+    /// Values referencing a ConfigMap key are replaced by the actual value, and variables bulk
+    /// loaded from ConfigMaps via `envFrom` are added to the container's environment (explicit
+    /// `env` entries take precedence, as they do in Kubernetes). Init, regular and ephemeral
+    /// containers are resolved from the `valueFrom` and `envFrom` sources of their spec. Secrets
+    /// are never resolved and keep their `secret[name:key]` reference. ConfigMaps that cannot be
+    /// read are left as references.
+    ///
+    /// # Arguments
+    ///
+    /// * `pod` - The pod whose environment variables should be resolved
+    #[instrument(skip(self, pod), fields(pod = %pod.name), level = "debug")]
+    pub async fn resolve_pod_env(&self, pod: &mut FarosPod) -> Result<()> {
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), &pod.namespace);
+        let mut config_maps: BTreeMap<String, Option<BTreeMap<String, String>>> = BTreeMap::new();
+
+        let referenced = pod.containers.iter().flat_map(|container| {
+            let key_refs = container
+                .env
+                .iter()
+                .filter_map(|var| var.value_from.as_ref()?.config_map_key_ref.as_ref())
+                .map(|r| r.name.clone());
+            let env_from = container
+                .env_from_sources
+                .iter()
+                .filter_map(|source| source.config_map_ref.as_ref())
+                .map(|r| r.name.clone());
+            key_refs.chain(env_from).collect::<Vec<_>>()
+        });
+        for name in referenced.collect::<Vec<_>>() {
+            if config_maps.contains_key(&name) {
+                continue;
+            }
+            let data = match api.get_opt(&name).await {
+                Ok(config_map) => config_map.map(|cm| cm.data.unwrap_or_default()),
+                Err(e) => {
+                    debug!(config_map = %name, error = ?e, "Failed to read ConfigMap");
+                    None
+                }
+            };
+            config_maps.insert(name, data);
+        }
+
+        for container in &pod.containers {
+            let env_vars = pod
+                .container_env_vars
+                .entry(container.name.clone())
+                .or_default();
+
+            for var in &container.env {
+                let Some(key_ref) = var
+                    .value_from
+                    .as_ref()
+                    .and_then(|v| v.config_map_key_ref.as_ref())
+                else {
+                    continue;
+                };
+                let resolved = config_maps
+                    .get(&key_ref.name)
+                    .and_then(Option::as_ref)
+                    .and_then(|data| data.get(&key_ref.key));
+                if let Some(resolved) = resolved {
+                    env_vars.insert(var.name.clone(), resolved.clone());
+                }
+            }
+
+            for source in &container.env_from_sources {
+                let Some(config_map_ref) = &source.config_map_ref else {
+                    continue;
+                };
+                let Some(Some(data)) = config_maps.get(&config_map_ref.name) else {
+                    continue;
+                };
+                let prefix = source.prefix.as_deref().unwrap_or_default();
+                for (key, value) in data {
+                    env_vars
+                        .entry(format!("{}{}", prefix, key))
+                        .or_insert_with(|| value.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
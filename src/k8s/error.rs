@@ -0,0 +1,316 @@
+use kube::client::AuthError;
+use kube::core::ErrorResponse;
+use regex::Regex;
+use std::error::Error as StdError;
+use std::io::ErrorKind;
+use std::sync::LazyLock;
+use thiserror::Error;
+
+/// Exit code for errors that are not specific to Kubernetes
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Parts of the message the API server sends with a 403 response
+static FORBIDDEN_MESSAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"cannot (\S+) resource "([^"]+)"(?: in API group "([^"]*)")?(?: in the namespace "([^"]+)")?"#,
+    )
+    .expect("valid forbidden pattern")
+});
+
+/// Errors that can occur when interacting with Kubernetes
+///
+/// Each kind of error has its own process exit code (see [`K8sError::exit_code`]).
+#[derive(Debug, Clone, Error)]
+pub enum K8sError {
+    /// Configuration-related errors
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+    /// Connection-related errors
+    #[error("Connection error: {0}")]
+    ConnectionError(String),
+    /// The API server is overloaded or failing (HTTP 429 and 5xx)
+    #[error("Server unavailable: {0}")]
+    ServerUnavailable(String),
+    /// The request or connection timed out
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    /// The TLS connection to the API server could not be established
+    #[error("TLS error: {0}")]
+    Tls(String),
+    /// The credentials were rejected or could not be used (HTTP 401)
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+    /// The credential plugin configured in the kubeconfig failed
+    #[error("Credential plugin failed: {0}")]
+    ExecCredentialFailed(String),
+    /// The user lacks RBAC permission for a request (HTTP 403)
+    #[error("Forbidden: {message}")]
+    Forbidden {
+        /// The denied verb (e.g. list), if reported by the API server
+        verb: Option<String>,
+        /// The denied resource, with its API group (e.g. deployments.apps)
+        resource: Option<String>,
+        /// The namespace of the request, or None for cluster-wide requests
+        namespace: Option<String>,
+        /// The message of the API server
+        message: String,
+    },
+    /// Resource not found errors
+    #[error("Resource not found: {0}")]
+    ResourceNotFound(String),
+    /// API-related errors
+    #[error("API error: {0}")]
+    ApiError(String),
+    /// The user declined to confirm a change, so nothing was changed
+    #[error("Aborted: {0}")]
+    Aborted(String),
+}
+
+impl K8sError {
+    /// Get the process exit code for this kind of error
+    ///
+    /// | Code | Error |
+    /// |------|-------|
+    /// | 3 | Configuration error |
+    /// | 4 | Connection error |
+    /// | 5 | Server unavailable |
+    /// | 6 | Timeout |
+    /// | 7 | TLS error |
+    /// | 8 | Unauthorized |
+    /// | 9 | Credential plugin failed |
+    /// | 10 | Forbidden |
+    /// | 11 | Resource not found |
+    /// | 12 | Other API errors |
+    /// | 13 | Aborted at a confirmation prompt |
+    ///
+    /// Other errors exit with [`EXIT_FAILURE`] and invalid arguments with 2.
+    ///
+    /// # Returns
+    ///
+    /// * `i32` - The exit code
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            K8sError::ConfigError(_) => 3,
+            K8sError::ConnectionError(_) => 4,
+            K8sError::ServerUnavailable(_) => 5,
+            K8sError::Timeout(_) => 6,
+            K8sError::Tls(_) => 7,
+            K8sError::Unauthorized(_) => 8,
+            K8sError::ExecCredentialFailed(_) => 9,
+            K8sError::Forbidden { .. } => 10,
+            K8sError::ResourceNotFound(_) => 11,
+            K8sError::ApiError(_) => 12,
+            K8sError::Aborted(_) => 13,
+        }
+    }
+
+    /// Suggest how to resolve the error
+    ///
+    /// # Returns
+    ///
+    /// * `Option<String>` - The suggestion, if there is one
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            K8sError::ConfigError(_) => "Check the kubeconfig file (--kubeconfig or KUBECONFIG) \
+                 and its current context, or use --in-cluster inside a pod."
+                .to_string(),
+            K8sError::ConnectionError(_) => "Check that the API server address in the kubeconfig \
+                 is reachable from this machine (VPN, proxy, firewall)."
+                .to_string(),
+            K8sError::ServerUnavailable(_) => {
+                "The API server is overloaded or unavailable; try again later, or retry more \
+                 often with --max-retries."
+                    .to_string()
+            }
+            K8sError::Timeout(_) => "The API server did not respond in time; try again, raise \
+                 --request-timeout or --command-timeout, or check the network latency to the \
+                 cluster."
+                .to_string(),
+            K8sError::Tls(_) => "The API server certificate could not be verified. Check \
+                 certificate-authority-data in the kubeconfig and the system clock."
+                .to_string(),
+            K8sError::Unauthorized(_) => "The credentials were rejected or have expired. Log in \
+                 again, e.g. by refreshing the token or fetching new credentials from your \
+                 cloud provider."
+                .to_string(),
+            K8sError::ExecCredentialFailed(_) => "Run the command configured under \
+                 users[].user.exec in the kubeconfig to see its error, and make sure it is \
+                 installed and on the PATH."
+                .to_string(),
+            K8sError::Forbidden {
+                verb: Some(verb),
+                resource: Some(resource),
+                namespace,
+                ..
+            } => {
+                let mut check = format!("kubectl auth can-i {} {}", verb, resource);
+                let scope = match namespace {
+                    Some(namespace) => {
+                        check.push_str(&format!(" -n {}", namespace));
+                        format!("in namespace {}", namespace)
+                    }
+                    None => "cluster-wide".to_string(),
+                };
+                format!(
+                    "Missing RBAC permission to {} {} {}. Ask a cluster administrator for a \
+                     Role or ClusterRole granting it; check with `{}`.",
+                    verb, resource, scope, check
+                )
+            }
+            K8sError::Forbidden { .. } => {
+                "Missing RBAC permission; ask a cluster administrator for access.".to_string()
+            }
+            K8sError::ResourceNotFound(_) => {
+                "Check the name and the namespace (-n, or -A for all namespaces).".to_string()
+            }
+            K8sError::ApiError(_) => return None,
+            K8sError::Aborted(_) => "Answer yes to apply the changes, or pass --yes to skip the \
+                 confirmation."
+                .to_string(),
+        };
+        Some(hint)
+    }
+
+    /// Find the most specific Kubernetes error in the chain of an error
+    ///
+    /// Errors returned by kube, its credential plugins, TLS and the network are mapped to the
+    /// matching kind of K8sError.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error, usually with context added by fakos
+    ///
+    /// # Returns
+    ///
+    /// * `Option<K8sError>` - The Kubernetes error, or None if the error is not related to
+    ///   Kubernetes
+    pub fn find(error: &anyhow::Error) -> Option<K8sError> {
+        error.chain().find_map(classify).or_else(|| {
+            // Errors of kube that carry no more specific cause
+            error
+                .chain()
+                .find_map(|e| e.downcast_ref::<kube::Error>())
+                .map(|e| match e {
+                    kube::Error::InferConfig(_) | kube::Error::InferKubeconfig(_) => {
+                        K8sError::ConfigError(e.to_string())
+                    }
+                    kube::Error::HyperError(_) | kube::Error::Service(_) => {
+                        K8sError::ConnectionError(e.to_string())
+                    }
+                    _ => K8sError::ApiError(e.to_string()),
+                })
+        })
+    }
+}
+
+impl From<&ErrorResponse> for K8sError {
+    fn from(response: &ErrorResponse) -> Self {
+        let message = response.message.clone();
+        match response.code {
+            401 => K8sError::Unauthorized(message),
+            403 => {
+                let captures = FORBIDDEN_MESSAGE.captures(&message);
+                let capture = |i| {
+                    captures
+                        .as_ref()
+                        .and_then(|c| c.get(i))
+                        .map(|m| m.as_str().to_string())
+                };
+                let resource = capture(2).map(|resource| match capture(3) {
+                    Some(group) if !group.is_empty() => format!("{}.{}", resource, group),
+                    _ => resource,
+                });
+                K8sError::Forbidden {
+                    verb: capture(1),
+                    resource,
+                    namespace: capture(4),
+                    message,
+                }
+            }
+            404 => K8sError::ResourceNotFound(message),
+            408 | 504 => K8sError::Timeout(message),
+            429 | 500..=599 => K8sError::ServerUnavailable(message),
+            _ if response.reason == "Timeout" => K8sError::Timeout(message),
+            _ => K8sError::ApiError(format!("{} ({})", message, response.reason)),
+        }
+    }
+}
+
+impl From<kube::Error> for K8sError {
+    fn from(error: kube::Error) -> Self {
+        let error = anyhow::Error::new(error);
+        K8sError::find(&error).unwrap_or_else(|| K8sError::ApiError(error.to_string()))
+    }
+}
+
+/// Check if an error of the HTTP client is likely to go away when the request is retried
+///
+/// # Arguments
+///
+/// * `error` - The error returned for a request
+///
+/// # Returns
+///
+/// * `bool` - True if the connection failed or timed out
+pub(super) fn is_transient(error: &(dyn StdError + 'static)) -> bool {
+    let mut error = Some(error);
+    while let Some(e) = error {
+        if let Some(kind) = classify(e) {
+            return matches!(kind, K8sError::ConnectionError(_) | K8sError::Timeout(_));
+        }
+        error = e.source();
+    }
+    false
+}
+
+/// Map a single error of an error chain to a K8sError, if it identifies the kind of failure
+fn classify(error: &(dyn StdError + 'static)) -> Option<K8sError> {
+    if let Some(error) = error.downcast_ref::<K8sError>() {
+        return Some(error.clone());
+    }
+    if let Some(kube::Error::Api(response)) = error.downcast_ref::<kube::Error>() {
+        return Some(K8sError::from(response));
+    }
+    if let Some(kube::Error::RustlsTls(e)) = error.downcast_ref::<kube::Error>() {
+        return Some(K8sError::Tls(e.to_string()));
+    }
+    if let Some(auth) = error.downcast_ref::<AuthError>() {
+        return Some(match auth {
+            AuthError::ExecPluginFailed
+            | AuthError::AuthExecStart(_)
+            | AuthError::AuthExecRun { .. }
+            | AuthError::AuthExecParse(_)
+            | AuthError::AuthExec(_) => K8sError::ExecCredentialFailed(auth.to_string()),
+            _ => K8sError::Unauthorized(auth.to_string()),
+        });
+    }
+    if error.downcast_ref::<rustls::Error>().is_some() {
+        return Some(K8sError::Tls(error.to_string()));
+    }
+    if error
+        .downcast_ref::<tokio::time::error::Elapsed>()
+        .is_some()
+    {
+        return Some(K8sError::Timeout(error.to_string()));
+    }
+    if let Some(io) = error.downcast_ref::<std::io::Error>() {
+        // The error wrapped by an I/O error is not part of the source chain
+        if let Some(inner) = io.get_ref()
+            && let Some(error) = classify(inner)
+        {
+            return Some(error);
+        }
+        return match io.kind() {
+            ErrorKind::TimedOut => Some(K8sError::Timeout(io.to_string())),
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+            | ErrorKind::AddrNotAvailable => Some(K8sError::ConnectionError(io.to_string())),
+            _ => None,
+        };
+    }
+    None
+}
@@ -0,0 +1,790 @@
+use super::{K8sClient, ResourceRef};
+use anyhow::{Context, Result};
+use futures::{Stream, StreamExt};
+use k8s_openapi::api::apps::v1::ReplicaSet;
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::api::events::v1::Event;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::api::ListParams;
+use kube::runtime::{WatchStreamExt, watcher};
+use kube::{Api, Client};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, instrument, warn};
+
+/// Represents a (possibly deduplicated) Kubernetes event
+#[derive(Debug, Clone)]
+pub struct FarosEvent {
+    /// Namespace of the object the event is about (empty for cluster-scoped objects)
+    pub namespace: String,
+    /// Kind of the object the event is about
+    pub kind: String,
+    /// Name of the object the event is about
+    pub name: String,
+    /// Type of the event (Normal, Warning)
+    pub event_type: String,
+    /// Short machine-readable reason for the event
+    pub reason: String,
+    /// Human-readable description of the most recent occurrence
+    pub message: String,
+    /// Number of times this event has been observed
+    pub count: i32,
+    /// When the event was first observed
+    pub first_seen: Option<DateTime<Utc>>,
+    /// When the event was last observed
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl FarosEvent {
+    /// Check if this is a warning event
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the event type is Warning
+    pub fn is_warning(&self) -> bool {
+        self.event_type == "Warning"
+    }
+
+    /// Get the object the event is about in `kind/name` form
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The object reference
+    pub fn object(&self) -> String {
+        format!("{}/{}", self.kind.to_lowercase(), self.name)
+    }
+}
+
+impl From<&Event> for FarosEvent {
+    fn from(event: &Event) -> Self {
+        let regarding = event.regarding.as_ref();
+        let created = event.metadata.creation_timestamp.as_ref().map(|t| t.0);
+        let event_time = event.event_time.as_ref().map(|t| t.0);
+
+        let first_seen = event
+            .deprecated_first_timestamp
+            .as_ref()
+            .map(|t| t.0)
+            .or(event_time)
+            .or(created);
+        let last_seen = event
+            .series
+            .as_ref()
+            .map(|s| s.last_observed_time.0)
+            .or_else(|| event.deprecated_last_timestamp.as_ref().map(|t| t.0))
+            .or(event_time)
+            .or(created);
+        let count = event
+            .series
+            .as_ref()
+            .map(|s| s.count)
+            .or(event.deprecated_count)
+            .unwrap_or(1);
+
+        Self {
+            namespace: regarding
+                .and_then(|r| r.namespace.clone())
+                .unwrap_or_default(),
+            kind: regarding.and_then(|r| r.kind.clone()).unwrap_or_default(),
+            name: regarding.and_then(|r| r.name.clone()).unwrap_or_default(),
+            event_type: event.type_.clone().unwrap_or_else(|| "Normal".to_string()),
+            reason: event.reason.clone().unwrap_or_default(),
+            message: event.note.clone().unwrap_or_default(),
+            count,
+            first_seen,
+            last_seen,
+        }
+    }
+}
+
+/// Merge events about the same object with the same type and reason into a single entry,
+/// summing their counts, and sort the result into a timeline (oldest first)
+///
+/// # Arguments
+///
+/// * `events` - The events to deduplicate
+///
+/// # Returns
+///
+/// * `Vec<FarosEvent>` - The deduplicated events sorted by last occurrence
+pub(crate) fn dedupe_events(events: Vec<FarosEvent>) -> Vec<FarosEvent> {
+    let mut merged: BTreeMap<EventKey, FarosEvent> = BTreeMap::new();
+
+    for event in events {
+        match merged.get_mut(&event_key(&event)) {
+            Some(existing) => {
+                existing.count += event.count;
+                existing.first_seen = match (existing.first_seen, event.first_seen) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                if event.last_seen >= existing.last_seen {
+                    existing.last_seen = event.last_seen;
+                    existing.message = event.message;
+                }
+            }
+            None => {
+                merged.insert(event_key(&event), event);
+            }
+        }
+    }
+
+    let mut timeline: Vec<FarosEvent> = merged.into_values().collect();
+    timeline.sort_by(|a, b| a.last_seen.cmp(&b.last_seen));
+    timeline
+}
+
+/// Namespace, kind and name of the object, type and reason of an event, by which events are
+/// deduplicated
+type EventKey = (String, String, String, String, String);
+
+fn event_key(event: &FarosEvent) -> EventKey {
+    (
+        event.namespace.clone(),
+        event.kind.clone(),
+        event.name.clone(),
+        event.event_type.clone(),
+        event.reason.clone(),
+    )
+}
+
+/// Set of objects whose events should be shown, keyed by (kind, namespace, name)
+#[derive(Debug, Default)]
+struct EventScope {
+    objects: Option<BTreeSet<(String, String, String)>>,
+}
+
+impl EventScope {
+    fn matches(&self, event: &FarosEvent) -> bool {
+        match &self.objects {
+            None => true,
+            Some(objects) => {
+                // Events about cluster-scoped objects carry no namespace, and events about
+                // node-scheduled pods may come from any namespace, so fall back to kind/name
+                objects.contains(&(
+                    event.kind.clone(),
+                    event.namespace.clone(),
+                    event.name.clone(),
+                )) || objects.contains(&(event.kind.clone(), String::new(), event.name.clone()))
+            }
+        }
+    }
+}
+
+/// Kinds of objects whose owner may be the resource of an `--include-pods` watch
+const OWNED_KINDS: [&str; 3] = ["Pod", "ReplicaSet", "Job"];
+
+/// State of a watch for new events, shared by the items of its stream
+struct EventWatch {
+    client: Client,
+    namespace: String,
+    resource: Option<ResourceRef>,
+    include_pods: bool,
+    scope: EventScope,
+    /// Objects that were not owned by the resource when the scope was last refreshed
+    unrelated: BTreeSet<(String, String, String)>,
+    /// Resource version of each event seen, by UID
+    seen: HashMap<String, String>,
+    /// Resource version of each event listed while the watch (re)initialises, by UID
+    relisted: HashMap<String, String>,
+    /// Last occurrence of the events shown before the watch started
+    listed: HashMap<EventKey, Option<DateTime<Utc>>>,
+    /// Whether the initial list of the watch is done, after which listed events are new
+    initialized: bool,
+}
+
+impl EventWatch {
+    /// Turn a watcher event into a new event to show, if any
+    ///
+    /// Events listed when the watch starts are new only if they occurred after the events shown
+    /// before the watch started. When the watch is re-established (e.g. after the API server
+    /// compacted its history), the events listed again are compared with those seen before, so
+    /// events created or updated while the watch was down are shown too.
+    async fn handle(&mut self, event: watcher::Event<Event>) -> Option<FarosEvent> {
+        let event = match event {
+            watcher::Event::Init => {
+                self.relisted.clear();
+                return None;
+            }
+            watcher::Event::InitApply(event) => {
+                let (uid, version) = event_version(&event);
+                self.relisted.insert(uid.clone(), version.clone());
+                let known = if self.initialized {
+                    self.seen.get(&uid) == Some(&version)
+                } else {
+                    self.was_listed(&FarosEvent::from(&event))
+                };
+                self.seen.insert(uid, version);
+                if known {
+                    return None;
+                }
+                event
+            }
+            watcher::Event::InitDone => {
+                // Forget events deleted while the watch was down
+                self.seen = std::mem::take(&mut self.relisted);
+                self.initialized = true;
+                return None;
+            }
+            watcher::Event::Apply(event) => {
+                let (uid, version) = event_version(&event);
+                self.seen.insert(uid, version);
+                event
+            }
+            watcher::Event::Delete(event) => {
+                self.seen.remove(&event_version(&event).0);
+                return None;
+            }
+        };
+
+        let event = FarosEvent::from(&event);
+        if !self.scope.matches(&event) && self.may_be_owned(&event) {
+            self.refresh_scope(&event).await;
+        }
+        self.scope.matches(&event).then_some(event)
+    }
+
+    /// Check if an event was already shown before the watch started
+    fn was_listed(&self, event: &FarosEvent) -> bool {
+        self.listed
+            .get(&event_key(event))
+            .is_some_and(|last_seen| event.last_seen <= *last_seen)
+    }
+
+    /// Check if an event is about an object that may have been created for the resource since
+    /// the scope was resolved, e.g. a pod of a new ReplicaSet
+    fn may_be_owned(&self, event: &FarosEvent) -> bool {
+        let key = (
+            event.kind.clone(),
+            event.namespace.clone(),
+            event.name.clone(),
+        );
+        self.include_pods
+            && self.scope.objects.is_some()
+            && OWNED_KINDS.contains(&event.kind.as_str())
+            && !self.unrelated.contains(&key)
+    }
+
+    /// Resolve the objects owned by the resource again, remembering the object of the event
+    /// as unrelated if it still is not one of them
+    async fn refresh_scope(&mut self, event: &FarosEvent) {
+        let Some(resource) = &self.resource else {
+            return;
+        };
+        match event_objects(&self.client, &self.namespace, resource, true).await {
+            Ok(objects) => {
+                debug!(
+                    object = %event.object(),
+                    objects = objects.len(),
+                    "Refreshed objects to show events for"
+                );
+                self.scope.objects = Some(objects);
+            }
+            Err(e) => debug!(error = ?e, "Failed to refresh objects to show events for"),
+        }
+        if !self.scope.matches(event) {
+            self.unrelated.insert((
+                event.kind.clone(),
+                event.namespace.clone(),
+                event.name.clone(),
+            ));
+        }
+    }
+}
+
+/// Check if a watch error may go away when the watch is re-established, unlike e.g. missing
+/// permissions
+fn is_transient(error: &watcher::Error) -> bool {
+    match error {
+        watcher::Error::InitialListFailed(kube::Error::Api(response))
+        | watcher::Error::WatchStartFailed(kube::Error::Api(response)) => {
+            !matches!(response.code, 401 | 403 | 404)
+        }
+        _ => true,
+    }
+}
+
+/// Get the UID and resource version of an event, to tell whether it changed
+fn event_version(event: &Event) -> (String, String) {
+    (
+        event.metadata.uid.clone().unwrap_or_default(),
+        event.metadata.resource_version.clone().unwrap_or_default(),
+    )
+}
+
+/// Check if any of the owner references points at one of the given objects
+fn owned_by(
+    owners: Option<&Vec<OwnerReference>>,
+    namespace: &str,
+    objects: &BTreeSet<(String, String, String)>,
+) -> bool {
+    owners.is_some_and(|owners| {
+        owners.iter().any(|o| {
+            objects.contains(&(o.kind.clone(), namespace.to_string(), o.name.clone()))
+                || objects.contains(&(o.kind.clone(), String::new(), o.name.clone()))
+        })
+    })
+}
+
+/// Build the set of objects whose events should be shown for the given resource
+///
+/// # Arguments
+///
+/// * `client` - The client to list the owned objects with
+/// * `namespace` - The namespace the resource lives in
+/// * `resource` - The resource to show events for
+/// * `include_pods` - If true, also include pods (and intermediate ReplicaSets/Jobs) owned by
+///   the resource, or scheduled on it if the resource is a node
+///
+/// # Returns
+///
+/// * `Result<BTreeSet<(String, String, String)>>` - Objects keyed by (kind, namespace, name)
+async fn event_objects(
+    client: &Client,
+    namespace: &str,
+    resource: &ResourceRef,
+    include_pods: bool,
+) -> Result<BTreeSet<(String, String, String)>> {
+    let object_namespace = if resource.is_cluster_scoped() {
+        String::new()
+    } else {
+        namespace.to_string()
+    };
+
+    let mut objects = BTreeSet::new();
+    objects.insert((
+        resource.kind.clone(),
+        object_namespace,
+        resource.name.clone(),
+    ));
+
+    if !include_pods {
+        return Ok(objects);
+    }
+
+    if resource.kind == "Node" {
+        let api: Api<Pod> = Api::all(client.clone());
+        let params = ListParams::default().fields(&format!("spec.nodeName={}", resource.name));
+        let pods = api
+            .list(&params)
+            .await
+            .context("Failed to list pods on node from Kubernetes API")?;
+        for pod in pods.items {
+            objects.insert((
+                "Pod".to_string(),
+                pod.metadata.namespace.unwrap_or_default(),
+                pod.metadata.name.unwrap_or_default(),
+            ));
+        }
+        return Ok(objects);
+    }
+
+    match resource.kind.as_str() {
+        "Deployment" => {
+            let api: Api<ReplicaSet> = Api::namespaced(client.clone(), namespace);
+            let replica_sets = api
+                .list(&ListParams::default())
+                .await
+                .context("Failed to list replicasets from Kubernetes API")?;
+            for rs in replica_sets.items {
+                if owned_by(rs.metadata.owner_references.as_ref(), namespace, &objects) {
+                    let name = rs.metadata.name.unwrap_or_default();
+                    objects.insert(("ReplicaSet".to_string(), namespace.to_string(), name));
+                }
+            }
+        }
+        "CronJob" => {
+            let api: Api<Job> = Api::namespaced(client.clone(), namespace);
+            let jobs = api
+                .list(&ListParams::default())
+                .await
+                .context("Failed to list jobs from Kubernetes API")?;
+            for job in jobs.items {
+                if owned_by(job.metadata.owner_references.as_ref(), namespace, &objects) {
+                    let name = job.metadata.name.unwrap_or_default();
+                    objects.insert(("Job".to_string(), namespace.to_string(), name));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let pods = api
+        .list(&ListParams::default())
+        .await
+        .context("Failed to list pods from Kubernetes API")?;
+    let owners = objects.clone();
+    for pod in pods.items {
+        if owned_by(pod.metadata.owner_references.as_ref(), namespace, &owners) {
+            let name = pod.metadata.name.unwrap_or_default();
+            objects.insert(("Pod".to_string(), namespace.to_string(), name));
+        }
+    }
+
+    debug!(
+        objects = objects.len(),
+        "Resolved objects to show events for"
+    );
+    Ok(objects)
+}
+
+impl K8sClient {
+    /// Resolve the events API and filters for the given query
+    async fn event_query(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&ResourceRef>,
+        include_pods: bool,
+    ) -> Result<(Api<Event>, Option<String>, EventScope)> {
+        let cluster_wide = all_namespaces
+            || resource.is_some_and(|r| r.is_cluster_scoped() || include_pods && r.kind == "Node");
+        let api: Api<Event> = if cluster_wide {
+            Api::all(self.client.clone())
+        } else {
+            Api::namespaced(self.client.clone(), namespace)
+        };
+
+        let Some(resource) = resource else {
+            return Ok((api, None, EventScope::default()));
+        };
+
+        // Narrow down server-side when only a single object is of interest
+        let field_selector = (!include_pods).then(|| {
+            format!(
+                "regarding.kind={},regarding.name={}",
+                resource.kind, resource.name
+            )
+        });
+        let objects = event_objects(&self.client, namespace, resource, include_pods).await?;
+
+        Ok((
+            api,
+            field_selector,
+            EventScope {
+                objects: Some(objects),
+            },
+        ))
+    }
+
+    /// Get events for a resource, deduplicated by reason and sorted into a timeline
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, query events across all namespaces
+    /// * `resource` - Optional resource to restrict events to
+    /// * `include_pods` - If true, also include events of pods owned by the resource
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<FarosEvent>>` - The deduplicated events, oldest first
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_events(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&ResourceRef>,
+        include_pods: bool,
+    ) -> Result<Vec<FarosEvent>> {
+        let (api, field_selector, scope) = self
+            .event_query(namespace, all_namespaces, resource, include_pods)
+            .await?;
+
+        let mut list_params = ListParams::default();
+        if let Some(fields) = &field_selector {
+            list_params = list_params.fields(fields);
+        }
+
+        let event_list = api
+            .list(&list_params)
+            .await
+            .context("Failed to list events from Kubernetes API")?;
+
+        let events = event_list
+            .items
+            .iter()
+            .map(FarosEvent::from)
+            .filter(|event| scope.matches(event))
+            .collect();
+
+        Ok(dedupe_events(events))
+    }
+
+    /// Watch for new events for a resource
+    ///
+    /// Only events that occur after those in `listed` are yielded; use
+    /// [`K8sClient::get_events`] to fetch the existing ones. With `include_pods`, the objects
+    /// owned by the resource are resolved again when an event is about a pod, ReplicaSet or Job
+    /// that is not known yet, so pods created later (e.g. by a rollout) are included.
+    ///
+    /// When the watch fails (e.g. because the API server expired its resource version, the
+    /// connection dropped or no event arrived within the request timeout), the error is logged
+    /// and the watch is re-established with a backoff. Only errors that will not go away (e.g.
+    /// missing permissions) end the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, watch events across all namespaces
+    /// * `resource` - Optional resource to restrict events to
+    /// * `include_pods` - If true, also include events of pods owned by the resource
+    /// * `listed` - The events already shown, e.g. by [`K8sClient::get_events`]
+    ///
+    /// # Returns
+    ///
+    /// * `Result<impl Stream<Item = Result<FarosEvent>>>` - A stream of new events
+    #[instrument(skip(self, listed), level = "debug")]
+    pub async fn watch_events(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&ResourceRef>,
+        include_pods: bool,
+        listed: &[FarosEvent],
+    ) -> Result<impl Stream<Item = Result<FarosEvent>> + use<>> {
+        let (api, field_selector, scope) = self
+            .event_query(namespace, all_namespaces, resource, include_pods)
+            .await?;
+
+        let mut config = watcher::Config::default();
+        if let Some(fields) = &field_selector {
+            config = config.fields(fields);
+        }
+
+        let watch = Arc::new(Mutex::new(EventWatch {
+            client: self.client.clone(),
+            namespace: namespace.to_string(),
+            resource: resource.cloned(),
+            include_pods,
+            scope,
+            unrelated: BTreeSet::new(),
+            seen: HashMap::new(),
+            relisted: HashMap::new(),
+            listed: listed
+                .iter()
+                .map(|event| (event_key(event), event.last_seen))
+                .collect(),
+            initialized: false,
+        }));
+        let stream = watcher(api, config)
+            .default_backoff()
+            .filter_map(move |event| {
+                let watch = watch.clone();
+                async move {
+                    match event {
+                        Ok(event) => watch.lock().await.handle(event).await.map(Ok),
+                        Err(e) if is_transient(&e) => {
+                            warn!(error = %e, "Watch of events failed, reconnecting");
+                            None
+                        }
+                        Err(e) => {
+                            Some(Err(anyhow::Error::new(e).context("Failed to watch events")))
+                        }
+                    }
+                }
+            });
+
+        Ok(stream)
+    }
+
+    /// Attach recent warning events to each of the given pods
+    ///
+    /// # Arguments
+    ///
+    /// * `pods` - The pods to attach events to
+    /// * `namespace` - The namespace the pods were queried from
+    /// * `all_namespaces` - Whether the pods were queried across all namespaces
+    #[instrument(skip(self, pods), level = "debug")]
+    pub async fn attach_pod_events(
+        &self,
+        pods: &mut [super::FarosPod],
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<()> {
+        let all_namespaces = all_namespaces || pods.iter().any(|p| p.namespace != namespace);
+        let warnings = self.list_warnings("Pod", namespace, all_namespaces).await?;
+        for pod in pods.iter_mut() {
+            pod.events = warnings
+                .iter()
+                .filter(|e| e.namespace == pod.namespace && e.name == pod.name)
+                .cloned()
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// Attach recent warning events to each of the given nodes
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The nodes to attach events to
+    #[instrument(skip(self, nodes), level = "debug")]
+    pub async fn attach_node_events(&self, nodes: &mut [super::FarosNode]) -> Result<()> {
+        let warnings = self.list_warnings("Node", "", true).await?;
+        for node in nodes.iter_mut() {
+            node.events = warnings
+                .iter()
+                .filter(|e| e.name == node.name)
+                .cloned()
+                .collect();
+        }
+        Ok(())
+    }
+
+    /// List deduplicated warning events for objects of the given kind
+    async fn list_warnings(
+        &self,
+        kind: &str,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<FarosEvent>> {
+        let api: Api<Event> = if all_namespaces {
+            Api::all(self.client.clone())
+        } else {
+            Api::namespaced(self.client.clone(), namespace)
+        };
+        let list_params =
+            ListParams::default().fields(&format!("type=Warning,regarding.kind={}", kind));
+
+        let event_list = api
+            .list(&list_params)
+            .await
+            .context("Failed to list events from Kubernetes API")?;
+
+        Ok(dedupe_events(
+            event_list.items.iter().map(FarosEvent::from).collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::k8s::FakeApiServer;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::MicroTime;
+    use serde_json::json;
+    use std::path::Path;
+
+    fn watch() -> EventWatch {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/empty");
+        EventWatch {
+            client: FakeApiServer::from_fixture(&fixture)
+                .unwrap()
+                .client()
+                .client,
+            namespace: "default".to_string(),
+            resource: None,
+            include_pods: false,
+            scope: EventScope::default(),
+            unrelated: BTreeSet::new(),
+            seen: HashMap::new(),
+            relisted: HashMap::new(),
+            listed: HashMap::new(),
+            initialized: false,
+        }
+    }
+
+    fn event(uid: &str, version: &str, reason: &str) -> Event {
+        serde_json::from_value(json!({
+            "metadata": {"name": uid, "namespace": "default", "uid": uid, "resourceVersion": version},
+            "eventTime": null,
+            "reason": reason,
+            "regarding": {"kind": "Pod", "name": "web", "namespace": "default"}
+        }))
+        .unwrap()
+    }
+
+    /// Feed watcher events to a watch and get the reasons of the events it yields
+    async fn handle(watch: &mut EventWatch, events: Vec<watcher::Event<Event>>) -> Vec<String> {
+        let mut reasons = Vec::new();
+        for event in events {
+            if let Some(event) = watch.handle(event).await {
+                reasons.push(event.reason);
+            }
+        }
+        reasons
+    }
+
+    #[tokio::test]
+    async fn listed_events_are_only_new_after_the_initial_list() {
+        let mut watch = watch();
+        let scheduled = FarosEvent::from(&event("a", "1", "Scheduled"));
+        watch
+            .listed
+            .insert(event_key(&scheduled), scheduled.last_seen);
+        let initial = handle(
+            &mut watch,
+            vec![
+                watcher::Event::Init,
+                watcher::Event::InitApply(event("a", "1", "Scheduled")),
+                watcher::Event::InitDone,
+                watcher::Event::Apply(event("b", "2", "Pulled")),
+            ],
+        )
+        .await;
+        assert_eq!(initial, ["Pulled"]);
+
+        // The watch is re-established after events were created, updated and deleted
+        let relisted = handle(
+            &mut watch,
+            vec![
+                watcher::Event::Init,
+                watcher::Event::InitApply(event("a", "1", "Scheduled")),
+                watcher::Event::InitApply(event("b", "5", "BackOff")),
+                watcher::Event::InitApply(event("c", "6", "Killing")),
+                watcher::Event::InitDone,
+            ],
+        )
+        .await;
+        assert_eq!(relisted, ["BackOff", "Killing"]);
+        assert_eq!(watch.seen.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn events_listed_before_the_watch_started_are_not_repeated() {
+        let mut watch = watch();
+        let mut pulled = event("a", "1", "Pulled");
+        pulled.event_time = Some(MicroTime("2026-01-01T00:00:00Z".parse().unwrap()));
+        watch.listed = HashMap::from([(
+            event_key(&FarosEvent::from(&pulled)),
+            FarosEvent::from(&pulled).last_seen,
+        )]);
+
+        let mut repeated = event("b", "2", "Pulled");
+        repeated.event_time = Some(MicroTime("2026-01-01T00:05:00Z".parse().unwrap()));
+        let reasons = handle(
+            &mut watch,
+            vec![
+                watcher::Event::Init,
+                watcher::Event::InitApply(pulled),
+                watcher::Event::InitApply(repeated),
+                watcher::Event::InitApply(event("c", "3", "Started")),
+                watcher::Event::InitDone,
+            ],
+        )
+        .await;
+        assert_eq!(reasons, ["Pulled", "Started"]);
+    }
+
+    #[tokio::test]
+    async fn deleted_events_are_forgotten() {
+        let mut watch = watch();
+        let reasons = handle(
+            &mut watch,
+            vec![
+                watcher::Event::Init,
+                watcher::Event::InitDone,
+                watcher::Event::Apply(event("a", "1", "Scheduled")),
+                watcher::Event::Delete(event("a", "2", "Scheduled")),
+            ],
+        )
+        .await;
+        assert_eq!(reasons, ["Scheduled"]);
+        assert!(watch.seen.is_empty());
+    }
+}
@@ -0,0 +1,714 @@
+use super::K8sClient;
+use super::manifests::read_manifest_documents;
+use super::record::RecordLayer;
+use super::selector::{matches_field_selector, matches_label_selector};
+use super::snapshot::{SNAPSHOT_VERSION, Snapshot, SnapshotResource, snapshot_resources};
+use anyhow::{Context, Result, bail};
+use bytes::Bytes;
+use futures::StreamExt;
+use futures::channel::mpsc::{UnboundedSender, unbounded};
+use http::{Method, Request, Response, StatusCode};
+use http_body::Frame;
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Full, StreamBody};
+use k8s_openapi::api::core::v1::Secret;
+use k8s_openapi::chrono::Utc;
+use kube::Client;
+use kube::client::{Body, ClientBuilder};
+use kube::discovery::ApiResource;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+pub(super) type ResponseBody = UnsyncBoxBody<Bytes, Infallible>;
+
+/// How long a watch stays open when the client does not ask for a timeout
+const DEFAULT_WATCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// In-process fake Kubernetes API server that [`K8sClient`] can be pointed at
+///
+/// It serves discovery, get, list and watch requests, with label and field selectors, from
+/// objects held in memory. Snapshots are served read-only; fixtures loaded with
+/// [`FakeApiServer::from_fixture`] can be changed with [`FakeApiServer::apply`] and
+/// [`FakeApiServer::delete`], and the changes are sent to open watches. Requests that would
+/// modify objects are always refused. Fixture servers have no authorization and allow every
+/// SelfSubjectAccessReview.
+#[derive(Clone)]
+pub struct FakeApiServer {
+    state: Arc<Mutex<ServerState>>,
+}
+
+struct ServerState {
+    snapshot: Snapshot,
+    /// Refuse watches, as the objects of a snapshot never change
+    read_only: bool,
+    /// Resource version of the latest change
+    resource_version: u64,
+    /// Position of each object in the items of its resource, by namespace and name
+    positions: Vec<HashMap<ObjectKey, usize>>,
+    watchers: Vec<Watcher>,
+    /// Method and URI of every request received, in order
+    requests: Vec<String>,
+}
+
+/// Namespace (empty for cluster-scoped objects) and name of an object
+type ObjectKey = (String, String);
+
+/// An open watch request
+struct Watcher {
+    group: String,
+    version: String,
+    plural: String,
+    namespace: Option<String>,
+    query: BTreeMap<String, String>,
+    sender: UnboundedSender<Value>,
+}
+
+impl FakeApiServer {
+    /// Serve a snapshot read-only
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The objects to serve
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - The server
+    pub fn from_snapshot(snapshot: Snapshot) -> Self {
+        Self::new(snapshot, true, 0)
+    }
+
+    /// Serve the objects in YAML or JSON fixture files
+    ///
+    /// All resource types fakos reads from a snapshot are served, and Secrets, so listing a type
+    /// without objects in the fixture returns an empty list. Objects get sequential resource
+    /// versions and namespaced objects without a namespace are placed in `default`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A fixture file or a directory of `.yaml`, `.yml` and `.json` files
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - The server, or an error if a fixture contains an unsupported kind
+    #[instrument(level = "debug")]
+    pub fn from_fixture(path: &Path) -> Result<Self> {
+        // Snapshots never hold Secrets, but fixtures may, e.g. to test that they are scrubbed
+        let resources = snapshot_resources()
+            .into_iter()
+            .chain([(ApiResource::erase::<Secret>(&()), true)])
+            .map(|(resource, namespaced)| SnapshotResource {
+                group: resource.group,
+                version: resource.version,
+                kind: resource.kind,
+                plural: resource.plural,
+                namespaced,
+                items: Vec::new(),
+            })
+            .collect();
+        let server = Self::new(
+            Snapshot {
+                version: SNAPSHOT_VERSION,
+                created: Utc::now(),
+                server_version: Some(json!({
+                    "major": "1",
+                    "minor": "32",
+                    "gitVersion": "v1.32.0-fakos",
+                    "gitCommit": "",
+                    "gitTreeState": "clean",
+                    "buildDate": "",
+                    "goVersion": "",
+                    "compiler": "",
+                    "platform": "linux/amd64",
+                })),
+                resources,
+            },
+            false,
+            0,
+        );
+
+        let objects = read_manifest_documents(path)?;
+        debug!(objects = objects.len(), "Loaded fixture");
+        for object in objects {
+            server
+                .apply(object)
+                .with_context(|| format!("Invalid fixture {}", path.display()))?;
+        }
+        Ok(server)
+    }
+
+    fn new(snapshot: Snapshot, read_only: bool, resource_version: u64) -> Self {
+        let positions = snapshot
+            .resources
+            .iter()
+            .map(|resource| {
+                resource
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(|(position, item)| (object_key(item), position))
+                    .collect()
+            })
+            .collect();
+        Self {
+            state: Arc::new(Mutex::new(ServerState {
+                snapshot,
+                read_only,
+                resource_version,
+                positions,
+                watchers: Vec::new(),
+                requests: Vec::new(),
+            })),
+        }
+    }
+
+    /// Create a client that sends all requests to this server
+    ///
+    /// # Returns
+    ///
+    /// * `K8sClient` - A new K8sClient instance backed by the server
+    pub fn client(&self) -> K8sClient {
+        let server = self.clone();
+        let service = tower::service_fn(move |request: Request<Body>| {
+            let server = server.clone();
+            async move { Ok::<_, Infallible>(server.respond(&request)) }
+        });
+
+        K8sClient {
+            client: Client::new(service, "default"),
+            connection: None,
+            stats: Default::default(),
+        }
+    }
+
+    /// Create a client that sends all requests to this server and records them like `--record`
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The new or empty directory to record to
+    ///
+    /// # Returns
+    ///
+    /// * `Result<K8sClient>` - A new K8sClient instance backed by the server, or an error if the
+    ///   directory cannot be used
+    pub fn recording_client(&self, dir: &Path) -> Result<K8sClient> {
+        let server = self.clone();
+        let service = tower::service_fn(move |request: Request<Body>| {
+            let server = server.clone();
+            async move { Ok::<_, Infallible>(server.respond(&request)) }
+        });
+
+        Ok(K8sClient {
+            client: ClientBuilder::new(service, "default")
+                .with_layer(&RecordLayer::new(dir)?)
+                .build(),
+            connection: None,
+            stats: Default::default(),
+        })
+    }
+
+    /// Get a copy of the objects currently served
+    ///
+    /// # Returns
+    ///
+    /// * `Snapshot` - The objects, e.g. to write to a file for `--from-snapshot`
+    pub fn to_snapshot(&self) -> Snapshot {
+        let mut snapshot = self.lock().snapshot.clone();
+        snapshot.created = Utc::now();
+        snapshot
+    }
+
+    /// Create or replace an object and notify open watches
+    ///
+    /// # Arguments
+    ///
+    /// * `object` - The object, with `apiVersion`, `kind` and `metadata.name`
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - An error if the server is read-only or the kind is not served
+    pub fn apply(&self, mut object: Value) -> Result<()> {
+        let mut state = self.lock();
+        if state.read_only {
+            bail!("Snapshots are read-only");
+        }
+        let index = state.resource_index(&object)?;
+        state.resource_version += 1;
+        let resource_version = state.resource_version.to_string();
+
+        let state = &mut *state;
+        let resource = &mut state.snapshot.resources[index];
+        if resource.namespaced && object.pointer("/metadata/namespace").is_none() {
+            object["metadata"]["namespace"] = json!("default");
+        }
+        if object.pointer("/metadata/uid").is_none() {
+            object["metadata"]["uid"] = json!(format!("fakos-{}", resource_version));
+        }
+        object["metadata"]["resourceVersion"] = json!(resource_version);
+
+        let positions = &mut state.positions[index];
+        let event_type = match positions.get(&object_key(&object)) {
+            Some(&position) => {
+                resource.items[position] = object.clone();
+                "MODIFIED"
+            }
+            None => {
+                positions.insert(object_key(&object), resource.items.len());
+                resource.items.push(object.clone());
+                "ADDED"
+            }
+        };
+        state.notify(index, event_type, object);
+        Ok(())
+    }
+
+    /// Delete an object and notify open watches
+    ///
+    /// # Arguments
+    ///
+    /// * `api_version` - API version of the object (e.g. `v1`, `apps/v1`)
+    /// * `kind` - Kind of the object
+    /// * `namespace` - Namespace of the object, or None for cluster-scoped objects
+    /// * `name` - Name of the object
+    ///
+    /// # Returns
+    ///
+    /// * `Result<bool>` - Whether the object existed
+    pub fn delete(
+        &self,
+        api_version: &str,
+        kind: &str,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Result<bool> {
+        let mut state = self.lock();
+        if state.read_only {
+            bail!("Snapshots are read-only");
+        }
+        let index = state.resource_index(&json!({
+            "apiVersion": api_version,
+            "kind": kind,
+            "metadata": { "name": name },
+        }))?;
+        state.resource_version += 1;
+        let resource_version = state.resource_version.to_string();
+
+        let state = &mut *state;
+        let resource = &mut state.snapshot.resources[index];
+        let positions = &mut state.positions[index];
+        let Some(position) =
+            positions.remove(&(namespace.unwrap_or_default().to_string(), name.to_string()))
+        else {
+            return Ok(false);
+        };
+        let mut object = resource.items.swap_remove(position);
+        if let Some(moved) = resource.items.get(position) {
+            positions.insert(object_key(moved), position);
+        }
+        object["metadata"]["resourceVersion"] = json!(resource_version);
+        state.notify(index, "DELETED", object);
+        Ok(true)
+    }
+
+    /// End all open watches with a `410 Gone` error, as the API server does when the resource
+    /// version a watch started from was compacted
+    pub fn expire_watches(&self) {
+        let expired = json!({
+            "type": "ERROR",
+            "object": {
+                "apiVersion": "v1",
+                "kind": "Status",
+                "status": "Failure",
+                "reason": "Expired",
+                "message": "too old resource version",
+                "code": 410,
+            },
+        });
+        for watcher in self.lock().watchers.drain(..) {
+            let _ = watcher.sender.unbounded_send(expired.clone());
+        }
+    }
+
+    /// Get the requests received so far, including refused ones
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The method and URI of each request, in order (e.g. `GET /api/v1/pods`)
+    pub fn requests(&self) -> Vec<String> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ServerState> {
+        // A panic while holding the lock cannot leave the objects half-updated
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn respond(&self, request: &Request<Body>) -> Response<ResponseBody> {
+        let query = parse_query(request.uri().query().unwrap_or_default());
+        debug!(method = %request.method(), uri = %request.uri(), "Fake API server request");
+
+        let mut state = self.lock();
+        state
+            .requests
+            .push(format!("{} {}", request.method(), request.uri()));
+        if request.method() == Method::POST
+            && request.uri().path() == "/apis/authorization.k8s.io/v1/selfsubjectaccessreviews"
+            && !state.read_only
+        {
+            return ok(&json!({
+                "apiVersion": "authorization.k8s.io/v1",
+                "kind": "SelfSubjectAccessReview",
+                "spec": {},
+                "status": { "allowed": true, "reason": "the fake API server allows all requests" },
+            }));
+        }
+        if request.method() != Method::GET {
+            let message = if state.read_only {
+                "snapshots are read-only"
+            } else {
+                "the fake API server only accepts changes through FakeApiServer::apply"
+            };
+            return status(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed", message);
+        }
+        let watch = query.get("watch").is_some_and(|w| w == "true" || w == "1");
+        if watch && state.read_only {
+            return status(
+                StatusCode::METHOD_NOT_ALLOWED,
+                "MethodNotAllowed",
+                "watching is not supported for snapshots",
+            );
+        }
+
+        let segments: Vec<&str> = request
+            .uri()
+            .path()
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+        let (group, version, rest) = match segments.as_slice() {
+            ["version"] => {
+                return match &state.snapshot.server_version {
+                    Some(version) => ok(version),
+                    None => not_found("server version not recorded in snapshot"),
+                };
+            }
+            ["api"] => {
+                return ok(&json!({
+                    "kind": "APIVersions",
+                    "versions": ["v1"],
+                    "serverAddressByClientCIDRs": [],
+                }));
+            }
+            ["apis"] => return ok(&state.group_list()),
+            ["api", version] => return ok(&state.resource_list("", version)),
+            ["apis", group, version] => return ok(&state.resource_list(group, version)),
+            ["api", version, rest @ ..] => ("", *version, rest),
+            ["apis", group, version, rest @ ..] => (*group, *version, rest),
+            _ => return not_found(&format!("{} not found", request.uri().path())),
+        };
+
+        // Paths are either /<plural>[/<name>] or /namespaces/<ns>/<plural>[/<name>], except that
+        // /namespaces[/<name>] refers to the namespaces themselves
+        let (namespace, plural, name, subresource) = match rest {
+            ["namespaces", namespace, plural, tail @ ..] => {
+                (Some(*namespace), *plural, tail.first(), tail.get(1))
+            }
+            [plural, tail @ ..] => (None, *plural, tail.first(), tail.get(1)),
+            [] => return not_found("missing resource"),
+        };
+        if subresource.is_some() {
+            return not_found("subresources are not served");
+        }
+        let Some(index) = state
+            .snapshot
+            .resources
+            .iter()
+            .position(|r| r.group == group && r.version == version && r.plural == plural)
+        else {
+            return not_found(&format!("{} are not included in the snapshot", plural));
+        };
+
+        if watch {
+            return state.watch(index, namespace, &query);
+        }
+
+        let resource = &state.snapshot.resources[index];
+        if let Some(name) = name {
+            return match resource.items.iter().find(|item| {
+                matches_namespace(item, namespace)
+                    && item.pointer("/metadata/name").and_then(Value::as_str) == Some(name)
+            }) {
+                Some(item) => ok(item),
+                None => not_found(&format!("{} \"{}\" not found", plural, name)),
+            };
+        }
+
+        let items: Vec<&Value> = resource
+            .items
+            .iter()
+            .filter(|item| matches_query(item, namespace, &query))
+            .collect();
+        ok(&json!({
+            "apiVersion": resource.api_version(),
+            "kind": format!("{}List", resource.kind),
+            "metadata": { "resourceVersion": state.resource_version.to_string() },
+            "items": items,
+        }))
+    }
+}
+
+impl ServerState {
+    /// Find the served resource of an object from its `apiVersion` and `kind`
+    fn resource_index(&self, object: &Value) -> Result<usize> {
+        let api_version = object["apiVersion"].as_str().unwrap_or_default();
+        let kind = object["kind"].as_str().unwrap_or_default();
+        if object.pointer("/metadata/name").is_none() {
+            bail!("{} without metadata.name", kind);
+        }
+        let Some(index) = self
+            .snapshot
+            .resources
+            .iter()
+            .position(|r| r.kind == kind && r.api_version() == api_version)
+        else {
+            bail!("Unsupported kind {} ({})", kind, api_version);
+        };
+        Ok(index)
+    }
+
+    /// Open a watch, sending changes after the requested resource version
+    fn watch(
+        &mut self,
+        index: usize,
+        namespace: Option<&str>,
+        query: &BTreeMap<String, String>,
+    ) -> Response<ResponseBody> {
+        let resource = &self.snapshot.resources[index];
+        let since: u64 = query
+            .get("resourceVersion")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let timeout = query
+            .get("timeoutSeconds")
+            .and_then(|t| t.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_WATCH_TIMEOUT);
+
+        let (sender, receiver) = unbounded();
+        for item in resource.items.iter().filter(|item| {
+            matches_query(item, namespace, query)
+                && item
+                    .pointer("/metadata/resourceVersion")
+                    .and_then(Value::as_str)
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .is_some_and(|v| v > since)
+        }) {
+            let _ = sender.unbounded_send(json!({ "type": "ADDED", "object": item }));
+        }
+        self.watchers.push(Watcher {
+            group: resource.group.clone(),
+            version: resource.version.clone(),
+            plural: resource.plural.clone(),
+            namespace: namespace.map(str::to_string),
+            query: query.clone(),
+            sender,
+        });
+
+        let frames = receiver
+            .take_until(tokio::time::sleep(timeout))
+            .map(|event| Ok(Frame::data(Bytes::from(format!("{}\n", event)))));
+        let mut response = Response::new(StreamBody::new(frames).boxed_unsync());
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/json"),
+        );
+        response
+    }
+
+    /// Send a change to the open watches of its resource, dropping closed watches
+    fn notify(&mut self, index: usize, event_type: &str, object: Value) {
+        let resource = &self.snapshot.resources[index];
+        let event = json!({ "type": event_type, "object": object });
+        self.watchers.retain(|watcher| {
+            if watcher.group != resource.group
+                || watcher.version != resource.version
+                || watcher.plural != resource.plural
+                || !matches_query(&object, watcher.namespace.as_deref(), &watcher.query)
+            {
+                return !watcher.sender.is_closed();
+            }
+            watcher.sender.unbounded_send(event.clone()).is_ok()
+        });
+    }
+
+    fn group_list(&self) -> Value {
+        let mut groups: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for resource in self
+            .snapshot
+            .resources
+            .iter()
+            .filter(|r| !r.group.is_empty())
+        {
+            let versions = groups.entry(&resource.group).or_default();
+            if !versions.contains(&resource.version.as_str()) {
+                versions.push(&resource.version);
+            }
+        }
+
+        let groups: Vec<Value> = groups
+            .into_iter()
+            .map(|(group, versions)| {
+                let versions: Vec<Value> = versions
+                    .iter()
+                    .map(|v| json!({ "groupVersion": format!("{}/{}", group, v), "version": v }))
+                    .collect();
+                json!({
+                    "name": group,
+                    "versions": versions,
+                    "preferredVersion": versions[0],
+                })
+            })
+            .collect();
+        json!({ "kind": "APIGroupList", "apiVersion": "v1", "groups": groups })
+    }
+
+    fn resource_list(&self, group: &str, version: &str) -> Value {
+        let verbs = if self.read_only {
+            json!(["get", "list"])
+        } else {
+            json!(["get", "list", "watch"])
+        };
+        let resources: Vec<Value> = self
+            .snapshot
+            .resources
+            .iter()
+            .filter(|r| r.group == group && r.version == version)
+            .map(|r| {
+                json!({
+                    "name": r.plural,
+                    "singularName": r.kind.to_lowercase(),
+                    "namespaced": r.namespaced,
+                    "kind": r.kind,
+                    "verbs": verbs,
+                })
+            })
+            .collect();
+        let group_version = if group.is_empty() {
+            version.to_string()
+        } else {
+            format!("{}/{}", group, version)
+        };
+        json!({
+            "kind": "APIResourceList",
+            "apiVersion": "v1",
+            "groupVersion": group_version,
+            "resources": resources,
+        })
+    }
+}
+
+fn object_key(item: &Value) -> ObjectKey {
+    let field = |pointer| {
+        item.pointer(pointer)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    (field("/metadata/namespace"), field("/metadata/name"))
+}
+
+fn matches_namespace(item: &Value, namespace: Option<&str>) -> bool {
+    namespace
+        .is_none_or(|ns| item.pointer("/metadata/namespace").and_then(Value::as_str) == Some(ns))
+}
+
+/// Check an object against the namespace and the label and field selectors of a request
+fn matches_query(item: &Value, namespace: Option<&str>, query: &BTreeMap<String, String>) -> bool {
+    matches_namespace(item, namespace)
+        && query.get("labelSelector").is_none_or(|selector| {
+            let labels = item
+                .pointer("/metadata/labels")
+                .and_then(|l| serde_json::from_value(l.clone()).ok())
+                .unwrap_or_default();
+            matches_label_selector(&labels, selector)
+        })
+        && query
+            .get("fieldSelector")
+            .is_none_or(|selector| matches_field_selector(item, selector))
+}
+
+fn ok(body: &Value) -> Response<ResponseBody> {
+    respond_json(StatusCode::OK, body)
+}
+
+pub(super) fn not_found(message: &str) -> Response<ResponseBody> {
+    status(StatusCode::NOT_FOUND, "NotFound", message)
+}
+
+/// Build a Kubernetes `Status` error response
+fn status(code: StatusCode, reason: &str, message: &str) -> Response<ResponseBody> {
+    respond_json(
+        code,
+        &json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": message,
+            "reason": reason,
+            "code": code.as_u16(),
+        }),
+    )
+}
+
+fn respond_json(code: StatusCode, body: &Value) -> Response<ResponseBody> {
+    let mut response = Response::new(Full::new(Bytes::from(body.to_string())).boxed_unsync());
+    *response.status_mut() = code;
+    response.headers_mut().insert(
+        http::header::CONTENT_TYPE,
+        http::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+/// Parse a URL query string into decoded key/value pairs
+pub(super) fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode a form-urlencoded query component (`+` for spaces, `%XX` escapes)
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let escaped = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match escaped {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
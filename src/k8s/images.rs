@@ -0,0 +1,230 @@
+use super::{ContainerKind, FarosPod};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Reasons a container is waiting for that indicate the image could not be pulled
+const PULL_ERRORS: [&str; 4] = [
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "InvalidImageName",
+    "ErrImageNeverPull",
+];
+
+/// Represents a container image used by one or more pods
+#[derive(Debug, Clone)]
+pub struct FarosImage {
+    /// Image reference as written in the pod specs
+    pub image: String,
+    /// Repository part of the image reference (without tag or digest)
+    pub repository: String,
+    /// Tag of the image reference, if any
+    pub tag: Option<String>,
+    /// Digests the image resolved to on the nodes (from `containerStatuses.imageID`)
+    pub digests: BTreeSet<String>,
+    /// Pods using the image, as namespace/name
+    pub pods: BTreeSet<String>,
+    /// Kinds of containers using the image
+    pub container_kinds: BTreeSet<ContainerKind>,
+    /// Pods where pulling the image failed, as namespace/name
+    pub pull_errors: BTreeSet<String>,
+}
+
+impl FarosImage {
+    /// Check if the image floats with the `latest` tag (explicitly or by omitting the tag)
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the image is not pinned to a specific tag or digest
+    pub fn is_latest(&self) -> bool {
+        !self.image.contains('@') && self.tag.as_deref().is_none_or(|tag| tag == "latest")
+    }
+
+    /// Check if the same image reference resolved to different digests on different pods
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if more than one digest was observed
+    pub fn has_digest_mismatch(&self) -> bool {
+        self.digests.len() > 1
+    }
+}
+
+/// Split an image reference into repository and tag
+///
+/// # Arguments
+///
+/// * `image` - The image reference (e.g. `registry:5000/app:1.2@sha256:...`)
+///
+/// # Returns
+///
+/// * `(String, Option<String>)` - The repository and the tag, if any
+pub(crate) fn split_image(image: &str) -> (String, Option<String>) {
+    let without_digest = image.split('@').next().unwrap_or(image);
+    // A colon after the last slash separates the tag; one before it belongs to a registry port
+    let name_start = without_digest.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match without_digest[name_start..].rfind(':') {
+        Some(i) => (
+            without_digest[..name_start + i].to_string(),
+            Some(without_digest[name_start + i + 1..].to_string()),
+        ),
+        None => (without_digest.to_string(), None),
+    }
+}
+
+/// Extract the digest from an image ID reported by the container runtime
+/// (e.g. `docker-pullable://nginx@sha256:abc` or `sha256:abc`)
+fn digest_from_image_id(image_id: &str) -> Option<String> {
+    image_id
+        .rsplit_once('@')
+        .map(|(_, digest)| digest)
+        .or_else(|| image_id.find("sha256:").map(|i| &image_id[i..]))
+        .map(str::to_string)
+}
+
+/// Aggregate the images of all init, regular and ephemeral containers across pods
+///
+/// # Arguments
+///
+/// * `pods` - The pods to aggregate images for
+///
+/// # Returns
+///
+/// * `Vec<FarosImage>` - One entry per distinct image reference, sorted by reference
+pub fn aggregate_images(pods: &[FarosPod]) -> Vec<FarosImage> {
+    let mut images: BTreeMap<String, FarosImage> = BTreeMap::new();
+
+    for pod in pods {
+        let pod_ref = format!("{}/{}", pod.namespace, pod.name);
+        for container in &pod.containers {
+            let image = images.entry(container.image.clone()).or_insert_with(|| {
+                let (repository, tag) = split_image(&container.image);
+                FarosImage {
+                    image: container.image.clone(),
+                    repository,
+                    tag,
+                    digests: BTreeSet::new(),
+                    pods: BTreeSet::new(),
+                    container_kinds: BTreeSet::new(),
+                    pull_errors: BTreeSet::new(),
+                }
+            });
+
+            image.pods.insert(pod_ref.clone());
+            image.container_kinds.insert(container.kind);
+            if let Some(digest) = container.image_id.as_deref().and_then(digest_from_image_id) {
+                image.digests.insert(digest);
+            }
+            if container
+                .waiting_reason
+                .as_deref()
+                .is_some_and(|reason| PULL_ERRORS.contains(&reason))
+            {
+                image.pull_errors.insert(pod_ref.clone());
+            }
+        }
+    }
+
+    images.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::k8s::ExtractOptions;
+    use k8s_openapi::api::core::v1::Pod;
+    use serde_json::{Value, json};
+
+    /// Build a pod with one container running `image`, in the given container state
+    fn pod(name: &str, image: &str, state: Value) -> FarosPod {
+        let pod: Pod = serde_json::from_value(json!({
+            "metadata": {"name": name, "namespace": "shop"},
+            "spec": {"containers": [{"name": "app", "image": image}]},
+            "status": {"containerStatuses": [{
+                "name": "app",
+                "image": image,
+                "imageID": "",
+                "ready": false,
+                "restartCount": 0,
+                "state": state
+            }]}
+        }))
+        .unwrap();
+        FarosPod::from_pod(&pod, &ExtractOptions::default())
+    }
+
+    #[test]
+    fn tags_are_split_from_the_repository() {
+        assert_eq!(split_image("nginx"), ("nginx".to_string(), None));
+        assert_eq!(
+            split_image("nginx:1.27"),
+            ("nginx".to_string(), Some("1.27".to_string()))
+        );
+        assert_eq!(
+            split_image("ghcr.io/org/app:v2"),
+            ("ghcr.io/org/app".to_string(), Some("v2".to_string()))
+        );
+    }
+
+    #[test]
+    fn registry_ports_are_not_tags() {
+        assert_eq!(
+            split_image("registry:5000/app"),
+            ("registry:5000/app".to_string(), None)
+        );
+        assert_eq!(
+            split_image("registry:5000/team/app:1.2"),
+            (
+                "registry:5000/team/app".to_string(),
+                Some("1.2".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn digests_are_not_part_of_the_tag() {
+        let digest = "sha256:0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+        assert_eq!(
+            split_image(&format!("nginx@{}", digest)),
+            ("nginx".to_string(), None)
+        );
+        assert_eq!(
+            split_image(&format!("registry:5000/app:1.2@{}", digest)),
+            ("registry:5000/app".to_string(), Some("1.2".to_string()))
+        );
+    }
+
+    #[test]
+    fn pull_errors_are_detected_from_the_waiting_reason() {
+        let pods = [
+            pod(
+                "a",
+                "app:1",
+                json!({"waiting": {"reason": "ImagePullBackOff"}}),
+            ),
+            pod(
+                "b",
+                "app:1",
+                json!({"waiting": {"reason": "CrashLoopBackOff"}}),
+            ),
+            pod(
+                "c",
+                "app:1",
+                json!({"terminated": {"exitCode": 1, "reason": "ErrImagePull"}}),
+            ),
+            pod(
+                "d",
+                "app:2",
+                json!({"waiting": {"reason": "ErrImagePull", "message": "not found"}}),
+            ),
+        ];
+        let images = aggregate_images(&pods);
+        assert_eq!(images.len(), 2);
+        assert_eq!(
+            images[0].pull_errors,
+            BTreeSet::from(["shop/a".to_string()])
+        );
+        assert_eq!(
+            images[1].pull_errors,
+            BTreeSet::from(["shop/d".to_string()])
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use super::record::is_watch;
+use super::stats::RequestStats;
+use bytes::Bytes;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use http::{Request, Response};
+use http_body::{Frame, SizeHint};
+use kube::client::Body;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, ready};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower::buffer::Buffer;
+use tower::{BoxError, Layer, Service, ServiceExt};
+use tracing::debug;
+
+/// Requests that are queued for the HTTP client while it is busy
+const BUFFER_SIZE: usize = 1024;
+
+/// Waits shorter than this are not reported as queueing
+const QUEUE_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// How fast and how many requests at once a client may send to the API server
+///
+/// Like `--qps` and `--burst` of kubectl, requests are limited by a token bucket: up to `burst`
+/// requests are sent at once, after which requests are sent at `qps` per second. Retries count
+/// as requests too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    /// Requests per second, on average; 0 disables the limit
+    pub qps: f32,
+    /// Requests that may be sent at once, before `qps` applies
+    pub burst: u32,
+    /// Requests in flight at the same time, until their response body is read (watches until
+    /// their response starts); 0 disables the limit
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            qps: 50.0,
+            burst: 100,
+            max_concurrent_requests: 32,
+        }
+    }
+}
+
+/// Token bucket for the QPS limit
+#[derive(Debug)]
+struct Bucket {
+    /// Tokens left, negative if requests have reserved tokens that are not available yet
+    tokens: f64,
+    /// When the tokens were last refilled
+    updated: Instant,
+}
+
+/// Limiter shared by all requests of a client
+#[derive(Debug)]
+struct Limiter {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl Limiter {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(limit.burst.max(1)),
+                updated: Instant::now(),
+            }),
+            in_flight: (limit.max_concurrent_requests > 0)
+                .then(|| Arc::new(Semaphore::new(limit.max_concurrent_requests))),
+            limit,
+        }
+    }
+
+    /// Take a token from the bucket
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - How long to wait until the token is available
+    fn reserve(&self) -> Duration {
+        if self.limit.qps <= 0.0 {
+            return Duration::ZERO;
+        }
+        let qps = f64::from(self.limit.qps);
+        let burst = f64::from(self.limit.burst.max(1));
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * qps;
+        bucket.tokens = (bucket.tokens + refill).min(burst) - 1.0;
+        bucket.updated = now;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / qps)
+        }
+    }
+
+    /// Wait until a request may be sent
+    ///
+    /// # Returns
+    ///
+    /// * `Option<OwnedSemaphorePermit>` - The slot of the request among the concurrent requests,
+    ///   to hold until its response is read, or None if concurrency is not limited
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let delay = self.reserve();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        match &self.in_flight {
+            // The semaphore is never closed
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+}
+
+/// Tower layer limiting the rate and concurrency of API requests according to a [`RateLimit`]
+///
+/// The layer also lets the requests of a client share the HTTP client, so it goes directly
+/// around it, below the layers that send a request more than once.
+#[derive(Clone)]
+pub(super) struct RateLimitLayer {
+    limiter: Arc<Limiter>,
+    stats: Arc<RequestStats>,
+}
+
+impl RateLimitLayer {
+    /// Create a layer that counts the time requests waited in `stats`
+    pub(super) fn new(limit: RateLimit, stats: Arc<RequestStats>) -> Self {
+        Self {
+            limiter: Arc::new(Limiter::new(limit)),
+            stats,
+        }
+    }
+}
+
+impl<S, B> Layer<S> for RateLimitLayer
+where
+    S: Service<Request<Body>, Response = Response<B>> + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError> + Send + Sync,
+    B: Send + 'static,
+{
+    type Service = RateLimitService<Buffer<Request<Body>, S::Future>>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        // Requests wait for the limiter in their own task, then need their own handle to the
+        // client, so the client is shared through a buffer
+        RateLimitService {
+            inner: Buffer::new(inner, BUFFER_SIZE),
+            limiter: self.limiter.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// Service limiting the requests of the HTTP client of kube
+#[derive(Clone)]
+pub(super) struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<Limiter>,
+    stats: Arc<RequestStats>,
+}
+
+impl<S, B> Service<Request<Body>> for RateLimitService<S>
+where
+    S: Service<Request<Body>, Response = Response<B>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send,
+    B: Send + 'static,
+{
+    type Response = Response<LimitedBody<B>>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        // Requests wait for the limiter and the client when they are sent
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let inner = self.inner.clone();
+        let limiter = self.limiter.clone();
+        let stats = self.stats.clone();
+        async move {
+            let queued = Instant::now();
+            let permit = limiter.acquire().await;
+            let waited = queued.elapsed();
+            if waited >= QUEUE_THRESHOLD {
+                debug!(
+                    method = %request.method(),
+                    uri = %request.uri(),
+                    queued = ?waited,
+                    "API request waited for the client rate limit"
+                );
+                stats.record_queued(waited);
+            }
+            // A watch stays open for as long as the command runs, so it only counts until its
+            // response starts, while list bodies may take long to stream
+            let watch = is_watch(request.uri());
+            let response = inner.oneshot(request).await?;
+            let permit = if watch { None } else { permit };
+            Ok(response.map(|inner| LimitedBody { inner, permit }))
+        }
+        .boxed()
+    }
+}
+
+/// Response body that holds the slot of its request among the concurrent requests
+///
+/// The slot is released when the body ends, fails or is dropped.
+pub(super) struct LimitedBody<B> {
+    inner: B,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<B> http_body::Body for LimitedBody<B>
+where
+    B: http_body::Body<Data = Bytes> + Unpin,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = &mut *self;
+        let frame = ready!(Pin::new(&mut this.inner).poll_frame(cx));
+        if !matches!(frame, Some(Ok(_))) {
+            this.permit = None;
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::{BodyExt, Full};
+
+    /// A client that answers every request right away, sending one request at a time
+    fn one_at_a_time() -> impl Service<
+        Request<Body>,
+        Response = Response<LimitedBody<Full<Bytes>>>,
+        Error = BoxError,
+        Future: Send,
+    > + Clone {
+        let limit = RateLimit {
+            qps: 0.0,
+            burst: 1,
+            max_concurrent_requests: 1,
+        };
+        let inner = tower::service_fn(|_: Request<Body>| async {
+            Ok::<_, BoxError>(Response::new(Full::new(Bytes::from_static(b"{}"))))
+        });
+        RateLimitLayer::new(limit, Arc::new(RequestStats::default())).layer(inner)
+    }
+
+    fn request(uri: &str) -> Request<Body> {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_hold_their_slot_until_the_body_is_read() {
+        let service = one_at_a_time();
+        let first = service
+            .clone()
+            .oneshot(request("/api/v1/pods"))
+            .await
+            .unwrap();
+        let second = tokio::spawn(service.oneshot(request("/api/v1/nodes")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!second.is_finished());
+
+        first.into_body().collect().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(5), second)
+            .await
+            .expect("request still waiting after the first body was read")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn watches_release_their_slot_when_the_response_starts() {
+        let service = one_at_a_time();
+        let _watch = service
+            .clone()
+            .oneshot(request("/api/v1/pods?watch=true"))
+            .await
+            .unwrap();
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            service.oneshot(request("/api/v1/pods")),
+        )
+        .await
+        .expect("request waiting for an open watch")
+        .unwrap();
+    }
+}
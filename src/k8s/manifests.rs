@@ -0,0 +1,188 @@
+use super::FarosPod;
+use anyhow::{Context, Result, bail};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::Job;
+use k8s_openapi::api::core::v1::{Pod, PodTemplateSpec};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use k8s_openapi::{Metadata, Resource};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument};
+
+/// Parse a workload manifest and build the pod its template describes
+///
+/// The pod is named after the workload and owned by it, so it is displayed like a pod controlled
+/// by the workload.
+fn template_pod<K>(value: Value, template: impl Fn(&K) -> Option<&PodTemplateSpec>) -> Result<Pod>
+where
+    K: Resource + Metadata<Ty = ObjectMeta> + DeserializeOwned,
+{
+    let workload: K = serde_json::from_value(value)
+        .with_context(|| format!("Failed to parse {} manifest", K::KIND))?;
+    let meta = workload.metadata();
+    let template = template(&workload).cloned().unwrap_or_default();
+    let template_meta = template.metadata.unwrap_or_default();
+
+    Ok(Pod {
+        metadata: ObjectMeta {
+            name: meta.name.clone(),
+            namespace: meta.namespace.clone(),
+            labels: template_meta.labels,
+            annotations: template_meta.annotations,
+            owner_references: Some(vec![OwnerReference {
+                api_version: K::API_VERSION.to_string(),
+                kind: K::KIND.to_string(),
+                name: meta.name.clone().unwrap_or_default(),
+                controller: Some(true),
+                ..Default::default()
+            }]),
+            ..Default::default()
+        },
+        spec: template.spec,
+        status: None,
+    })
+}
+
+/// Build the pod described by a manifest document, if it describes one
+fn document_pod(value: Value) -> Result<Option<Pod>> {
+    let kind = value["kind"].as_str().unwrap_or_default().to_string();
+    let pod = match kind.as_str() {
+        "Pod" => serde_json::from_value(value).context("Failed to parse Pod manifest")?,
+        "Deployment" => {
+            template_pod::<Deployment>(value, |d| d.spec.as_ref().map(|s| &s.template))?
+        }
+        "StatefulSet" => {
+            template_pod::<StatefulSet>(value, |s| s.spec.as_ref().map(|s| &s.template))?
+        }
+        "DaemonSet" => template_pod::<DaemonSet>(value, |d| d.spec.as_ref().map(|s| &s.template))?,
+        "Job" => template_pod::<Job>(value, |j| j.spec.as_ref().map(|s| &s.template))?,
+        kind => {
+            debug!(kind = %kind, "Skipping manifest without pods");
+            return Ok(None);
+        }
+    };
+    Ok(Some(pod))
+}
+
+/// Add a manifest document, expanding `List` kinds into their items
+fn push_document(value: Value, documents: &mut Vec<Value>) {
+    if value["kind"]
+        .as_str()
+        .is_some_and(|kind| kind.ends_with("List"))
+        && let Value::Array(items) = value["items"].clone()
+    {
+        for item in items {
+            push_document(item, documents);
+        }
+    } else {
+        documents.push(value);
+    }
+}
+
+/// Parse all YAML or JSON documents of a manifest
+fn parse_manifest(content: &str, source: &str, documents: &mut Vec<Value>) -> Result<()> {
+    for document in serde_yaml::Deserializer::from_str(content) {
+        let value = Value::deserialize(document)
+            .with_context(|| format!("Failed to parse manifest {}", source))?;
+        if !value.is_null() {
+            push_document(value, documents);
+        }
+    }
+    Ok(())
+}
+
+/// Find the manifest files in a directory and its subdirectories, sorted by path
+fn manifest_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(manifest_files(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "yaml" || ext == "yml" || ext == "json")
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read all objects from manifest files
+///
+/// Multi-document YAML files are split and `List` kinds are expanded into their items.
+///
+/// # Arguments
+///
+/// * `path` - A manifest file, a directory of `.yaml`, `.yml` and `.json` files, or `-` for stdin
+///
+/// # Returns
+///
+/// * `Result<Vec<Value>>` - The objects, in file order
+pub(crate) fn read_manifest_documents(path: &Path) -> Result<Vec<Value>> {
+    let mut documents = Vec::new();
+    if path == Path::new("-") {
+        let mut content = String::new();
+        std::io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read manifests from stdin")?;
+        parse_manifest(&content, "<stdin>", &mut documents)?;
+    } else if path.is_dir() {
+        for file in manifest_files(path)? {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            parse_manifest(&content, &file.display().to_string(), &mut documents)?;
+        }
+    } else if path.exists() {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        parse_manifest(&content, &path.display().to_string(), &mut documents)?;
+    } else {
+        bail!("Manifest path {} does not exist", path.display());
+    }
+    Ok(documents)
+}
+
+/// Read pods from manifest files instead of the cluster
+///
+/// Pod, Deployment, StatefulSet, DaemonSet and Job manifests are read, including multi-document
+/// YAML files and `List` kinds. Workloads are represented by the pod their template describes.
+/// Other kinds are ignored.
+///
+/// # Arguments
+///
+/// * `path` - A manifest file, a directory of `.yaml`, `.yml` and `.json` files, or `-` for stdin
+/// * `default_namespace` - Namespace of manifests that do not specify one
+///
+/// # Returns
+///
+/// * `Result<Vec<FarosPod>>` - The pods described by the manifests
+#[instrument(level = "debug")]
+pub fn read_manifest_pods(path: &Path, default_namespace: &str) -> Result<Vec<FarosPod>> {
+    let mut pods = Vec::new();
+    for document in read_manifest_documents(path)? {
+        let name = format!(
+            "{}/{}",
+            document["kind"].as_str().unwrap_or_default(),
+            document
+                .pointer("/metadata/name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        );
+        if let Some(mut pod) =
+            document_pod(document).with_context(|| format!("Invalid manifest {}", name))?
+        {
+            pod.metadata
+                .namespace
+                .get_or_insert_with(|| default_namespace.to_string());
+            pods.push(FarosPod::from(&pod));
+        }
+    }
+    Ok(pods)
+}
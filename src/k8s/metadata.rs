@@ -0,0 +1,298 @@
+use super::K8sClient;
+use anyhow::{Context, Result};
+use kube::api::{DynamicObject, ListParams, Patch, PatchParams};
+use kube::discovery::Scope;
+use kube::{Api, ResourceExt};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+/// Identity and metadata of an object of any kind
+#[derive(Debug, Clone)]
+pub struct FarosObjectMeta {
+    /// Kind of the object
+    pub kind: String,
+    /// Namespace of the object, or None for cluster-scoped objects
+    pub namespace: Option<String>,
+    /// Name of the object
+    pub name: String,
+    /// Labels attached to the object
+    pub labels: BTreeMap<String, String>,
+    /// Annotations attached to the object
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Metadata maps that can be updated in bulk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataField {
+    /// `metadata.labels`
+    Labels,
+    /// `metadata.annotations`
+    Annotations,
+}
+
+impl MetadataField {
+    /// Get the current values of this field from an object
+    pub fn values<'a>(&self, object: &'a FarosObjectMeta) -> &'a BTreeMap<String, String> {
+        match self {
+            MetadataField::Labels => &object.labels,
+            MetadataField::Annotations => &object.annotations,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            MetadataField::Labels => "labels",
+            MetadataField::Annotations => "annotations",
+        }
+    }
+}
+
+/// A single change to a label or annotation, as given on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataChange {
+    /// Add or overwrite a key (`key=value`)
+    Set(String, String),
+    /// Remove a key (`key-`)
+    Remove(String),
+}
+
+impl std::str::FromStr for MetadataChange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((key, value)) = s.split_once('=') {
+            if key.is_empty() {
+                return Err(format!("missing key in '{}'", s));
+            }
+            return Ok(MetadataChange::Set(key.to_string(), value.to_string()));
+        }
+        match s.strip_suffix('-') {
+            Some(key) if !key.is_empty() => Ok(MetadataChange::Remove(key.to_string())),
+            _ => Err(format!("expected KEY=VALUE or KEY-, got '{}'", s)),
+        }
+    }
+}
+
+/// Compute the values of a label or annotation map after applying changes
+///
+/// Changing the value of an existing key is refused unless `overwrite` is set, like
+/// `kubectl label`. Removing a key that is not set is not an error.
+///
+/// # Arguments
+///
+/// * `current` - The current values
+/// * `changes` - The changes to apply, in order
+/// * `overwrite` - Whether existing keys may be given a different value
+///
+/// # Returns
+///
+/// * `Result<BTreeMap<String, String>, String>` - The new values, or the key that may not be
+///   overwritten
+pub fn plan_metadata_changes(
+    current: &BTreeMap<String, String>,
+    changes: &[MetadataChange],
+    overwrite: bool,
+) -> std::result::Result<BTreeMap<String, String>, String> {
+    let mut updated = current.clone();
+    for change in changes {
+        match change {
+            MetadataChange::Set(key, value) => {
+                if !overwrite && current.get(key).is_some_and(|existing| existing != value) {
+                    return Err(format!(
+                        "'{}' already has a value ({}), and --overwrite is false",
+                        key, current[key]
+                    ));
+                }
+                updated.insert(key.clone(), value.clone());
+            }
+            MetadataChange::Remove(key) => {
+                updated.remove(key);
+            }
+        }
+    }
+    Ok(updated)
+}
+
+impl K8sClient {
+    /// List the metadata of all objects of a kind
+    ///
+    /// Only object metadata is requested from the API server, so this is cheap even for kinds
+    /// with large specs.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind to list (well-known kinds, custom resources, or their plurals)
+    /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true or the kind is
+    ///   cluster-scoped)
+    /// * `all_namespaces` - If true, list objects across all namespaces
+    /// * `label_selector` - Optional label selector to filter objects by (e.g. `pool=gpu,!spot`)
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<FarosObjectMeta>>` - The metadata of the objects
+    #[instrument(skip(self), level = "debug")]
+    pub async fn list_object_metadata(
+        &self,
+        kind: &str,
+        namespace: &str,
+        all_namespaces: bool,
+        label_selector: Option<&str>,
+    ) -> Result<Vec<FarosObjectMeta>> {
+        let (resource, scope) = self.resolve_api_resource(kind).await?;
+        let api: Api<DynamicObject> = if matches!(scope, Scope::Cluster) || all_namespaces {
+            Api::all_with(self.client.clone(), &resource)
+        } else {
+            Api::namespaced_with(self.client.clone(), namespace, &resource)
+        };
+
+        let mut list_params = ListParams::default();
+        if let Some(selector) = label_selector {
+            list_params = list_params.labels(selector);
+        }
+
+        let objects = api
+            .list_metadata(&list_params)
+            .await
+            .with_context(|| format!("Failed to list {} from Kubernetes API", resource.plural))?;
+
+        Ok(objects
+            .items
+            .into_iter()
+            .map(|object| FarosObjectMeta {
+                kind: resource.kind.clone(),
+                namespace: object.namespace(),
+                name: object.name_any(),
+                labels: object.metadata.labels.unwrap_or_default(),
+                annotations: object.metadata.annotations.unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Update the labels or annotations of an object with a JSON merge patch
+    ///
+    /// Only the keys that differ between `before` and `after` are sent, so concurrent changes to
+    /// other keys are preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `object` - The object to update
+    /// * `field` - Whether to update labels or annotations
+    /// * `after` - The desired values of the field
+    /// * `server_dry_run` - If true, the API server validates the patch without persisting it
+    ///
+    /// # Returns
+    ///
+    /// * `Result<BTreeMap<String, String>>` - The values of the field returned by the API server
+    #[instrument(skip(self, object, after), fields(object = %object.name), level = "debug")]
+    pub async fn patch_metadata(
+        &self,
+        object: &FarosObjectMeta,
+        field: MetadataField,
+        after: &BTreeMap<String, String>,
+        server_dry_run: bool,
+    ) -> Result<BTreeMap<String, String>> {
+        let before = field.values(object);
+        let mut changes = Map::new();
+        for (key, value) in after {
+            if before.get(key) != Some(value) {
+                changes.insert(key.clone(), Value::String(value.clone()));
+            }
+        }
+        for key in before.keys().filter(|key| !after.contains_key(*key)) {
+            // A null value removes the key in a JSON merge patch
+            changes.insert(key.clone(), Value::Null);
+        }
+
+        let (resource, _) = self.resolve_api_resource(&object.kind).await?;
+        let api: Api<DynamicObject> = match &object.namespace {
+            Some(namespace) => Api::namespaced_with(self.client.clone(), namespace, &resource),
+            None => Api::all_with(self.client.clone(), &resource),
+        };
+
+        let params = PatchParams {
+            dry_run: server_dry_run,
+            ..Default::default()
+        };
+        let patch = json!({ "metadata": { field.key(): changes } });
+        let patched = api
+            .patch(&object.name, &params, &Patch::Merge(&patch))
+            .await
+            .with_context(|| format!("Failed to patch {}/{}", object.kind, object.name))?;
+
+        Ok(match field {
+            MetadataField::Labels => patched.metadata.labels,
+            MetadataField::Annotations => patched.metadata.annotations,
+        }
+        .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn changes(args: &[&str]) -> Vec<MetadataChange> {
+        args.iter().map(|arg| arg.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn changing_an_existing_value_requires_overwrite() {
+        let current = values(&[("team", "shop"), ("tier", "web")]);
+
+        let error =
+            plan_metadata_changes(&current, &changes(&["team=payments"]), false).unwrap_err();
+        assert_eq!(
+            error,
+            "'team' already has a value (shop), and --overwrite is false"
+        );
+
+        assert_eq!(
+            plan_metadata_changes(&current, &changes(&["team=payments"]), true).unwrap(),
+            values(&[("team", "payments"), ("tier", "web")])
+        );
+    }
+
+    #[test]
+    fn setting_the_same_value_or_new_keys_is_not_a_conflict() {
+        let current = values(&[("team", "shop")]);
+        assert_eq!(
+            plan_metadata_changes(&current, &changes(&["team=shop", "env=prod"]), false).unwrap(),
+            values(&[("env", "prod"), ("team", "shop")])
+        );
+    }
+
+    #[test]
+    fn removing_keys_never_conflicts() {
+        let current = values(&[("team", "shop"), ("tier", "web")]);
+        assert_eq!(
+            plan_metadata_changes(&current, &changes(&["tier-", "missing-"]), false).unwrap(),
+            values(&[("team", "shop")])
+        );
+    }
+
+    #[test]
+    fn changes_are_parsed_like_kubectl() {
+        assert_eq!(
+            "url=http://a=b".parse(),
+            Ok(MetadataChange::Set(
+                "url".to_string(),
+                "http://a=b".to_string()
+            ))
+        );
+        assert_eq!(
+            "team-".parse(),
+            Ok(MetadataChange::Remove("team".to_string()))
+        );
+        assert!("=value".parse::<MetadataChange>().is_err());
+        assert!("team".parse::<MetadataChange>().is_err());
+        assert!("-".parse::<MetadataChange>().is_err());
+    }
+}
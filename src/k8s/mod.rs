@@ -1,9 +1,63 @@
 use anyhow::{Context, Result};
-use k8s_openapi::api::core::v1::{Node, Pod};
+use cluster::ConnectionInfo;
+use k8s_openapi::api::core::v1::{Namespace, Node, Pod};
 use kube::api::ListParams;
-use kube::{Api, Client};
-use thiserror::Error;
-use tracing::{debug, error, info, instrument};
+use kube::client::ClientBuilder;
+use kube::config::{KubeConfigOptions, Kubeconfig};
+use kube::{Api, Client, Config};
+use limit::RateLimitLayer;
+use record::RecordLayer;
+use retry::RetryLayer;
+use stats::RequestStats;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, info, instrument};
+
+mod access;
+mod cluster;
+mod convert;
+mod details;
+mod drain;
+mod env;
+mod error;
+mod events;
+mod fake;
+mod images;
+mod limit;
+mod manifests;
+mod metadata;
+mod options;
+mod owners;
+pub(crate) mod quantity;
+mod record;
+mod retry;
+mod selector;
+mod snapshot;
+mod source;
+mod stats;
+mod workloads;
+
+pub use access::{FarosAccess, FarosPermission, required_permissions, review_access};
+pub use cluster::FarosClusterInfo;
+pub use convert::ExtractOptions;
+pub use details::{ContainerKind, FarosCondition, FarosContainer, FarosOwner};
+pub use drain::{DrainOptions, EvictionObserver, EvictionState, PodEviction};
+pub use error::{EXIT_FAILURE, K8sError};
+pub use events::FarosEvent;
+pub use fake::FakeApiServer;
+pub use images::{FarosImage, aggregate_images};
+pub use limit::RateLimit;
+pub use manifests::read_manifest_pods;
+pub use metadata::{FarosObjectMeta, MetadataChange, MetadataField, plan_metadata_changes};
+pub use options::ClientOptions;
+pub use owners::FarosTreeNode;
+pub(crate) use record::REDACTED;
+pub use retry::RetryPolicy;
+pub use snapshot::{Snapshot, SnapshotResource};
+pub use source::ClusterSource;
+#[cfg(feature = "mock")]
+pub use source::MockClusterSource;
+pub use workloads::FarosWorkload;
 
 /// Represents a running Kubernetes pod
 #[derive(Debug, Clone)]
@@ -21,6 +75,35 @@ pub struct FarosPod {
     /// Environment variables for each container (Container Name -> (Env Var Name -> Env Var Value))
     pub container_env_vars:
         std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>,
+    /// Phase of the pod (Pending, Running, Succeeded, Failed, Unknown)
+    pub phase: String,
+    /// IP address allocated to the pod
+    pub pod_ip: Option<String>,
+    /// Service account the pod runs as
+    pub service_account: Option<String>,
+    /// Quality of service class of the pod
+    pub qos_class: Option<String>,
+    /// When the pod was created
+    pub created: Option<k8s_openapi::chrono::DateTime<k8s_openapi::chrono::Utc>>,
+    /// Node selector of the pod
+    pub node_selector: std::collections::BTreeMap<String, String>,
+    /// Workload that ultimately controls the pod (e.g. Deployment/my-app)
+    pub controller: Option<FarosOwner>,
+    /// Controller chain of the pod, starting with its direct controller (only the direct
+    /// controller unless resolved with [`K8sClient::resolve_owner_chain`])
+    pub owner_chain: Vec<FarosOwner>,
+    /// Init, regular and ephemeral containers of the pod
+    pub containers: Vec<FarosContainer>,
+    /// Conditions of the pod
+    pub conditions: Vec<FarosCondition>,
+    /// Tolerations of the pod
+    pub tolerations: Vec<String>,
+    /// Node and pod (anti-)affinity rules of the pod
+    pub affinity: Vec<String>,
+    /// Volumes of the pod
+    pub volumes: Vec<String>,
+    /// Recent events for the pod (only populated when requested)
+    pub events: Vec<FarosEvent>,
 }
 
 /// Represents a Kubernetes node
@@ -34,29 +117,203 @@ pub struct FarosNode {
     pub annotations: std::collections::BTreeMap<String, String>,
     /// Status of the node (Ready, NotReady, etc.)
     pub status: String,
+    /// Whether the node is cordoned
+    pub unschedulable: bool,
+    /// When the node was created
+    pub created: Option<k8s_openapi::chrono::DateTime<k8s_openapi::chrono::Utc>>,
+    /// Addresses of the node (e.g. InternalIP: 10.0.0.1)
+    pub addresses: Vec<String>,
+    /// Taints of the node (e.g. dedicated=gpu:NoSchedule)
+    pub taints: Vec<String>,
+    /// Total resources of the node (Resource Name -> Quantity)
+    pub capacity: std::collections::BTreeMap<String, String>,
+    /// Resources of the node available for pods (Resource Name -> Quantity)
+    pub allocatable: std::collections::BTreeMap<String, String>,
+    /// System information reported by the kubelet (e.g. Kubelet Version, OS Image)
+    pub system_info: Vec<(String, String)>,
+    /// Conditions of the node
+    pub conditions: Vec<FarosCondition>,
+    /// Recent events for the node (only populated when requested)
+    pub events: Vec<FarosEvent>,
 }
 
-/// Errors that can occur when interacting with Kubernetes
-#[derive(Debug, Error)]
-pub enum K8sError {
-    /// Configuration-related errors
-    #[error("Configuration error: {0}")]
-    ConfigError(String),
-    /// Connection-related errors
-    #[error("Connection error: {0}")]
-    ConnectionError(String),
-    /// API-related errors
-    #[error("API error: {0}")]
-    ApiError(String),
-    /// Resource not found errors
-    #[error("Resource not found: {0}")]
-    ResourceNotFound(String),
+impl FarosPod {
+    /// Get the effective request of a resource for scheduling purposes
+    ///
+    /// Like the scheduler, this is the larger of the sum over all regular containers and the
+    /// largest request of any init container.
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource name (e.g. cpu, memory)
+    ///
+    /// # Returns
+    ///
+    /// * `f64` - The request in base units (cores for CPU, bytes for memory)
+    pub fn resource_request(&self, resource: &str) -> f64 {
+        let request = |container: &FarosContainer| {
+            container
+                .requests
+                .get(resource)
+                .and_then(|q| quantity::parse_quantity(q))
+                .unwrap_or(0.0)
+        };
+        let regular: f64 = self
+            .containers
+            .iter()
+            .filter(|c| c.kind == ContainerKind::Regular)
+            .map(request)
+            .sum();
+        let init = self
+            .containers
+            .iter()
+            .filter(|c| c.kind == ContainerKind::Init)
+            .map(request)
+            .fold(0.0, f64::max);
+        regular.max(init)
+    }
+
+    /// Check if the pod has terminated and no longer consumes node resources
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the pod has succeeded or failed
+    pub fn is_terminated(&self) -> bool {
+        matches!(self.phase.as_str(), "Succeeded" | "Failed")
+    }
+
+    /// Check if the pod is managed by a DaemonSet
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the pod's controller is a DaemonSet
+    pub fn is_daemonset_pod(&self) -> bool {
+        self.controller
+            .as_ref()
+            .is_some_and(|c| c.kind == "DaemonSet")
+    }
+}
+
+impl FarosNode {
+    /// Get the taints of the node keyed by `key:Effect`, with the taint value as the value
+    ///
+    /// Keying by key and effect lets a changed taint value show up as a change of one entry.
+    ///
+    /// # Returns
+    ///
+    /// * `BTreeMap<String, String>` - The taints of the node
+    pub fn taints_by_key(&self) -> std::collections::BTreeMap<String, String> {
+        self.taints
+            .iter()
+            .map(|taint| match taint.split_once('=') {
+                Some((key, rest)) => {
+                    let (value, effect) = rest.rsplit_once(':').unwrap_or((rest, ""));
+                    (format!("{}:{}", key, effect), value.to_string())
+                }
+                None => (taint.clone(), String::new()),
+            })
+            .collect()
+    }
+
+    /// Get the allocatable amount of a resource
+    ///
+    /// # Arguments
+    ///
+    /// * `resource` - The resource name (e.g. cpu, memory, pods)
+    ///
+    /// # Returns
+    ///
+    /// * `Option<f64>` - The allocatable amount in base units, if reported by the node
+    pub fn allocatable_quantity(&self, resource: &str) -> Option<f64> {
+        self.allocatable
+            .get(resource)
+            .and_then(|q| quantity::parse_quantity(q))
+    }
+}
+
+/// Reference to a Kubernetes object given as `KIND/NAME` (e.g. `pod/my-pod`, `deploy/my-app`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceRef {
+    /// Canonical kind of the object (e.g. Pod, Deployment)
+    pub kind: String,
+    /// Name of the object
+    pub name: String,
+}
+
+impl ResourceRef {
+    /// Check if the referenced object is cluster-scoped
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the object does not live in a namespace
+    pub fn is_cluster_scoped(&self) -> bool {
+        matches!(
+            self.kind.as_str(),
+            "Node" | "Namespace" | "PersistentVolume"
+        )
+    }
+}
+
+impl std::str::FromStr for ResourceRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, name) = s
+            .split_once('/')
+            .filter(|(kind, name)| !kind.is_empty() && !name.is_empty())
+            .ok_or_else(|| format!("expected KIND/NAME, got '{}'", s))?;
+
+        let kind = canonical_kind(kind);
+
+        Ok(Self {
+            kind,
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Map a kind given on the command line to its canonical name
+///
+/// The same short names and plurals as kubectl are accepted for well-known kinds; any other
+/// kind (e.g. a custom resource) is returned as given.
+///
+/// # Arguments
+///
+/// * `kind` - The kind as typed by the user (e.g. `deploy`, `pods`, `Node`)
+///
+/// # Returns
+///
+/// * `String` - The canonical kind (e.g. `Deployment`, `Pod`, `Node`)
+pub(crate) fn canonical_kind(kind: &str) -> String {
+    match kind.to_lowercase().as_str() {
+        "po" | "pod" | "pods" => "Pod".to_string(),
+        "no" | "node" | "nodes" => "Node".to_string(),
+        "ns" | "namespace" | "namespaces" => "Namespace".to_string(),
+        "pv" | "persistentvolume" | "persistentvolumes" => "PersistentVolume".to_string(),
+        "deploy" | "deployment" | "deployments" => "Deployment".to_string(),
+        "rs" | "replicaset" | "replicasets" => "ReplicaSet".to_string(),
+        "sts" | "statefulset" | "statefulsets" => "StatefulSet".to_string(),
+        "ds" | "daemonset" | "daemonsets" => "DaemonSet".to_string(),
+        "job" | "jobs" => "Job".to_string(),
+        "cj" | "cronjob" | "cronjobs" => "CronJob".to_string(),
+        _ => kind.to_string(),
+    }
+}
+
+impl std::fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.kind.to_lowercase(), self.name)
+    }
 }
 
 /// Client for interacting with Kubernetes clusters
 pub struct K8sClient {
     /// The underlying Kubernetes client
     client: Client,
+    /// How the client connects to its cluster, or None for snapshots and recordings
+    connection: Option<ConnectionInfo>,
+    /// Counters of the requests sent to the API server
+    stats: Arc<RequestStats>,
 }
 
 impl K8sClient {
@@ -85,26 +342,24 @@ impl K8sClient {
         Ok(default_kubeconfig)
     }
 
-    /// Get pods that match the specified filters
+    /// List raw pod objects, for consumers that need the full pod spec
     ///
     /// # Arguments
     ///
     /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
     /// * `all_namespaces` - If true, query pods across all namespaces
     /// * `node_name` - Optional filter by node name
-    /// * `pod_name` - Optional filter by pod name
     ///
     /// # Returns
     ///
-    /// * `Result<Vec<FarosPod>>` - A list of pods matching the filters
+    /// * `Result<Vec<Pod>>` - A list of pods matching the filters
     #[instrument(skip(self), level = "debug")]
-    pub async fn get_pods(
+    pub async fn list_pods(
         &self,
         namespace: &str,
         all_namespaces: bool,
         node_name: Option<&str>,
-        pod_name: Option<&str>,
-    ) -> Result<Vec<FarosPod>> {
+    ) -> Result<Vec<Pod>> {
         let api = if all_namespaces {
             Api::all(self.client.clone())
         } else {
@@ -122,8 +377,65 @@ impl K8sClient {
             .await
             .context("Failed to list pods from Kubernetes API")?;
 
-        let pods: Vec<FarosPod> = pod_list
-            .items
+        Ok(pod_list.items)
+    }
+
+    /// Get namespaces, including their labels
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to get (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, get all namespaces
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<Namespace>>` - The namespaces
+    #[instrument(skip(self), level = "debug")]
+    pub async fn list_namespaces(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<Namespace>> {
+        let api: Api<Namespace> = Api::all(self.client.clone());
+
+        if all_namespaces {
+            let namespace_list = api
+                .list(&ListParams::default())
+                .await
+                .context("Failed to list namespaces from Kubernetes API")?;
+            return Ok(namespace_list.items);
+        }
+
+        let namespace = api
+            .get_opt(namespace)
+            .await
+            .context("Failed to get namespace from Kubernetes API")?;
+        Ok(namespace.into_iter().collect())
+    }
+
+    /// Get pods that match the specified filters
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, query pods across all namespaces
+    /// * `node_name` - Optional filter by node name
+    /// * `pod_name` - Optional filter by pod name
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<FarosPod>>` - A list of pods matching the filters
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_pods(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        node_name: Option<&str>,
+        pod_name: Option<&str>,
+    ) -> Result<Vec<FarosPod>> {
+        let pods: Vec<FarosPod> = self
+            .list_pods(namespace, all_namespaces, node_name)
+            .await?
             .into_iter()
             .filter_map(|pod: Pod| {
                 // Filter by pod name if specified (field selector doesn't support pod name)
@@ -133,110 +445,48 @@ impl K8sClient {
                     return None;
                 }
 
-                // Extract pod name
-                let pod_name_str = pod.metadata.name.as_deref().unwrap_or_default().to_string();
-                let name = pod_name_str.clone();
-
-                // Extract namespace
-                let pod_namespace = pod
-                    .metadata
-                    .namespace
-                    .as_deref()
-                    .unwrap_or_default()
-                    .to_string();
-
-                // Extract node name
-                let pod_node_name = pod
-                    .spec
-                    .as_ref()
-                    .and_then(|spec| spec.node_name.as_ref())
-                    .cloned();
-                let node = pod_node_name.clone();
-
-                // Extract labels
-                let labels = pod.metadata.labels.unwrap_or_default();
-                let annotations = pod.metadata.annotations.unwrap_or_default();
-
-                // Extract environment variables
-                let mut container_env_vars = std::collections::BTreeMap::new();
-                if let Some(spec) = &pod.spec {
-                    for container in &spec.containers {
-                        let mut env_vars = std::collections::BTreeMap::new();
-                        if let Some(env) = &container.env {
-                            for var in env {
-                                let name = var.name.clone();
-                                let value = if let Some(val) = &var.value {
-                                    val.clone()
-                                } else if let Some(val_from) = &var.value_from {
-                                    if let Some(field_ref) = &val_from.field_ref {
-                                        // Attempt to resolve fieldRef from pod data
-                                        match field_ref.field_path.as_str() {
-                                            "metadata.name" => pod_name_str.clone(),
-                                            "metadata.namespace" => pod_namespace.clone(),
-                                            "metadata.uid" => {
-                                                pod.metadata.uid.clone().unwrap_or_default()
-                                            }
-                                            "spec.nodeName" => {
-                                                pod_node_name.clone().unwrap_or_default()
-                                            }
-                                            "spec.serviceAccountName" => pod
-                                                .spec
-                                                .as_ref()
-                                                .and_then(|s| s.service_account_name.clone())
-                                                .unwrap_or_default(),
-                                            "status.hostIP" => pod
-                                                .status
-                                                .as_ref()
-                                                .and_then(|s| s.host_ip.clone())
-                                                .unwrap_or_default(),
-                                            "status.podIP" => pod
-                                                .status
-                                                .as_ref()
-                                                .and_then(|s| s.pod_ip.clone())
-                                                .unwrap_or_default(),
-                                            _ => format!("fieldRef[{}]", field_ref.field_path),
-                                        }
-                                    } else if let Some(secret_ref) = &val_from.secret_key_ref {
-                                        format!(
-                                            "secret[{}:{}]",
-                                            secret_ref.name.clone(),
-                                            secret_ref.key
-                                        )
-                                    } else if let Some(cm_ref) = &val_from.config_map_key_ref {
-                                        format!("configmap[{}:{}]", cm_ref.name.clone(), cm_ref.key)
-                                    } else if let Some(res_ref) = &val_from.resource_field_ref {
-                                        format!(
-                                            "resource[{}:{}]",
-                                            res_ref.resource,
-                                            res_ref.container_name.clone().unwrap_or_default()
-                                        )
-                                    } else {
-                                        "<value-from>".to_string()
-                                    }
-                                } else {
-                                    "".to_string()
-                                };
-                                env_vars.insert(name, value);
-                            }
-                        }
-                        container_env_vars.insert(container.name.clone(), env_vars);
-                    }
-                }
-
-                Some(FarosPod {
-                    name,
-                    namespace: pod_namespace,
-                    node,
-                    labels,
-                    annotations,
-                    container_env_vars,
-                })
+                Some(FarosPod::from(&pod))
             })
             .collect();
 
         Ok(pods)
     }
 
+    /// Get a single pod by name
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace of the pod
+    /// * `pod_name` - The name of the pod
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FarosPod>` - The pod, or a `ResourceNotFound` error if it does not exist
+    pub async fn get_pod(&self, namespace: &str, pod_name: &str) -> Result<FarosPod> {
+        self.get_pods(namespace, false, None, Some(pod_name))
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                K8sError::ResourceNotFound(format!("pod/{} in {}", pod_name, namespace)).into()
+            })
+    }
+
+    /// Get a single node by name
+    ///
+    /// # Arguments
+    ///
+    /// * `node_name` - The name of the node
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FarosNode>` - The node, or a `ResourceNotFound` error if it does not exist
+    pub async fn get_node(&self, node_name: &str) -> Result<FarosNode> {
+        self.get_nodes(Some(node_name))
+            .await?
+            .pop()
+            .ok_or_else(|| K8sError::ResourceNotFound(format!("node/{}", node_name)).into())
+    }
+
     /// Get nodes that match the specified filters
     ///
     /// # Arguments
@@ -267,78 +517,18 @@ impl K8sClient {
                     return None;
                 }
 
-                // Extract node name
-                let name = node
-                    .metadata
-                    .name
-                    .as_deref()
-                    .unwrap_or_default()
-                    .to_string();
-
-                // Extract labels
-                let labels = node.metadata.labels.unwrap_or_default();
-                let annotations = node.metadata.annotations.unwrap_or_default();
-
-                // Extract status
-                let status = node
-                    .status
-                    .as_ref()
-                    .and_then(|s| s.conditions.as_ref())
-                    .and_then(|conditions| {
-                        conditions.iter().find(|c| c.type_ == "Ready").map(|c| {
-                            if c.status == "True" {
-                                "Ready".to_string()
-                            } else {
-                                "NotReady".to_string()
-                            }
-                        })
-                    })
-                    .unwrap_or_else(|| "Unknown".to_string());
-
-                Some(FarosNode {
-                    name,
-                    labels,
-                    annotations,
-                    status,
-                })
+                Some(FarosNode::from(&node))
             })
             .collect();
 
         Ok(nodes)
     }
 
-    /// Check if the Kubernetes cluster is accessible
+    /// Log how many requests the client sent and retried, at info level
     ///
-    /// # Returns
-    ///
-    /// * `Result<bool>` - True if the cluster is accessible, false otherwise
-    #[instrument(skip(self))]
-    pub async fn is_accessible(&self) -> Result<bool> {
-        debug!("Checking cluster accessibility");
-        let api: Api<Pod> = Api::namespaced(self.client.clone(), "default");
-
-        match api.list(&Default::default()).await {
-            Ok(_) => {
-                debug!("Successfully connected to cluster");
-                Ok(true)
-            }
-            Err(e) => match e {
-                kube::Error::Api(api_err) => {
-                    error!("Kubernetes API error occurred");
-                    Err(
-                        K8sError::ApiError(format!("{} ({})", api_err.message, api_err.reason))
-                            .into(),
-                    )
-                }
-                _ => {
-                    error!("Failed to connect to Kubernetes cluster");
-                    Err(
-                        K8sError::ConnectionError("Failed to connect to Kubernetes cluster".into())
-                            .into(),
-                    )
-                }
-            },
-        }
+    /// Nothing is logged if the client sent no requests.
+    pub fn log_request_summary(&self) {
+        self.stats.log_summary();
     }
 
     /// Create a new Kubernetes client
@@ -348,22 +538,107 @@ impl K8sClient {
     /// * `Result<Self>` - A new K8sClient instance or an error if initialization fails
     #[instrument(skip_all)]
     pub async fn new() -> Result<Self> {
-        debug!("Initializing Kubernetes client");
+        Self::with_options(&ClientOptions::default()).await
+    }
 
-        let kubeconfig_path = Self::get_kubeconfig_path()?;
-        debug!(path = %kubeconfig_path, "Using kubeconfig path");
+    /// Create a new Kubernetes client for a specific kubeconfig file or context
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The kubeconfig file and context to use, and settings overriding the
+    ///   kubeconfig
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - A new K8sClient instance or an error if initialization fails
+    #[instrument(skip_all)]
+    pub async fn with_options(options: &ClientOptions) -> Result<Self> {
+        debug!("Initializing Kubernetes client");
 
-        let client = Client::try_default()
+        let mut config = if options.in_cluster {
+            if options.kubeconfig.is_some() || options.context.is_some() {
+                return Err(K8sError::ConfigError(
+                    "--in-cluster cannot be combined with a kubeconfig or context".into(),
+                )
+                .into());
+            }
+            debug!("Using the in-cluster configuration");
+            options::in_cluster_config()?
+        } else if options.kubeconfig.is_none() && options.context.is_none() {
+            match Self::get_kubeconfig_path() {
+                Ok(kubeconfig_path) => {
+                    debug!(path = %kubeconfig_path, "Using kubeconfig path");
+
+                    Config::infer()
+                        .await
+                        .context("Failed to create Kubernetes client")?
+                }
+                // --server and credential flags are enough to connect without a kubeconfig
+                Err(e) => match &options.server {
+                    Some(server) => {
+                        debug!(server = %server, "No kubeconfig found, using --server");
+                        Config::new(options::parse_url("--server", server)?)
+                    }
+                    // Inside a pod, fall back to its ServiceAccount
+                    None if options::is_in_cluster() => {
+                        info!("No kubeconfig found, using the in-cluster configuration");
+                        options::in_cluster_config()?
+                    }
+                    None => return Err(e),
+                },
+            }
+        } else {
+            let kubeconfig_path = match &options.kubeconfig {
+                Some(path) => path.clone(),
+                None => PathBuf::from(Self::get_kubeconfig_path()?),
+            };
+            debug!(path = %kubeconfig_path.display(), context = ?options.context, "Using kubeconfig path");
+
+            let kubeconfig = Kubeconfig::read_from(&kubeconfig_path).map_err(|e| {
+                K8sError::ConfigError(format!(
+                    "Failed to read kubeconfig {}: {}",
+                    kubeconfig_path.display(),
+                    e
+                ))
+            })?;
+            Config::from_custom_kubeconfig(
+                kubeconfig,
+                &KubeConfigOptions {
+                    context: options.context.clone(),
+                    ..Default::default()
+                },
+            )
             .await
-            .context("Failed to create Kubernetes client")?;
+            .map_err(|e| K8sError::ConfigError(e.to_string()))?
+        };
 
-        let k8s_client = Self { client };
+        options.apply(&mut config)?;
+
+        let connection = ConnectionInfo::from_config(&config);
+        let stats = Arc::new(RequestStats::default());
+        let builder = ClientBuilder::try_from(config)
+            .context("Failed to create Kubernetes client")?
+            .with_layer(&RateLimitLayer::new(
+                options.rate_limit.clone(),
+                stats.clone(),
+            ))
+            .with_layer(&RetryLayer::new(options.retry.clone(), stats.clone()));
+        // Recordings hold the final response of each request, not the attempts that were retried
+        let client = match &options.record {
+            Some(dir) => builder.with_layer(&RecordLayer::new(dir)?).build(),
+            None => builder.build(),
+        };
 
-        // Verify cluster accessibility
-        if !k8s_client.is_accessible().await? {
-            return Err(
-                K8sError::ConnectionError("Kubernetes cluster is not accessible".into()).into(),
-            );
+        let k8s_client = Self {
+            client,
+            connection: Some(connection),
+            stats,
+        };
+
+        if options.skip_preflight {
+            debug!("Skipping cluster connectivity check");
+        } else {
+            k8s_client.preflight().await?;
         }
 
         info!("Successfully initialized Kubernetes client");
@@ -0,0 +1,186 @@
+use super::{K8sError, RateLimit, RetryPolicy};
+use anyhow::Result;
+use http::{HeaderName, HeaderValue, Uri};
+use kube::Config;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use secrecy::SecretString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::debug;
+
+/// Path of the ServiceAccount token that Kubernetes mounts into pods
+pub(super) const SERVICE_ACCOUNT_TOKEN: &str =
+    "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// Options for connecting to a Kubernetes cluster
+///
+/// Options that are set override the matching settings of the kubeconfig, like the global flags
+/// of kubectl.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    /// Path to the kubeconfig file (defaults to `KUBECONFIG` or ~/.kube/config)
+    pub kubeconfig: Option<PathBuf>,
+    /// Use the ServiceAccount of the pod fakos runs in, even if a kubeconfig exists
+    ///
+    /// Without a kubeconfig, the ServiceAccount is used whenever fakos runs in a pod.
+    pub in_cluster: bool,
+    /// Kubeconfig context to use (defaults to the current context)
+    pub context: Option<String>,
+    /// Directory to record all API requests and responses to (see [`K8sClient::from_recording`])
+    ///
+    /// [`K8sClient::from_recording`]: super::K8sClient::from_recording
+    pub record: Option<PathBuf>,
+    /// User to impersonate, e.g. system:serviceaccount:NAMESPACE:NAME
+    pub impersonate: Option<String>,
+    /// Groups to impersonate, together with `impersonate`
+    pub impersonate_groups: Vec<String>,
+    /// UID to impersonate, together with `impersonate`
+    pub impersonate_uid: Option<String>,
+    /// Bearer token to authenticate with, instead of the credentials of the kubeconfig
+    pub token: Option<SecretString>,
+    /// URL of the API server (usable without a kubeconfig)
+    pub server: Option<String>,
+    /// Path to a PEM file with the certificate authorities to verify the API server with
+    pub certificate_authority: Option<PathBuf>,
+    /// Do not verify the certificate of the API server
+    pub insecure_skip_tls_verify: bool,
+    /// Timeout for connecting, sending a request and reading its response; zero disables the
+    /// timeouts
+    pub request_timeout: Option<Duration>,
+    /// How requests that fail for transient reasons are retried
+    pub retry: RetryPolicy,
+    /// How fast and how many requests at once the client may send
+    pub rate_limit: RateLimit,
+    /// URL of an HTTP or SOCKS5 proxy to connect to the API server through
+    pub proxy_url: Option<String>,
+    /// Skip the connectivity check when the client is created (see [`K8sClient::preflight`])
+    ///
+    /// [`K8sClient::preflight`]: super::K8sClient::preflight
+    pub skip_preflight: bool,
+}
+
+impl ClientOptions {
+    /// Override the settings of a client configuration with the options that are set
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration loaded from the kubeconfig
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok, or a `ConfigError` if an option is invalid
+    pub(super) fn apply(&self, config: &mut Config) -> Result<()> {
+        if let Some(server) = &self.server {
+            config.cluster_url = parse_url("--server", server)?;
+        }
+        if let Some(path) = &self.certificate_authority {
+            config.root_cert = Some(read_certificates(path)?);
+        }
+        if self.insecure_skip_tls_verify {
+            debug!("Skipping verification of the API server certificate");
+            config.accept_invalid_certs = true;
+        }
+        if let Some(proxy_url) = &self.proxy_url {
+            config.proxy_url = Some(parse_url("--proxy-url", proxy_url)?);
+        }
+        if let Some(timeout) = self.request_timeout {
+            let timeout = Some(timeout).filter(|t| !t.is_zero());
+            config.connect_timeout = timeout;
+            config.read_timeout = timeout;
+            config.write_timeout = timeout;
+        }
+
+        if let Some(token) = &self.token {
+            // The token replaces all other credentials of the kubeconfig user
+            let auth = &mut config.auth_info;
+            auth.token = Some(token.clone());
+            auth.token_file = None;
+            auth.exec = None;
+            auth.auth_provider = None;
+            auth.username = None;
+            auth.password = None;
+        }
+
+        if let Some(user) = &self.impersonate {
+            debug!(user = %user, groups = ?self.impersonate_groups, uid = ?self.impersonate_uid, "Impersonating user");
+            config.auth_info.impersonate = Some(user.clone());
+            if !self.impersonate_groups.is_empty() {
+                config.auth_info.impersonate_groups = Some(self.impersonate_groups.clone());
+            }
+            // kube has no setting for the UID, so its header is added directly
+            if let Some(uid) = &self.impersonate_uid {
+                let uid = HeaderValue::from_str(uid)
+                    .map_err(|e| K8sError::ConfigError(format!("Invalid --as-uid: {}", e)))?;
+                config
+                    .headers
+                    .push((HeaderName::from_static("impersonate-uid"), uid));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check if fakos runs inside a pod with a mounted ServiceAccount token
+///
+/// # Returns
+///
+/// * `bool` - True if the in-cluster configuration is available
+pub(super) fn is_in_cluster() -> bool {
+    std::env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+        && Path::new(SERVICE_ACCOUNT_TOKEN).exists()
+}
+
+/// Load the configuration of the ServiceAccount of the pod fakos runs in
+///
+/// # Returns
+///
+/// * `Result<Config>` - The configuration, or a `ConfigError` if fakos does not run in a pod
+pub(super) fn in_cluster_config() -> Result<Config> {
+    Config::incluster().map_err(|e| {
+        K8sError::ConfigError(format!(
+            "Failed to load the in-cluster configuration (is fakos running in a pod?): {}",
+            e
+        ))
+        .into()
+    })
+}
+
+/// Parse a URL given as an option
+///
+/// # Arguments
+///
+/// * `option` - The name of the option, for the error message
+/// * `url` - The URL to parse
+///
+/// # Returns
+///
+/// * `Result<Uri>` - The URL, or a `ConfigError` if it is invalid
+pub(super) fn parse_url(option: &str, url: &str) -> Result<Uri> {
+    url.parse::<Uri>()
+        .ok()
+        .filter(|uri| uri.scheme().is_some() && uri.host().is_some())
+        .ok_or_else(|| {
+            K8sError::ConfigError(format!("Invalid {} '{}': expected a URL", option, url)).into()
+        })
+}
+
+/// Read the certificates of a PEM file
+fn read_certificates(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let error = |message: String| {
+        K8sError::ConfigError(format!(
+            "Failed to read certificate authority {}: {}",
+            path.display(),
+            message
+        ))
+    };
+    let certificates = CertificateDer::pem_file_iter(path)
+        .map_err(|e| error(e.to_string()))?
+        .map(|certificate| certificate.map(|c| c.to_vec()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| error(e.to_string()))?;
+    if certificates.is_empty() {
+        return Err(error("no certificates found".to_string()).into());
+    }
+    Ok(certificates)
+}
@@ -0,0 +1,366 @@
+use super::{FarosOwner, K8sClient, K8sError, ResourceRef};
+use anyhow::{Context, Result};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::batch::v1::{CronJob, Job};
+use k8s_openapi::api::core::v1::{ConfigMap, Node, PersistentVolumeClaim, Pod, Service};
+use kube::api::{ApiResource, DynamicObject, ListParams};
+use kube::core::{GroupVersion, GroupVersionKind};
+use kube::discovery::{self, Discovery, Scope};
+use kube::{Api, ResourceExt};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tracing::{debug, instrument};
+
+/// Maximum number of owners followed when walking up an owner chain
+const MAX_OWNER_DEPTH: usize = 16;
+
+/// A Kubernetes object and all objects it (transitively) owns
+#[derive(Debug, Clone)]
+pub struct FarosTreeNode {
+    /// Kind of the object
+    pub kind: String,
+    /// Name of the object
+    pub name: String,
+    /// Short status summary (e.g. `Running (2/2 ready)`, `3/3 ready`)
+    pub status: String,
+    /// Whether the object is healthy, if that can be determined from its status
+    pub healthy: Option<bool>,
+    /// Objects owned by this object
+    pub children: Vec<FarosTreeNode>,
+}
+
+/// Resource types whose objects are commonly owned by workloads and operators
+pub(super) fn child_resources() -> Vec<ApiResource> {
+    vec![
+        ApiResource::erase::<Deployment>(&()),
+        ApiResource::erase::<StatefulSet>(&()),
+        ApiResource::erase::<DaemonSet>(&()),
+        ApiResource::erase::<ReplicaSet>(&()),
+        ApiResource::erase::<CronJob>(&()),
+        ApiResource::erase::<Job>(&()),
+        ApiResource::erase::<Pod>(&()),
+        ApiResource::erase::<Service>(&()),
+        ApiResource::erase::<ConfigMap>(&()),
+        ApiResource::erase::<PersistentVolumeClaim>(&()),
+    ]
+}
+
+/// Summarise the status of an arbitrary object from its well-known status fields
+///
+/// # Returns
+///
+/// * `(String, Option<bool>)` - The status summary and whether the object is healthy
+fn summarize_status(kind: &str, data: &Value) -> (String, Option<bool>) {
+    let spec = &data["spec"];
+    let status = &data["status"];
+    let int = |value: &Value| value.as_i64().unwrap_or(0);
+
+    if kind == "Pod" {
+        let phase = status["phase"].as_str().unwrap_or("Unknown");
+        let statuses = status["containerStatuses"].as_array();
+        let total = statuses.map(Vec::len).unwrap_or(0);
+        let ready = statuses
+            .map(|s| {
+                s.iter()
+                    .filter(|c| c["ready"].as_bool() == Some(true))
+                    .count()
+            })
+            .unwrap_or(0);
+        let healthy = phase == "Succeeded" || (phase == "Running" && ready == total);
+        return (
+            format!("{} ({}/{} ready)", phase, ready, total),
+            Some(healthy),
+        );
+    }
+
+    if kind == "DaemonSet" {
+        let desired = int(&status["desiredNumberScheduled"]);
+        let ready = int(&status["numberReady"]);
+        return (
+            format!("{}/{} ready", ready, desired),
+            Some(ready >= desired),
+        );
+    }
+
+    if kind == "Job" {
+        let succeeded = int(&status["succeeded"]);
+        let completions = spec["completions"].as_i64().unwrap_or(1);
+        let failed = int(&status["failed"]);
+        let summary = if failed > 0 {
+            format!("{}/{} succeeded, {} failed", succeeded, completions, failed)
+        } else {
+            format!("{}/{} succeeded", succeeded, completions)
+        };
+        return (summary, Some(failed == 0 || succeeded >= completions));
+    }
+
+    if spec.get("replicas").is_some() || status.get("replicas").is_some() {
+        let desired = spec["replicas"]
+            .as_i64()
+            .unwrap_or_else(|| int(&status["replicas"]));
+        let ready = int(&status["readyReplicas"]);
+        return (
+            format!("{}/{} ready", ready, desired),
+            Some(ready >= desired),
+        );
+    }
+
+    // Fall back to the Ready/Available conditions used by most controllers and operators
+    let condition = status["conditions"].as_array().and_then(|conditions| {
+        conditions
+            .iter()
+            .find(|c| matches!(c["type"].as_str(), Some("Ready") | Some("Available")))
+    });
+    match condition {
+        Some(condition) => {
+            let ready = condition["status"].as_str() == Some("True");
+            let summary = match condition["reason"].as_str() {
+                Some(reason) if !ready => format!("NotReady: {}", reason),
+                _ if ready => "Ready".to_string(),
+                _ => "NotReady".to_string(),
+            };
+            (summary, Some(ready))
+        }
+        None => ("-".to_string(), None),
+    }
+}
+
+impl K8sClient {
+    /// Fetch an arbitrary object by its owner reference
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace of the owned object (owners are either in the same
+    ///   namespace or cluster-scoped)
+    /// * `owner` - The owner to fetch
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ApiResource, DynamicObject)>` - The API resource of the owner and the owner
+    pub(crate) async fn get_owner(
+        &self,
+        namespace: &str,
+        owner: &FarosOwner,
+    ) -> Result<(ApiResource, DynamicObject)> {
+        let gvk = owner
+            .api_version
+            .parse::<GroupVersion>()
+            .map(|gv| gv.with_kind(&owner.kind))
+            .unwrap_or_else(|_| GroupVersionKind::gvk("", &owner.api_version, &owner.kind));
+
+        let (resource, capabilities) = discovery::pinned_kind(&self.client, &gvk)
+            .await
+            .with_context(|| format!("Failed to discover API resource for {}", owner.kind))?;
+
+        let api: Api<DynamicObject> = match capabilities.scope {
+            Scope::Namespaced => Api::namespaced_with(self.client.clone(), namespace, &resource),
+            Scope::Cluster => Api::all_with(self.client.clone(), &resource),
+        };
+
+        let object = api
+            .get(&owner.name)
+            .await
+            .with_context(|| format!("Failed to get {} from Kubernetes API", owner))?;
+        Ok((resource, object))
+    }
+
+    /// Extend an owner chain upwards by following controller owner references
+    ///
+    /// The chain is resolved on a best-effort basis: if an owner cannot be fetched (e.g. because
+    /// of missing RBAC permissions) the chain ends there.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace of the object the chain belongs to
+    /// * `chain` - The chain to extend, starting with the direct controller of the object
+    #[instrument(skip(self, chain), level = "debug")]
+    pub async fn resolve_owner_chain(&self, namespace: &str, chain: &mut Vec<FarosOwner>) {
+        while chain.len() < MAX_OWNER_DEPTH {
+            let Some(current) = chain.last() else {
+                return;
+            };
+
+            let object = match self.get_owner(namespace, current).await {
+                Ok((_, object)) => object,
+                Err(e) => {
+                    debug!(owner = %current, error = ?e, "Stopping owner chain resolution");
+                    return;
+                }
+            };
+
+            let next = object
+                .metadata
+                .owner_references
+                .iter()
+                .flatten()
+                .find(|o| o.controller.unwrap_or(false))
+                .map(FarosOwner::from);
+
+            match next {
+                Some(owner) if !chain.contains(&owner) => chain.push(owner),
+                _ => return,
+            }
+        }
+    }
+
+    /// Resolve the API resource and scope for a kind given on the command line
+    ///
+    /// Well-known kinds are resolved statically; any other kind (e.g. a custom resource) is
+    /// looked up through API discovery by kind or plural name.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind to resolve
+    ///
+    /// # Returns
+    ///
+    /// * `Result<(ApiResource, Scope)>` - The API resource and its scope
+    pub(crate) async fn resolve_api_resource(&self, kind: &str) -> Result<(ApiResource, Scope)> {
+        if kind == "Node" {
+            return Ok((ApiResource::erase::<Node>(&()), Scope::Cluster));
+        }
+        if let Some(resource) = child_resources().into_iter().find(|r| r.kind == kind) {
+            return Ok((resource, Scope::Namespaced));
+        }
+
+        debug!(kind = %kind, "Resolving kind through API discovery");
+        let discovery = Discovery::new(self.client.clone())
+            .run()
+            .await
+            .context("Failed to run API discovery")?;
+
+        discovery
+            .groups()
+            .flat_map(|group| group.recommended_resources())
+            .find(|(resource, _)| {
+                resource.kind.eq_ignore_ascii_case(kind)
+                    || resource.plural.eq_ignore_ascii_case(kind)
+            })
+            .map(|(resource, capabilities)| (resource, capabilities.scope))
+            .ok_or_else(|| {
+                K8sError::ResourceNotFound(format!("unknown resource kind {}", kind)).into()
+            })
+    }
+
+    /// Build the ownership tree of a resource
+    ///
+    /// The tree is rooted at the top-most controller of the resource (e.g. the Deployment of a
+    /// pod) and contains all objects transitively owned by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace of the resource
+    /// * `resource` - The resource to build the tree for
+    ///
+    /// # Returns
+    ///
+    /// * `Result<FarosTreeNode>` - The root of the ownership tree
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_owner_tree(
+        &self,
+        namespace: &str,
+        resource: &ResourceRef,
+    ) -> Result<FarosTreeNode> {
+        let (api_resource, scope) = self.resolve_api_resource(&resource.kind).await?;
+        let api: Api<DynamicObject> = match scope {
+            Scope::Namespaced => {
+                Api::namespaced_with(self.client.clone(), namespace, &api_resource)
+            }
+            Scope::Cluster => Api::all_with(self.client.clone(), &api_resource),
+        };
+        let object = api
+            .get_opt(&resource.name)
+            .await
+            .with_context(|| format!("Failed to get {} from Kubernetes API", resource))?
+            .ok_or_else(|| K8sError::ResourceNotFound(format!("{} in {}", resource, namespace)))?;
+
+        // Walk up to the top-most controller
+        let mut root = (api_resource.clone(), object);
+        let mut candidates = child_resources();
+        if !candidates.iter().any(|r| r.kind == api_resource.kind) {
+            candidates.push(api_resource);
+        }
+        for _ in 0..MAX_OWNER_DEPTH {
+            let Some(owner) = root
+                .1
+                .owner_references()
+                .iter()
+                .find(|o| o.controller.unwrap_or(false))
+                .map(FarosOwner::from)
+            else {
+                break;
+            };
+            match self.get_owner(namespace, &owner).await {
+                Ok((parent_resource, parent)) => {
+                    if !candidates.iter().any(|r| r.kind == parent_resource.kind) {
+                        candidates.push(parent_resource.clone());
+                    }
+                    root = (parent_resource, parent);
+                }
+                Err(e) => {
+                    debug!(owner = %owner, error = ?e, "Stopping owner chain resolution");
+                    break;
+                }
+            }
+        }
+
+        // Index every object in the namespace that has a controller by its owner's UID
+        let mut children: BTreeMap<String, Vec<(String, DynamicObject)>> = BTreeMap::new();
+        for candidate in &candidates {
+            let api: Api<DynamicObject> =
+                Api::namespaced_with(self.client.clone(), namespace, candidate);
+            let list = match api.list(&ListParams::default()).await {
+                Ok(list) => list,
+                Err(e) => {
+                    debug!(kind = %candidate.kind, error = ?e, "Skipping resource type");
+                    continue;
+                }
+            };
+            for item in list.items {
+                if let Some(owner) = item
+                    .owner_references()
+                    .iter()
+                    .find(|o| o.controller.unwrap_or(false))
+                {
+                    children
+                        .entry(owner.uid.clone())
+                        .or_default()
+                        .push((candidate.kind.clone(), item));
+                }
+            }
+        }
+
+        Ok(build_tree(&root.0.kind, &root.1, &children, 0))
+    }
+}
+
+/// Recursively build a tree node and its children from the owner index
+fn build_tree(
+    kind: &str,
+    object: &DynamicObject,
+    children: &BTreeMap<String, Vec<(String, DynamicObject)>>,
+    depth: usize,
+) -> FarosTreeNode {
+    let (status, healthy) = summarize_status(kind, &object.data);
+    let mut node = FarosTreeNode {
+        kind: kind.to_string(),
+        name: object.name_any(),
+        status,
+        healthy,
+        children: Vec::new(),
+    };
+
+    if depth < MAX_OWNER_DEPTH
+        && let Some(uid) = object.uid()
+        && let Some(owned) = children.get(&uid)
+    {
+        node.children = owned
+            .iter()
+            .map(|(kind, child)| build_tree(kind, child, children, depth + 1))
+            .collect();
+        node.children
+            .sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+    }
+
+    node
+}
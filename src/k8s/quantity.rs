@@ -0,0 +1,120 @@
+/// Parse a Kubernetes resource quantity (e.g. `250m`, `1.5Gi`, `2`, `1e3`) into base units
+///
+/// CPU quantities are returned in cores and memory quantities in bytes.
+///
+/// # Arguments
+///
+/// * `quantity` - The quantity to parse
+///
+/// # Returns
+///
+/// * `Option<f64>` - The value in base units or None if the quantity is malformed
+pub fn parse_quantity(quantity: &str) -> Option<f64> {
+    let quantity = quantity.trim();
+    let split = quantity
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '+' || c == '-'))
+        .unwrap_or(quantity.len());
+    let (number, suffix) = quantity.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024.0_f64.powi(2),
+        "Gi" => 1024.0_f64.powi(3),
+        "Ti" => 1024.0_f64.powi(4),
+        "Pi" => 1024.0_f64.powi(5),
+        "Ei" => 1024.0_f64.powi(6),
+        exponent => {
+            let exponent: i32 = exponent
+                .strip_prefix('e')
+                .or_else(|| exponent.strip_prefix('E'))?
+                .parse()
+                .ok()?;
+            10f64.powi(exponent)
+        }
+    };
+
+    Some(number * multiplier)
+}
+
+/// Format a CPU amount in cores as millicores (e.g. `2350m`)
+pub fn format_cpu(cores: f64) -> String {
+    format!("{}m", (cores * 1000.0).round() as i64)
+}
+
+/// Format a memory amount in bytes using the largest fitting binary unit (e.g. `4.2Gi`)
+pub fn format_memory(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["Ki", "Mi", "Gi", "Ti", "Pi"];
+    let mut value = bytes;
+    let mut unit = "";
+    for candidate in UNITS {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = candidate;
+    }
+    if unit.is_empty() {
+        format!("{}", value.round() as i64)
+    } else {
+        format!("{:.1}{}", value, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_suffixes_are_powers_of_1024() {
+        assert_eq!(parse_quantity("1Ki"), Some(1024.0));
+        assert_eq!(
+            parse_quantity("1.5Gi"),
+            Some(1.5 * 1024.0 * 1024.0 * 1024.0)
+        );
+        assert_eq!(parse_quantity("128Mi"), Some(128.0 * 1024.0 * 1024.0));
+    }
+
+    #[test]
+    fn decimal_suffixes_are_powers_of_1000() {
+        assert_eq!(parse_quantity("250m"), Some(0.25));
+        assert_eq!(parse_quantity("2"), Some(2.0));
+        assert_eq!(parse_quantity("1k"), Some(1000.0));
+        assert_eq!(parse_quantity(" 1G "), Some(1e9));
+    }
+
+    #[test]
+    fn exponents_scale_the_number() {
+        assert_eq!(parse_quantity("1e3"), Some(1000.0));
+        assert_eq!(parse_quantity("12E2"), Some(1200.0));
+        assert_eq!(parse_quantity("5e-3"), Some(0.005));
+        // A lone E is the exa suffix, not an exponent
+        assert_eq!(parse_quantity("1E"), Some(1e18));
+    }
+
+    #[test]
+    fn malformed_quantities_are_rejected() {
+        assert_eq!(parse_quantity(""), None);
+        assert_eq!(parse_quantity("Gi"), None);
+        assert_eq!(parse_quantity("1Xi"), None);
+        assert_eq!(parse_quantity("1e"), None);
+    }
+
+    #[test]
+    fn amounts_are_formatted_in_the_largest_unit() {
+        assert_eq!(format_cpu(2.35), "2350m");
+        assert_eq!(format_memory(512.0), "512");
+        assert_eq!(format_memory(1536.0), "1.5Ki");
+        assert_eq!(format_memory(4.2 * 1024.0 * 1024.0 * 1024.0), "4.2Gi");
+    }
+}
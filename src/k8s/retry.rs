@@ -0,0 +1,255 @@
+use super::error::is_transient;
+use super::stats::RequestStats;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use http::{HeaderMap, Method, Request, Response, StatusCode};
+use kube::client::Body;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+use tower::{BoxError, Layer, Service, ServiceExt};
+use tracing::warn;
+
+/// How API requests that fail for transient reasons are retried
+///
+/// Only requests that read from the cluster (GET) are retried, when the API server is overloaded
+/// (429), failing (5xx), or the connection failed or timed out. Requests that change the cluster
+/// are sent once, so a change is never applied twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retries
+    pub max_retries: u32,
+    /// Time to wait before the first retry, doubled for each further retry
+    pub initial_backoff: Duration,
+    /// Longest time to wait between attempts, also when the API server asks for more with
+    /// `Retry-After`
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Get the time to wait before a retry, with jitter
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of the retry, starting at 1
+    ///
+    /// # Returns
+    ///
+    /// * `Duration` - Between half and all of the exponential backoff for the attempt
+    fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+        backoff.mul_f64(0.5 + fastrand::f64() / 2.0)
+    }
+}
+
+/// Tower layer retrying API requests according to a [`RetryPolicy`]
+///
+/// Each attempt needs its own handle to the client, so the layer goes around a service that can
+/// be cloned, such as the one of the `RateLimitLayer`.
+#[derive(Clone)]
+pub(super) struct RetryLayer {
+    policy: Arc<RetryPolicy>,
+    stats: Arc<RequestStats>,
+}
+
+impl RetryLayer {
+    /// Create a layer that counts its requests and retries in `stats`
+    pub(super) fn new(policy: RetryPolicy, stats: Arc<RequestStats>) -> Self {
+        Self {
+            policy: Arc::new(policy),
+            stats,
+        }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = RetryService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RetryService {
+            inner,
+            policy: self.policy.clone(),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+/// Service retrying requests of the HTTP client of kube
+pub(super) struct RetryService<S> {
+    inner: S,
+    policy: Arc<RetryPolicy>,
+    stats: Arc<RequestStats>,
+}
+
+impl<S, B> Service<Request<Body>> for RetryService<S>
+where
+    S: Service<Request<Body>, Response = Response<B>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send,
+    B: Send + 'static,
+{
+    type Response = Response<B>;
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        self.stats.record_request();
+        if !matches!(*request.method(), Method::GET | Method::HEAD) || self.policy.max_retries == 0
+        {
+            return self.inner.call(request).boxed();
+        }
+
+        // kube sends GET requests without a body, so they can be rebuilt for each attempt
+        let (parts, _) = request.into_parts();
+        let first = self
+            .inner
+            .call(Request::from_parts(parts.clone(), Body::empty()));
+        let mut inner = self.inner.clone();
+        let policy = self.policy.clone();
+        let stats = self.stats.clone();
+        async move {
+            let mut result = first.await;
+            let mut attempt = 0;
+            loop {
+                let delay = match &result {
+                    Ok(response) if is_retryable_status(response.status()) => {
+                        retry_after(response.headers())
+                    }
+                    Err(e) if is_transient(e.as_ref()) => None,
+                    _ => return result,
+                };
+                if attempt == policy.max_retries {
+                    return result;
+                }
+                attempt += 1;
+
+                // A bogus Retry-After must not stall the command
+                let delay = delay.map_or_else(
+                    || policy.backoff(attempt),
+                    |delay| delay.min(policy.max_backoff),
+                );
+                let reason = match &result {
+                    Ok(response) => response.status().to_string(),
+                    Err(e) => e.to_string(),
+                };
+                warn!(
+                    method = %parts.method,
+                    uri = %parts.uri,
+                    attempt,
+                    delay = ?delay,
+                    reason = %reason,
+                    "Retrying API request"
+                );
+                stats.record_retry(attempt, delay);
+                tokio::time::sleep(delay).await;
+
+                let request = Request::from_parts(parts.clone(), Body::empty());
+                result = match inner.ready().await {
+                    Ok(service) => service.call(request).await,
+                    Err(e) => Err(e),
+                };
+            }
+        }
+        .boxed()
+    }
+}
+
+/// Check if a response status means that the request may succeed when retried
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Get the time the API server asked to wait before retrying, in seconds in `Retry-After`
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn retry_after_is_read_in_seconds() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(http::header::RETRY_AFTER, " 3 ".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+        // HTTP dates are not sent by the API server
+        headers.insert(
+            http::header::RETRY_AFTER,
+            "Wed, 21 Oct 2026 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy::default();
+        for (attempt, full) in [(1, 250), (2, 500), (3, 1000), (10, 10_000)] {
+            let backoff = policy.backoff(attempt);
+            let full = Duration::from_millis(full);
+            assert!(backoff >= full / 2 && backoff <= full, "{:?}", backoff);
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_after_is_capped_at_the_maximum_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 1,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(20),
+        };
+        let stats = Arc::new(RequestStats::default());
+        let inner = tower::service_fn(|_: Request<Body>| async {
+            let response = Response::builder()
+                .status(StatusCode::SERVICE_UNAVAILABLE)
+                .header(http::header::RETRY_AFTER, "3600")
+                .body(())
+                .unwrap();
+            Ok::<_, BoxError>(response)
+        });
+        let mut service = RetryLayer::new(policy, stats).layer(inner);
+
+        let start = Instant::now();
+        let response = service
+            .ready()
+            .await
+            .unwrap()
+            .call(Request::get("/api/v1/pods").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
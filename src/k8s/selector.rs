@@ -0,0 +1,105 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Split a selector at commas that are not inside a parenthesised value set
+fn split_terms(selector: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(selector[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    terms.push(selector[start..].trim());
+    terms.into_iter().filter(|t| !t.is_empty()).collect()
+}
+
+/// Parse a parenthesised value set such as `(a, b)`
+fn value_set(values: &str) -> Vec<&str> {
+    values
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(str::trim)
+        .collect()
+}
+
+/// Check if labels match a label selector as understood by the API server
+///
+/// Supports equality (`a=b`, `a==b`, `a!=b`), set (`a in (b,c)`, `a notin (b,c)`) and
+/// existence (`a`, `!a`) requirements.
+///
+/// # Arguments
+///
+/// * `labels` - The labels of the object
+/// * `selector` - The label selector
+///
+/// # Returns
+///
+/// * `bool` - True if all requirements of the selector are met
+pub(crate) fn matches_label_selector(labels: &BTreeMap<String, String>, selector: &str) -> bool {
+    split_terms(selector).into_iter().all(|term| {
+        if let Some((key, values)) = term.split_once(" notin ") {
+            return labels
+                .get(key.trim())
+                .is_none_or(|v| !value_set(values).contains(&v.as_str()));
+        }
+        if let Some((key, values)) = term.split_once(" in ") {
+            return labels
+                .get(key.trim())
+                .is_some_and(|v| value_set(values).contains(&v.as_str()));
+        }
+        if let Some((key, value)) = term.split_once("!=") {
+            return labels.get(key.trim()).map(String::as_str) != Some(value.trim());
+        }
+        if let Some((key, value)) = term.split_once('=') {
+            let value = value.trim_start_matches('=').trim();
+            return labels.get(key.trim()).map(String::as_str) == Some(value);
+        }
+        match term.strip_prefix('!') {
+            Some(key) => !labels.contains_key(key.trim()),
+            None => labels.contains_key(term),
+        }
+    })
+}
+
+/// Check if an object matches a field selector as understood by the API server
+///
+/// Fields are looked up by their dotted JSON path (e.g. `spec.nodeName`, `regarding.kind`);
+/// missing fields compare as the empty string.
+///
+/// # Arguments
+///
+/// * `object` - The object as JSON
+/// * `selector` - The field selector
+///
+/// # Returns
+///
+/// * `bool` - True if all requirements of the selector are met
+pub(crate) fn matches_field_selector(object: &Value, selector: &str) -> bool {
+    split_terms(selector).into_iter().all(|term| {
+        let (path, expected, negate) = match term.split_once("!=") {
+            Some((path, value)) => (path, value, true),
+            None => match term.split_once('=') {
+                Some((path, value)) => (path, value.trim_start_matches('='), false),
+                None => return false,
+            },
+        };
+
+        let pointer = format!("/{}", path.trim().replace('.', "/"));
+        let actual = match object.pointer(&pointer) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        (actual == expected.trim()) != negate
+    })
+}
@@ -0,0 +1,251 @@
+use super::fake::FakeApiServer;
+use super::owners::child_resources;
+use super::record::scrub;
+use super::{K8sClient, K8sError};
+use anyhow::{Context, Result};
+use k8s_openapi::api::core::v1::{Namespace, Node};
+use k8s_openapi::api::events::v1::Event;
+use k8s_openapi::chrono::{DateTime, Utc};
+use kube::Api;
+use kube::api::{ApiResource, DynamicObject, ListParams, TypeMeta};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tracing::{debug, instrument, warn};
+
+/// Version of the snapshot file format
+pub(super) const SNAPSHOT_VERSION: u32 = 1;
+
+/// A point-in-time copy of cluster objects that commands can be run against offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// Version of the snapshot file format
+    pub version: u32,
+    /// When the snapshot was taken
+    pub created: DateTime<Utc>,
+    /// Version information of the API server the snapshot was taken from
+    pub server_version: Option<Value>,
+    /// Objects in the snapshot, grouped by resource type
+    pub resources: Vec<SnapshotResource>,
+}
+
+/// All captured objects of one resource type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotResource {
+    /// API group (empty for the core group)
+    pub group: String,
+    /// API version
+    pub version: String,
+    /// Kind of the objects
+    pub kind: String,
+    /// Plural resource name used in API paths
+    pub plural: String,
+    /// Whether the resource is namespaced
+    pub namespaced: bool,
+    /// The objects as returned by the API server
+    pub items: Vec<Value>,
+}
+
+impl SnapshotResource {
+    pub(super) fn api_version(&self) -> String {
+        if self.group.is_empty() {
+            self.version.clone()
+        } else {
+            format!("{}/{}", self.group, self.version)
+        }
+    }
+}
+
+impl Snapshot {
+    /// Load a snapshot from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the snapshot file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Snapshot>` - The snapshot
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+        let snapshot: Snapshot = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse snapshot {}", path.display()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(K8sError::ConfigError(format!(
+                "Unsupported snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            ))
+            .into());
+        }
+        Ok(snapshot)
+    }
+
+    /// Write the snapshot to a file as JSON
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the snapshot file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or error
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string(self).context("Failed to serialize snapshot")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))
+    }
+
+    /// Get the number of objects in the snapshot
+    pub fn object_count(&self) -> usize {
+        self.resources.iter().map(|r| r.items.len()).sum()
+    }
+}
+
+/// Resource types captured in snapshots; Secrets are never captured
+pub(super) fn snapshot_resources() -> Vec<(ApiResource, bool)> {
+    let mut resources = vec![
+        (ApiResource::erase::<Namespace>(&()), false),
+        (ApiResource::erase::<Node>(&()), false),
+        (ApiResource::erase::<Event>(&()), true),
+    ];
+    resources.extend(child_resources().into_iter().map(|r| (r, true)));
+    resources
+}
+
+impl K8sClient {
+    /// Capture pods, nodes and related objects into a snapshot
+    ///
+    /// Resource types that cannot be listed (e.g. due to missing permissions) are skipped with a
+    /// warning.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to capture (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, capture objects across all namespaces
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Snapshot>` - The snapshot
+    #[instrument(skip(self), level = "debug")]
+    pub async fn create_snapshot(&self, namespace: &str, all_namespaces: bool) -> Result<Snapshot> {
+        let server_version = match self.client.apiserver_version().await {
+            Ok(info) => serde_json::to_value(info).ok(),
+            Err(e) => {
+                warn!(error = %e, "Failed to get API server version");
+                None
+            }
+        };
+
+        let mut resources = Vec::new();
+        for (resource, namespaced) in snapshot_resources() {
+            let items = match self
+                .snapshot_items(&resource, namespaced, namespace, all_namespaces)
+                .await
+            {
+                Ok(items) => items,
+                Err(e) => {
+                    warn!(resource = %resource.plural, error = %e, "Skipping resource in snapshot");
+                    continue;
+                }
+            };
+            debug!(resource = %resource.plural, count = items.len(), "Captured resource");
+
+            resources.push(SnapshotResource {
+                group: resource.group.clone(),
+                version: resource.version.clone(),
+                kind: resource.kind.clone(),
+                plural: resource.plural.clone(),
+                namespaced,
+                items,
+            });
+        }
+
+        Ok(Snapshot {
+            version: SNAPSHOT_VERSION,
+            created: Utc::now(),
+            server_version,
+            resources,
+        })
+    }
+
+    async fn snapshot_items(
+        &self,
+        resource: &ApiResource,
+        namespaced: bool,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<Value>> {
+        let objects: Vec<DynamicObject> = if resource.kind == "Namespace" && !all_namespaces {
+            let api: Api<DynamicObject> = Api::all_with(self.client.clone(), resource);
+            api.get_opt(namespace).await?.into_iter().collect()
+        } else {
+            let api: Api<DynamicObject> = if namespaced && !all_namespaces {
+                Api::namespaced_with(self.client.clone(), namespace, resource)
+            } else {
+                Api::all_with(self.client.clone(), resource)
+            };
+            api.list(&ListParams::default()).await?.items
+        };
+
+        objects
+            .into_iter()
+            .map(|mut object| {
+                // List items carry no type information, and managed fields only add bulk
+                object.types = Some(TypeMeta {
+                    api_version: resource.api_version.clone(),
+                    kind: resource.kind.clone(),
+                });
+                object.metadata.managed_fields = None;
+                let mut value =
+                    serde_json::to_value(object).context("Failed to serialize object")?;
+                scrub(&mut value, false);
+                Ok(value)
+            })
+            .collect()
+    }
+
+    /// Create a client that answers all read requests from a snapshot instead of a live cluster
+    ///
+    /// Requests that would modify objects or watch for changes are refused.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the snapshot file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Self>` - A new K8sClient instance backed by the snapshot
+    #[instrument(skip_all)]
+    pub async fn from_snapshot(path: &Path) -> Result<Self> {
+        let snapshot = Snapshot::from_file(path)?;
+        debug!(
+            path = %path.display(),
+            created = %snapshot.created,
+            objects = snapshot.object_count(),
+            "Using snapshot"
+        );
+
+        Ok(FakeApiServer::from_snapshot(snapshot).client())
+    }
+
+    /// Create a client that is not connected to any cluster
+    ///
+    /// Used by commands that read manifests instead of the cluster. Every request for resources
+    /// fails with NotFound.
+    ///
+    /// # Returns
+    ///
+    /// * `Self` - A new K8sClient instance backed by an empty snapshot
+    pub fn offline() -> Self {
+        FakeApiServer::from_snapshot(Snapshot {
+            version: SNAPSHOT_VERSION,
+            created: Utc::now(),
+            server_version: None,
+            resources: Vec::new(),
+        })
+        .client()
+    }
+}
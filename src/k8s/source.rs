@@ -0,0 +1,351 @@
+use super::{
+    DrainOptions, EvictionObserver, FarosAccess, FarosClusterInfo, FarosEvent, FarosNode,
+    FarosObjectMeta, FarosOwner, FarosPermission, FarosPod, FarosTreeNode, FarosWorkload,
+    K8sClient, K8sError, MetadataField, PodEviction, ResourceRef, Snapshot,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use k8s_openapi::api::core::v1::{Namespace, Pod};
+use std::collections::BTreeMap;
+
+/// Source of cluster state and target of cluster changes for all fakos commands
+///
+/// Implemented by [`K8sClient`] for live clusters and snapshots. With the `mock` feature,
+/// `MockClusterSource` implements it for tests that run without a cluster.
+///
+/// Optional references have named lifetimes because mockall cannot infer them.
+#[cfg_attr(feature = "mock", mockall::automock)]
+#[async_trait]
+pub trait ClusterSource: Send + Sync {
+    /// List raw pods, optionally only those scheduled on a node
+    async fn list_pods<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        node_name: Option<&'a str>,
+    ) -> Result<Vec<Pod>>;
+
+    /// List raw namespaces, or only `namespace` unless `all_namespaces` is true
+    async fn list_namespaces(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<Namespace>>;
+
+    /// Get pods, optionally filtered by node and pod name
+    async fn get_pods<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        node_name: Option<&'a str>,
+        pod_name: Option<&'a str>,
+    ) -> Result<Vec<FarosPod>>;
+
+    /// Get a single pod, or a `ResourceNotFound` error if it does not exist
+    async fn get_pod(&self, namespace: &str, pod_name: &str) -> Result<FarosPod> {
+        self.get_pods(namespace, false, None, Some(pod_name))
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                K8sError::ResourceNotFound(format!("pod/{} in {}", pod_name, namespace)).into()
+            })
+    }
+
+    /// Get nodes, optionally only the one with the given name
+    async fn get_nodes<'a>(&self, node_name: Option<&'a str>) -> Result<Vec<FarosNode>>;
+
+    /// Get a single node, or a `ResourceNotFound` error if it does not exist
+    async fn get_node(&self, node_name: &str) -> Result<FarosNode> {
+        self.get_nodes(Some(node_name))
+            .await?
+            .pop()
+            .ok_or_else(|| K8sError::ResourceNotFound(format!("node/{}", node_name)).into())
+    }
+
+    /// Get Deployments, StatefulSets, DaemonSets and CronJobs with their pod templates
+    async fn get_workloads(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<FarosWorkload>>;
+
+    /// Get events, optionally only those of a resource (and its pods)
+    async fn get_events<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&'a ResourceRef>,
+        include_pods: bool,
+    ) -> Result<Vec<FarosEvent>>;
+
+    /// Watch for events newer than those listed, optionally only those of a resource (and its
+    /// pods)
+    async fn watch_events<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&'a ResourceRef>,
+        include_pods: bool,
+        listed: &[FarosEvent],
+    ) -> Result<BoxStream<'static, Result<FarosEvent>>>;
+
+    /// Attach recent warning events to each pod
+    async fn attach_pod_events(
+        &self,
+        pods: &mut [FarosPod],
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<()>;
+
+    /// Attach recent warning events to each node
+    async fn attach_node_events(&self, nodes: &mut [FarosNode]) -> Result<()>;
+
+    /// Resolve ConfigMap references in the environment variables of a pod
+    async fn resolve_pod_env(&self, pod: &mut FarosPod) -> Result<()>;
+
+    /// Extend a controller chain up to the top-most controller
+    async fn resolve_owner_chain(&self, namespace: &str, chain: &mut Vec<FarosOwner>);
+
+    /// Get the ownership tree of a resource, from its top-most controller down
+    async fn get_owner_tree(
+        &self,
+        namespace: &str,
+        resource: &ResourceRef,
+    ) -> Result<FarosTreeNode>;
+
+    /// List the metadata of objects of any kind, optionally filtered by label selector
+    async fn list_object_metadata<'a>(
+        &self,
+        kind: &str,
+        namespace: &str,
+        all_namespaces: bool,
+        label_selector: Option<&'a str>,
+    ) -> Result<Vec<FarosObjectMeta>>;
+
+    /// Replace the labels or annotations of an object, returning the values after the change
+    async fn patch_metadata(
+        &self,
+        object: &FarosObjectMeta,
+        field: MetadataField,
+        after: &BTreeMap<String, String>,
+        server_dry_run: bool,
+    ) -> Result<BTreeMap<String, String>>;
+
+    /// Get the names of nodes selected by name or label selector
+    async fn select_nodes<'a>(
+        &self,
+        node_name: Option<&'a str>,
+        label_selector: Option<&'a str>,
+    ) -> Result<Vec<String>>;
+
+    /// Cordon or uncordon a node, returning whether it changed
+    async fn set_unschedulable(
+        &self,
+        node_name: &str,
+        unschedulable: bool,
+        server_dry_run: bool,
+    ) -> Result<bool>;
+
+    /// Decide which pods on a node would be evicted by a drain
+    async fn plan_drain(&self, node_name: &str, options: &DrainOptions)
+    -> Result<Vec<PodEviction>>;
+
+    /// Evict pods, reporting progress to the observer after every state change
+    async fn evict_pods(
+        &self,
+        pods: Vec<PodEviction>,
+        options: &DrainOptions,
+        observer: Box<dyn EvictionObserver>,
+    ) -> Result<Vec<PodEviction>>;
+
+    /// Capture pods, nodes and related objects into a snapshot
+    async fn create_snapshot(&self, namespace: &str, all_namespaces: bool) -> Result<Snapshot>;
+
+    /// Get the version, identity and API groups of the cluster
+    async fn cluster_info(&self) -> Result<FarosClusterInfo>;
+
+    /// Check whether the current user has a permission, in a namespace or all namespaces
+    async fn check_access<'a>(
+        &self,
+        permission: &FarosPermission,
+        namespace: Option<&'a str>,
+    ) -> Result<FarosAccess>;
+}
+
+#[async_trait]
+impl ClusterSource for K8sClient {
+    async fn list_pods<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        node_name: Option<&'a str>,
+    ) -> Result<Vec<Pod>> {
+        K8sClient::list_pods(self, namespace, all_namespaces, node_name).await
+    }
+
+    async fn list_namespaces(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<Namespace>> {
+        K8sClient::list_namespaces(self, namespace, all_namespaces).await
+    }
+
+    async fn get_pods<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        node_name: Option<&'a str>,
+        pod_name: Option<&'a str>,
+    ) -> Result<Vec<FarosPod>> {
+        K8sClient::get_pods(self, namespace, all_namespaces, node_name, pod_name).await
+    }
+
+    async fn get_nodes<'a>(&self, node_name: Option<&'a str>) -> Result<Vec<FarosNode>> {
+        K8sClient::get_nodes(self, node_name).await
+    }
+
+    async fn get_workloads(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<FarosWorkload>> {
+        K8sClient::get_workloads(self, namespace, all_namespaces).await
+    }
+
+    async fn get_events<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&'a ResourceRef>,
+        include_pods: bool,
+    ) -> Result<Vec<FarosEvent>> {
+        K8sClient::get_events(self, namespace, all_namespaces, resource, include_pods).await
+    }
+
+    async fn watch_events<'a>(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+        resource: Option<&'a ResourceRef>,
+        include_pods: bool,
+        listed: &[FarosEvent],
+    ) -> Result<BoxStream<'static, Result<FarosEvent>>> {
+        Ok(K8sClient::watch_events(
+            self,
+            namespace,
+            all_namespaces,
+            resource,
+            include_pods,
+            listed,
+        )
+        .await?
+        .boxed())
+    }
+
+    async fn attach_pod_events(
+        &self,
+        pods: &mut [FarosPod],
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<()> {
+        K8sClient::attach_pod_events(self, pods, namespace, all_namespaces).await
+    }
+
+    async fn attach_node_events(&self, nodes: &mut [FarosNode]) -> Result<()> {
+        K8sClient::attach_node_events(self, nodes).await
+    }
+
+    async fn resolve_pod_env(&self, pod: &mut FarosPod) -> Result<()> {
+        K8sClient::resolve_pod_env(self, pod).await
+    }
+
+    async fn resolve_owner_chain(&self, namespace: &str, chain: &mut Vec<FarosOwner>) {
+        K8sClient::resolve_owner_chain(self, namespace, chain).await
+    }
+
+    async fn get_owner_tree(
+        &self,
+        namespace: &str,
+        resource: &ResourceRef,
+    ) -> Result<FarosTreeNode> {
+        K8sClient::get_owner_tree(self, namespace, resource).await
+    }
+
+    async fn list_object_metadata<'a>(
+        &self,
+        kind: &str,
+        namespace: &str,
+        all_namespaces: bool,
+        label_selector: Option<&'a str>,
+    ) -> Result<Vec<FarosObjectMeta>> {
+        K8sClient::list_object_metadata(self, kind, namespace, all_namespaces, label_selector).await
+    }
+
+    async fn patch_metadata(
+        &self,
+        object: &FarosObjectMeta,
+        field: MetadataField,
+        after: &BTreeMap<String, String>,
+        server_dry_run: bool,
+    ) -> Result<BTreeMap<String, String>> {
+        K8sClient::patch_metadata(self, object, field, after, server_dry_run).await
+    }
+
+    async fn select_nodes<'a>(
+        &self,
+        node_name: Option<&'a str>,
+        label_selector: Option<&'a str>,
+    ) -> Result<Vec<String>> {
+        K8sClient::select_nodes(self, node_name, label_selector).await
+    }
+
+    async fn set_unschedulable(
+        &self,
+        node_name: &str,
+        unschedulable: bool,
+        server_dry_run: bool,
+    ) -> Result<bool> {
+        K8sClient::set_unschedulable(self, node_name, unschedulable, server_dry_run).await
+    }
+
+    async fn plan_drain(
+        &self,
+        node_name: &str,
+        options: &DrainOptions,
+    ) -> Result<Vec<PodEviction>> {
+        K8sClient::plan_drain(self, node_name, options).await
+    }
+
+    async fn evict_pods(
+        &self,
+        pods: Vec<PodEviction>,
+        options: &DrainOptions,
+        mut observer: Box<dyn EvictionObserver>,
+    ) -> Result<Vec<PodEviction>> {
+        let pods = K8sClient::evict_pods(self, pods, options, |pods: &[PodEviction]| {
+            observer.on_progress(pods)
+        })
+        .await?;
+        observer.on_finish(&pods)?;
+        Ok(pods)
+    }
+
+    async fn create_snapshot(&self, namespace: &str, all_namespaces: bool) -> Result<Snapshot> {
+        K8sClient::create_snapshot(self, namespace, all_namespaces).await
+    }
+
+    async fn cluster_info(&self) -> Result<FarosClusterInfo> {
+        K8sClient::cluster_info(self).await
+    }
+
+    async fn check_access<'a>(
+        &self,
+        permission: &FarosPermission,
+        namespace: Option<&'a str>,
+    ) -> Result<FarosAccess> {
+        K8sClient::check_access(self, permission, namespace).await
+    }
+}
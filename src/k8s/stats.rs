@@ -0,0 +1,70 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::info;
+
+/// Counters of the API requests sent by a client, shared by its tower layers
+#[derive(Debug, Default)]
+pub(super) struct RequestStats {
+    /// Requests sent, not counting retries
+    requests: AtomicU64,
+    /// Requests that were retried at least once
+    retried_requests: AtomicU64,
+    /// Retries of all requests
+    retries: AtomicU64,
+    /// Time spent waiting before retries, in milliseconds
+    retry_delay_ms: AtomicU64,
+    /// Attempts that waited for the client rate limit
+    queued_requests: AtomicU64,
+    /// Time spent waiting for the client rate limit, in milliseconds
+    queue_time_ms: AtomicU64,
+}
+
+impl RequestStats {
+    /// Count a request
+    pub(super) fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a retry of a request
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of the retry, starting at 1
+    /// * `delay` - The time waited before the retry
+    pub(super) fn record_retry(&self, attempt: u32, delay: Duration) {
+        if attempt == 1 {
+            self.retried_requests.fetch_add(1, Ordering::Relaxed);
+        }
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        self.retry_delay_ms
+            .fetch_add(delay.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Count an attempt that waited for the client rate limit
+    ///
+    /// # Arguments
+    ///
+    /// * `waited` - The time the attempt waited
+    pub(super) fn record_queued(&self, waited: Duration) {
+        self.queued_requests.fetch_add(1, Ordering::Relaxed);
+        self.queue_time_ms
+            .fetch_add(waited.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Log a summary of the requests, at info level
+    pub(super) fn log_summary(&self) {
+        let requests = self.requests.load(Ordering::Relaxed);
+        if requests == 0 {
+            return;
+        }
+        info!(
+            requests,
+            retried_requests = self.retried_requests.load(Ordering::Relaxed),
+            retries = self.retries.load(Ordering::Relaxed),
+            retry_delay = ?Duration::from_millis(self.retry_delay_ms.load(Ordering::Relaxed)),
+            queued_requests = self.queued_requests.load(Ordering::Relaxed),
+            queue_time = ?Duration::from_millis(self.queue_time_ms.load(Ordering::Relaxed)),
+            "API request summary"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use super::K8sClient;
+use super::convert::format_env_value;
+use anyhow::{Context, Result};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+use k8s_openapi::api::batch::v1::CronJob;
+use k8s_openapi::api::core::v1::PodTemplateSpec;
+use kube::api::ListParams;
+use kube::{Api, Resource, ResourceExt};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use tracing::{instrument, warn};
+
+/// Represents a workload controller (Deployment, StatefulSet, DaemonSet or CronJob) and the
+/// pod template it manages
+#[derive(Debug, Clone)]
+pub struct FarosWorkload {
+    /// Kind of the workload
+    pub kind: String,
+    /// Namespace of the workload
+    pub namespace: String,
+    /// Name of the workload
+    pub name: String,
+    /// Labels attached to the workload
+    pub labels: BTreeMap<String, String>,
+    /// Annotations attached to the workload
+    pub annotations: BTreeMap<String, String>,
+    /// Image of each container in the pod template, by container name
+    pub images: BTreeMap<String, String>,
+    /// Environment variables of each container in the pod template, by container name
+    pub container_env_vars: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+fn workload<K: Resource<DynamicType = ()> + ResourceExt>(
+    object: &K,
+    template: Option<&PodTemplateSpec>,
+) -> FarosWorkload {
+    let containers = template
+        .and_then(|t| t.spec.as_ref())
+        .map(|spec| {
+            spec.init_containers
+                .iter()
+                .flatten()
+                .chain(&spec.containers)
+        })
+        .into_iter()
+        .flatten();
+
+    let mut images = BTreeMap::new();
+    let mut container_env_vars = BTreeMap::new();
+    for container in containers {
+        images.insert(
+            container.name.clone(),
+            container.image.clone().unwrap_or_default(),
+        );
+        container_env_vars.insert(
+            container.name.clone(),
+            container
+                .env
+                .iter()
+                .flatten()
+                .map(|var| (var.name.clone(), format_env_value(var, None)))
+                .collect(),
+        );
+    }
+
+    FarosWorkload {
+        kind: K::kind(&()).to_string(),
+        namespace: object.namespace().unwrap_or_default(),
+        name: object.name_any(),
+        labels: object.labels().clone(),
+        annotations: object.annotations().clone(),
+        images,
+        container_env_vars,
+    }
+}
+
+impl K8sClient {
+    async fn list_namespaced<K>(&self, namespace: &str, all_namespaces: bool) -> Result<Vec<K>>
+    where
+        K: Resource<DynamicType = (), Scope = k8s_openapi::NamespaceResourceScope>
+            + Clone
+            + DeserializeOwned
+            + std::fmt::Debug,
+    {
+        let api: Api<K> = if all_namespaces {
+            Api::all(self.client.clone())
+        } else {
+            Api::namespaced(self.client.clone(), namespace)
+        };
+        match api.list(&ListParams::default()).await {
+            Ok(list) => Ok(list.items),
+            // Snapshots omit resource types that could not be listed when they were taken
+            Err(kube::Error::Api(e)) if e.code == 404 => {
+                warn!(resource = %K::plural(&()), "Resource type not available, skipping");
+                Ok(Vec::new())
+            }
+            Err(e) => Err(e)
+                .with_context(|| format!("Failed to list {} from Kubernetes API", K::plural(&()))),
+        }
+    }
+
+    /// Get Deployments, StatefulSets, DaemonSets and CronJobs with their pod templates
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - The namespace to query (ignored if `all_namespaces` is true)
+    /// * `all_namespaces` - If true, query workloads across all namespaces
+    ///
+    /// # Returns
+    ///
+    /// * `Result<Vec<FarosWorkload>>` - The workloads
+    #[instrument(skip(self), level = "debug")]
+    pub async fn get_workloads(
+        &self,
+        namespace: &str,
+        all_namespaces: bool,
+    ) -> Result<Vec<FarosWorkload>> {
+        let mut workloads = Vec::new();
+
+        for deployment in self
+            .list_namespaced::<Deployment>(namespace, all_namespaces)
+            .await?
+        {
+            let template = deployment.spec.as_ref().map(|s| &s.template);
+            workloads.push(workload(&deployment, template));
+        }
+        for stateful_set in self
+            .list_namespaced::<StatefulSet>(namespace, all_namespaces)
+            .await?
+        {
+            let template = stateful_set.spec.as_ref().map(|s| &s.template);
+            workloads.push(workload(&stateful_set, template));
+        }
+        for daemon_set in self
+            .list_namespaced::<DaemonSet>(namespace, all_namespaces)
+            .await?
+        {
+            let template = daemon_set.spec.as_ref().map(|s| &s.template);
+            workloads.push(workload(&daemon_set, template));
+        }
+        for cron_job in self
+            .list_namespaced::<CronJob>(namespace, all_namespaces)
+            .await?
+        {
+            let template = cron_job
+                .spec
+                .as_ref()
+                .and_then(|s| s.job_template.spec.as_ref())
+                .map(|s| &s.template);
+            workloads.push(workload(&cron_job, template));
+        }
+
+        Ok(workloads)
+    }
+}
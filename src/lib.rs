@@ -1,22 +1,59 @@
 //! fakos - A Kubernetes CLI tool
 //!
-//! This crate provides functionality for managing and inspecting container images
-//! in Kubernetes clusters.
+//! This crate provides functionality for inspecting the state and metadata of Kubernetes
+//! resources such as pods, nodes, events and container images, and for auditing them against
+//! security policies.
 
 // Public API
 pub use cli::Args;
-pub use k8s::K8sClient;
+pub use k8s::{ClusterSource, K8sClient};
+pub use run::process_commands;
 
 // Internal modules
+mod audit;
 mod cli;
+mod drift;
 mod k8s;
+mod lint;
+mod run;
 mod utils;
 
 // Re-export commonly used items
-pub use cli::{Commands, GetResources, LogFormat, OutputFormat};
-pub use k8s::{FarosNode, FarosPod, K8sError};
+pub use audit::{
+    EnvSecretFinding, NamespaceSecuritySummary, PodSecurityLevel, PodSecurityReport,
+    SecretConfidence, SecurityFinding, audit_pod_security, scan_pod_env_secrets,
+    summarize_namespaces,
+};
+pub use cli::{
+    AuditChecks, AuthActions, ClusterActions, Commands, DescribeResources, DryRunMode,
+    GetResources, GroupBy, LintOutputFormat, LintTargets, LogFormat, MetadataTarget,
+    MetadataUpdateArgs, NodeActions, NodeSelection, OutputFormat, SnapshotActions,
+};
+pub use drift::{
+    DriftChange, DriftObject, FieldChange, ObjectDrift, collect_inventory, compare_inventories,
+};
+#[cfg(feature = "mock")]
+pub use k8s::MockClusterSource;
+pub use k8s::{
+    ClientOptions, ContainerKind, DrainOptions, EXIT_FAILURE, EvictionObserver, EvictionState,
+    ExtractOptions, FakeApiServer, FarosAccess, FarosClusterInfo, FarosCondition, FarosContainer,
+    FarosEvent, FarosImage, FarosNode, FarosObjectMeta, FarosOwner, FarosPermission, FarosPod,
+    FarosTreeNode, FarosWorkload, K8sError, MetadataChange, MetadataField, PodEviction, RateLimit,
+    ResourceRef, RetryPolicy, Snapshot, SnapshotResource, aggregate_images, plan_metadata_changes,
+    read_manifest_pods, required_permissions, review_access,
+};
+pub use lint::{
+    KeyRules, LabelPolicy, LintSeverity, LintViolation, PolicyRule, ValuePattern, lint_object,
+};
 pub use utils::logging;
-pub use utils::{EnvVarsFilter, display_nodes, display_pods};
+pub use utils::{
+    DiffEntry, DiffSection, EnvVarsFilter, EvictionProgress, confirm, describe_cluster,
+    describe_node, describe_pod, diff_maps, diff_nodes, diff_pods, display_access, display_drift,
+    display_env_secret_findings, display_events, display_evictions, display_images,
+    display_lint_violations, display_nodes, display_pod_groups, display_pods,
+    display_security_reports, display_security_summary, display_tree, print_diff, print_event,
+    print_metadata_diff,
+};
 
 /// Result type for fakos operations
 pub type FakosResult<T> = anyhow::Result<T>;
@@ -0,0 +1,495 @@
+use crate::k8s::{FarosObjectMeta, MetadataField};
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Labels recommended for all workloads by the Kubernetes documentation
+const RECOMMENDED_APP_LABELS: [&str; 6] = [
+    "app.kubernetes.io/name",
+    "app.kubernetes.io/instance",
+    "app.kubernetes.io/version",
+    "app.kubernetes.io/component",
+    "app.kubernetes.io/part-of",
+    "app.kubernetes.io/managed-by",
+];
+
+/// Kinds linted when neither the command line nor the policy names any
+const DEFAULT_KINDS: [&str; 2] = ["Pod", "Node"];
+
+/// A label and annotation policy, usually loaded from a YAML file
+///
+/// ```yaml
+/// rules:
+///   - name: workloads
+///     kinds: [Pod, Deployment]
+///     recommendedAppLabels: true
+///     labels:
+///       required: [team]
+///       forbidden: [tmp/*]
+///       allowedValues:
+///         environment: dev|staging|prod
+///     annotations:
+///       maxValueBytes: 4096
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LabelPolicy {
+    /// Rules of the policy, all of which are checked
+    pub rules: Vec<PolicyRule>,
+}
+
+/// A set of checks applied to objects of some (or all) kinds
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PolicyRule {
+    /// Name of the rule, shown in violations
+    pub name: String,
+    /// Kinds the rule applies to; applies to all linted kinds if empty
+    #[serde(default)]
+    pub kinds: Vec<String>,
+    /// Whether to warn about missing `app.kubernetes.io/*` recommended labels
+    #[serde(default)]
+    pub recommended_app_labels: bool,
+    /// Checks on labels
+    #[serde(default)]
+    pub labels: KeyRules,
+    /// Checks on annotations
+    #[serde(default)]
+    pub annotations: KeyRules,
+}
+
+/// Checks on a set of labels or annotations
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct KeyRules {
+    /// Keys that must be present
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Keys that should be present (reported as warnings)
+    #[serde(default)]
+    pub recommended: Vec<String>,
+    /// Keys that must not be present; a trailing `*` matches any key with that prefix
+    #[serde(default)]
+    pub forbidden: Vec<String>,
+    /// Regexes that the whole value of a key must match, if the key is present
+    #[serde(default, deserialize_with = "deserialize_regexes")]
+    pub allowed_values: BTreeMap<String, ValuePattern>,
+    /// Maximum size in bytes of a single value
+    pub max_value_bytes: Option<usize>,
+    /// Maximum combined size in bytes of all keys and values
+    pub max_total_bytes: Option<usize>,
+}
+
+/// A pattern that the whole value of a label or annotation must match
+#[derive(Debug, Clone)]
+pub struct ValuePattern {
+    /// The pattern as written in the policy
+    pub pattern: String,
+    regex: Regex,
+}
+
+impl ValuePattern {
+    /// Check if a value matches the pattern in full
+    pub fn matches(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+/// Compile the allowed value patterns, anchored so that they must match the whole value
+fn deserialize_regexes<'de, D>(deserializer: D) -> Result<BTreeMap<String, ValuePattern>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BTreeMap::<String, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(key, pattern)| {
+            let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+                serde::de::Error::custom(format!("invalid regex for {}: {}", key, e))
+            })?;
+            Ok((key, ValuePattern { pattern, regex }))
+        })
+        .collect()
+}
+
+impl LabelPolicy {
+    /// Load a policy from a YAML file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the policy file
+    ///
+    /// # Returns
+    ///
+    /// * `Result<LabelPolicy>` - The parsed policy
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read policy file {}", path.display()))?;
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse policy file {}", path.display()))
+    }
+
+    /// Determine the kinds to lint when none are given on the command line
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<String>` - The kinds named by the rules, plus pods and nodes if any rule applies
+    ///   to all kinds
+    pub fn default_kinds(&self) -> Vec<String> {
+        let mut kinds: Vec<String> = Vec::new();
+        if self.rules.iter().any(|rule| rule.kinds.is_empty()) {
+            kinds.extend(DEFAULT_KINDS.iter().map(|k| k.to_string()));
+        }
+        for kind in self.rules.iter().flat_map(|rule| &rule.kinds) {
+            let kind = crate::k8s::canonical_kind(kind);
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        kinds
+    }
+}
+
+impl PolicyRule {
+    fn applies_to(&self, kind: &str) -> bool {
+        self.kinds.is_empty()
+            || self
+                .kinds
+                .iter()
+                .any(|k| crate::k8s::canonical_kind(k).eq_ignore_ascii_case(kind))
+    }
+}
+
+/// Severity of a policy violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    /// A convention that should be followed
+    Warning,
+    /// A convention that must be followed
+    Error,
+}
+
+impl std::fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single policy violation of an object
+#[derive(Debug, Clone, Serialize)]
+pub struct LintViolation {
+    /// Kind of the object
+    pub kind: String,
+    /// Namespace of the object, or None for cluster-scoped objects
+    pub namespace: Option<String>,
+    /// Name of the object
+    pub name: String,
+    /// Name of the policy rule that was violated
+    pub rule: String,
+    /// Identifier of the check that failed (e.g. `required-label`)
+    pub check: &'static str,
+    /// Severity of the violation
+    pub severity: LintSeverity,
+    /// Human-readable description of the violation
+    pub message: String,
+}
+
+/// Check the labels and annotations of an object against all applicable rules of a policy
+///
+/// # Arguments
+///
+/// * `policy` - The policy to check against
+/// * `object` - The object to check
+///
+/// # Returns
+///
+/// * `Vec<LintViolation>` - The violations found, in rule order
+pub fn lint_object(policy: &LabelPolicy, object: &FarosObjectMeta) -> Vec<LintViolation> {
+    let mut violations = Vec::new();
+
+    for rule in policy.rules.iter().filter(|r| r.applies_to(&object.kind)) {
+        let mut violation = |check, severity, message| {
+            violations.push(LintViolation {
+                kind: object.kind.clone(),
+                namespace: object.namespace.clone(),
+                name: object.name.clone(),
+                rule: rule.name.clone(),
+                check,
+                severity,
+                message,
+            });
+        };
+
+        for (field, noun, rules, values) in [
+            (MetadataField::Labels, "label", &rule.labels, &object.labels),
+            (
+                MetadataField::Annotations,
+                "annotation",
+                &rule.annotations,
+                &object.annotations,
+            ),
+        ] {
+            for key in &rules.required {
+                if !values.contains_key(key) {
+                    violation(
+                        check_id(field, Check::Required),
+                        LintSeverity::Error,
+                        format!("missing required {} {}", noun, key),
+                    );
+                }
+            }
+            for key in &rules.recommended {
+                if !values.contains_key(key) {
+                    violation(
+                        check_id(field, Check::Recommended),
+                        LintSeverity::Warning,
+                        format!("missing recommended {} {}", noun, key),
+                    );
+                }
+            }
+            for pattern in &rules.forbidden {
+                for key in values.keys().filter(|key| key_matches(pattern, key)) {
+                    violation(
+                        check_id(field, Check::Forbidden),
+                        LintSeverity::Error,
+                        format!("forbidden {} {} is set", noun, key),
+                    );
+                }
+            }
+            for (key, allowed) in &rules.allowed_values {
+                if let Some(value) = values.get(key)
+                    && !allowed.matches(value)
+                {
+                    violation(
+                        check_id(field, Check::Value),
+                        LintSeverity::Error,
+                        format!(
+                            "{} {}={} does not match {}",
+                            noun, key, value, allowed.pattern
+                        ),
+                    );
+                }
+            }
+            if let Some(max) = rules.max_value_bytes {
+                for (key, value) in values.iter().filter(|(_, v)| v.len() > max) {
+                    violation(
+                        check_id(field, Check::Size),
+                        LintSeverity::Error,
+                        format!("{} {} is {} bytes (limit {})", noun, key, value.len(), max),
+                    );
+                }
+            }
+            if let Some(max) = rules.max_total_bytes {
+                let total: usize = values.iter().map(|(k, v)| k.len() + v.len()).sum();
+                if total > max {
+                    violation(
+                        check_id(field, Check::Size),
+                        LintSeverity::Error,
+                        format!("{}s total {} bytes (limit {})", noun, total, max),
+                    );
+                }
+            }
+        }
+
+        if rule.recommended_app_labels {
+            for key in RECOMMENDED_APP_LABELS {
+                if !object.labels.contains_key(key) {
+                    violation(
+                        "recommended-label",
+                        LintSeverity::Warning,
+                        format!("missing recommended label {}", key),
+                    );
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Checks of a rule that apply to labels and annotations alike
+#[derive(Debug, Clone, Copy)]
+enum Check {
+    Required,
+    Recommended,
+    Forbidden,
+    Value,
+    Size,
+}
+
+/// Identifier of a check, e.g. `required-label` or `annotation-size`
+fn check_id(field: MetadataField, check: Check) -> &'static str {
+    match (field, check) {
+        (MetadataField::Labels, Check::Required) => "required-label",
+        (MetadataField::Labels, Check::Recommended) => "recommended-label",
+        (MetadataField::Labels, Check::Forbidden) => "forbidden-label",
+        (MetadataField::Labels, Check::Value) => "label-value",
+        (MetadataField::Labels, Check::Size) => "label-size",
+        (MetadataField::Annotations, Check::Required) => "required-annotation",
+        (MetadataField::Annotations, Check::Recommended) => "recommended-annotation",
+        (MetadataField::Annotations, Check::Forbidden) => "forbidden-annotation",
+        (MetadataField::Annotations, Check::Value) => "annotation-value",
+        (MetadataField::Annotations, Check::Size) => "annotation-size",
+    }
+}
+
+/// Match a key against a forbidden key pattern, where a trailing `*` matches any suffix
+fn key_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(yaml: &str) -> LabelPolicy {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn object(
+        kind: &str,
+        labels: &[(&str, &str)],
+        annotations: &[(&str, &str)],
+    ) -> FarosObjectMeta {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        FarosObjectMeta {
+            kind: kind.to_string(),
+            namespace: Some("shop".to_string()),
+            name: "web".to_string(),
+            labels: map(labels),
+            annotations: map(annotations),
+        }
+    }
+
+    /// Get the check and message of each violation
+    fn checks(violations: &[LintViolation]) -> Vec<(&str, &str)> {
+        violations
+            .iter()
+            .map(|v| (v.check, v.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn required_forbidden_and_allowed_values_are_checked() {
+        let policy = policy(
+            r#"
+rules:
+  - name: teams
+    labels:
+      required: [team]
+      recommended: [owner]
+      forbidden: [tmp/*]
+      allowedValues:
+        environment: dev|staging|prod
+"#,
+        );
+        let violations = lint_object(
+            &policy,
+            &object(
+                "Pod",
+                &[("environment", "production"), ("tmp/debug", "1")],
+                &[],
+            ),
+        );
+        assert_eq!(
+            checks(&violations),
+            [
+                ("required-label", "missing required label team"),
+                ("recommended-label", "missing recommended label owner"),
+                ("forbidden-label", "forbidden label tmp/debug is set"),
+                (
+                    "label-value",
+                    "label environment=production does not match dev|staging|prod"
+                ),
+            ]
+        );
+        assert_eq!(violations[0].severity, LintSeverity::Error);
+        assert_eq!(violations[1].severity, LintSeverity::Warning);
+        assert_eq!(violations[0].rule, "teams");
+
+        let compliant = object(
+            "Pod",
+            &[("team", "shop"), ("owner", "ana"), ("environment", "prod")],
+            &[],
+        );
+        assert!(lint_object(&policy, &compliant).is_empty());
+    }
+
+    #[test]
+    fn annotation_sizes_are_limited() {
+        let policy = policy(
+            r#"
+rules:
+  - name: sizes
+    annotations:
+      maxValueBytes: 8
+      maxTotalBytes: 15
+"#,
+        );
+        let violations = lint_object(
+            &policy,
+            &object("Node", &[], &[("note", "0123456789"), ("a", "b")]),
+        );
+        assert_eq!(
+            checks(&violations),
+            [
+                ("annotation-size", "annotation note is 10 bytes (limit 8)"),
+                ("annotation-size", "annotations total 16 bytes (limit 15)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_check_has_its_own_identifier() {
+        let checks = [
+            Check::Required,
+            Check::Recommended,
+            Check::Forbidden,
+            Check::Value,
+            Check::Size,
+        ];
+        let ids: std::collections::BTreeSet<&str> =
+            [MetadataField::Labels, MetadataField::Annotations]
+                .into_iter()
+                .flat_map(|field| checks.map(|check| check_id(field, check)))
+                .collect();
+        assert_eq!(ids.len(), 10);
+        assert!(ids.contains("label-size"));
+    }
+
+    #[test]
+    fn rules_only_apply_to_their_kinds() {
+        let policy = policy(
+            r#"
+rules:
+  - name: workloads
+    kinds: [deploy]
+    recommendedAppLabels: true
+"#,
+        );
+        assert!(lint_object(&policy, &object("Pod", &[], &[])).is_empty());
+        let violations = lint_object(
+            &policy,
+            &object("Deployment", &[("app.kubernetes.io/name", "web")], &[]),
+        );
+        assert_eq!(violations.len(), RECOMMENDED_APP_LABELS.len() - 1);
+        assert!(
+            violations
+                .iter()
+                .all(|v| v.check == "recommended-label" && v.severity == LintSeverity::Warning)
+        );
+    }
+}
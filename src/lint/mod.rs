@@ -0,0 +1,7 @@
+//! Linters that check resources against user-defined conventions
+
+mod labels;
+
+pub use labels::{
+    KeyRules, LabelPolicy, LintSeverity, LintViolation, PolicyRule, ValuePattern, lint_object,
+};
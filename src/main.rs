@@ -1,15 +1,34 @@
 use anyhow::Context;
 use clap::Parser;
 use fakos::{
-    Args, Commands, FakosResult, GetResources, K8sClient, display_nodes, display_pods, logging,
+    Args, Commands, EXIT_FAILURE, FakosResult, GetResources, K8sClient, K8sError, logging,
+    process_commands,
 };
-use tracing::{debug, info, instrument, warn};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info};
 
 /// Main entry point for the fakos application
+///
+/// Errors are printed with a hint on how to resolve them, and exit with the code of their kind
+/// (see [`K8sError::exit_code`]).
 #[tokio::main]
-async fn main() -> FakosResult<()> {
+async fn main() {
     let args = Args::parse();
 
+    if let Err(error) = run(args).await {
+        let k8s_error = K8sError::find(&error);
+        eprintln!("Error: {:?}", error);
+        if let Some(hint) = k8s_error.as_ref().and_then(K8sError::hint) {
+            eprintln!("\nHint: {}", hint);
+        }
+        std::process::exit(k8s_error.map_or(EXIT_FAILURE, |e| e.exit_code()));
+    }
+}
+
+/// Set up logging and the client, then run the command
+async fn run(args: Args) -> FakosResult<()> {
     // Initialize rustls crypto provider
     // Ignore error if already installed (e.g. by other dependencies)
     let _ = rustls::crypto::ring::default_provider().install_default();
@@ -20,97 +39,76 @@ async fn main() -> FakosResult<()> {
 
     debug!("Application started with args: {:?}", args);
 
+    // --command-timeout bounds connecting to the cluster and the command together
+    let timeout = args.command_timeout.filter(|t| !t.is_zero());
+    let deadline = timeout.map(|t| Instant::now() + t);
+
     // Create the client with improved error context
-    let client = K8sClient::new()
-        .await
-        .context("Failed to create Kubernetes client")?;
+    // `drift OLD NEW` reads the new state from NEW and `get pods -f` reads manifests, so neither
+    // needs the cluster
+    let (offline, snapshot) = match &args.command {
+        Commands::Drift { new: Some(new), .. } => (false, Some(new)),
+        Commands::Get {
+            resource: GetResources::Pods {
+                filename: Some(_), ..
+            },
+        } => (true, None),
+        _ => (false, args.from_snapshot.as_ref()),
+    };
+    let client = match (snapshot, &args.replay) {
+        (Some(path), _) => K8sClient::from_snapshot(path)
+            .await
+            .context("Failed to load snapshot")?,
+        (None, _) if offline => K8sClient::offline(),
+        (None, Some(dir)) => K8sClient::from_recording(dir).context("Failed to load recording")?,
+        (None, None) => {
+            let client = within(
+                deadline,
+                timeout,
+                K8sClient::with_options(&args.client_options()),
+            )
+            .await?
+            .context("Failed to create Kubernetes client")?;
 
-    info!("Successfully connected to Kubernetes cluster");
+            info!("Successfully connected to Kubernetes cluster");
+            client
+        }
+    };
 
-    process_commands(args, client).await?;
+    let result = within(deadline, timeout, process_commands(args, &client)).await;
+    client.log_request_summary();
+    result??;
 
     debug!("Application completed successfully");
     Ok(())
 }
 
-/// Process the command line arguments and execute the corresponding command
-#[instrument(skip(client), level = "debug")]
-async fn process_commands(args: Args, client: K8sClient) -> FakosResult<()> {
-    match args.command {
-        Commands::Get { resource } => match resource {
-            GetResources::Pods {
-                namespace,
-                node,
-                pod_name,
-                all_namespaces,
-                output,
-                labels,
-                annotations,
-                env_vars,
-                ..
-            } => {
-                if let Some(ref pod) = pod_name
-                    && all_namespaces
-                {
-                    warn!(
-                        pod = %pod,
-                        "Warning: Pod name specified with --all-namespaces flag. Pod names are unique within a namespace, so searching across all namespaces may be inefficient."
-                    );
-                }
-
-                debug!(
-                    namespace = %namespace,
-                    node = ?node,
-                    pod = ?pod_name,
-                    all_namespaces = %all_namespaces,
-                    output = ?output,
-                    labels = %labels,
-                    annotations = %annotations,
-                    "Processing..."
-                );
-
-                let pods = client
-                    .get_pods(
-                        &namespace,
-                        all_namespaces,
-                        node.as_deref(),
-                        pod_name.as_deref(),
-                    )
-                    .await
-                    .context("Failed to get pods")?;
-
-                display_pods(
-                    &pods,
-                    &output,
-                    labels,
-                    annotations,
-                    all_namespaces,
-                    env_vars.as_ref(),
-                )?;
-            }
-            GetResources::Nodes {
-                node_name,
-                output,
-                labels,
-                annotations,
-                ..
-            } => {
-                debug!(
-                    node = ?node_name,
-                    output = ?output,
-                    labels = %labels,
-                    annotations = %annotations,
-                    "Processing..."
-                );
-
-                let nodes = client
-                    .get_nodes(node_name.as_deref())
-                    .await
-                    .context("Failed to get nodes")?;
-
-                display_nodes(&nodes, &output, labels, annotations)?;
-            }
-        },
+/// Run a future until the deadline of the command, if it has one
+///
+/// # Arguments
+///
+/// * `deadline` - When the command times out, or None to wait forever
+/// * `timeout` - The timeout of the command, for the error message
+/// * `future` - The future to run
+///
+/// # Returns
+///
+/// * `FakosResult<T>` - The output of the future, or a `Timeout` error if the deadline passed
+async fn within<T>(
+    deadline: Option<Instant>,
+    timeout: Option<Duration>,
+    future: impl Future<Output = T>,
+) -> FakosResult<T> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, future)
+            .await
+            .map_err(|_| {
+                K8sError::Timeout(format!(
+                    "The command did not finish within --command-timeout {:?}",
+                    timeout.unwrap_or_default()
+                ))
+                .into()
+            }),
+        None => Ok(future.await),
     }
-    Ok(())
 }
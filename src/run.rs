@@ -0,0 +1,783 @@
+use crate::{
+    Args, AuditChecks, AuthActions, ClientOptions, ClusterActions, ClusterSource, Commands,
+    DescribeResources, DrainOptions, DryRunMode, EvictionProgress, EvictionState, FakosResult,
+    GetResources, GroupBy, K8sClient, K8sError, LabelPolicy, LintSeverity, LintTargets,
+    MetadataField, MetadataUpdateArgs, NodeActions, NodeSelection, ResourceRef, SnapshotActions,
+    aggregate_images, audit_pod_security, collect_inventory, compare_inventories, confirm,
+    describe_cluster, describe_node, describe_pod, diff_nodes, diff_pods, display_access,
+    display_drift, display_env_secret_findings, display_events, display_evictions, display_images,
+    display_lint_violations, display_nodes, display_pod_groups, display_pods,
+    display_security_reports, display_security_summary, display_tree, lint_object,
+    plan_metadata_changes, print_diff, print_event, print_metadata_diff, read_manifest_pods,
+    review_access, scan_pod_env_secrets, summarize_namespaces,
+};
+use anyhow::Context;
+use futures::StreamExt;
+use tracing::{debug, error, info, instrument, warn};
+
+/// Process the command line arguments and execute the corresponding command
+///
+/// # Arguments
+///
+/// * `args` - The parsed command line arguments
+/// * `client` - The cluster (or snapshot) to read from and modify
+///
+/// # Returns
+///
+/// * `FakosResult<()>` - An error if the command failed
+#[instrument(skip(client), level = "debug")]
+pub async fn process_commands(args: Args, client: &dyn ClusterSource) -> FakosResult<()> {
+    // Commands that connect to other contexts use the same global flags
+    let client_options = args.client_options();
+    match args.command {
+        Commands::Get { resource } => match resource {
+            GetResources::Pods {
+                namespace,
+                node,
+                pod_name,
+                all_namespaces,
+                output,
+                labels,
+                annotations,
+                env_vars,
+                events,
+                group_by,
+                filename,
+                ..
+            } => {
+                if let Some(ref pod) = pod_name
+                    && all_namespaces
+                {
+                    warn!(
+                        pod = %pod,
+                        "Warning: Pod name specified with --all-namespaces flag. Pod names are unique within a namespace, so searching across all namespaces may be inefficient."
+                    );
+                }
+
+                debug!(
+                    namespace = %namespace,
+                    node = ?node,
+                    pod = ?pod_name,
+                    all_namespaces = %all_namespaces,
+                    output = ?output,
+                    labels = %labels,
+                    annotations = %annotations,
+                    group_by = ?group_by,
+                    "Processing..."
+                );
+
+                let mut pods = match &filename {
+                    Some(path) => {
+                        let mut pods = read_manifest_pods(path, &namespace)
+                            .context("Failed to read manifests")?;
+                        if let Some(name) = &pod_name {
+                            pods.retain(|pod| &pod.name == name);
+                        }
+                        pods
+                    }
+                    None => client
+                        .get_pods(
+                            &namespace,
+                            all_namespaces,
+                            node.as_deref(),
+                            pod_name.as_deref(),
+                        )
+                        .await
+                        .context("Failed to get pods")?,
+                };
+
+                // Pods read from manifests were never scheduled, so they have no events
+                if events && filename.is_none() {
+                    client
+                        .attach_pod_events(&mut pods, &namespace, all_namespaces)
+                        .await
+                        .context("Failed to get pod events")?;
+                }
+
+                match &group_by {
+                    Some(group_by) => {
+                        let nodes = if matches!(group_by, GroupBy::Node) && filename.is_none() {
+                            if !all_namespaces {
+                                warn!(
+                                    "Grouping by node without --all-namespaces only accounts for pods in namespace {}",
+                                    namespace
+                                );
+                            }
+                            client
+                                .get_nodes(node.as_deref())
+                                .await
+                                .context("Failed to get nodes")?
+                        } else {
+                            Vec::new()
+                        };
+
+                        display_pod_groups(&pods, &nodes, group_by, |group| {
+                            display_pods(
+                                group,
+                                &output,
+                                labels,
+                                annotations,
+                                all_namespaces,
+                                env_vars.as_ref(),
+                                events,
+                            )
+                        })?;
+                    }
+                    None => display_pods(
+                        &pods,
+                        &output,
+                        labels,
+                        annotations,
+                        all_namespaces,
+                        env_vars.as_ref(),
+                        events,
+                    )?,
+                }
+            }
+            GetResources::Images {
+                namespace,
+                all_namespaces,
+                output,
+                ..
+            } => {
+                debug!(
+                    namespace = %namespace,
+                    all_namespaces = %all_namespaces,
+                    output = ?output,
+                    "Processing..."
+                );
+
+                let pods = client
+                    .get_pods(&namespace, all_namespaces, None, None)
+                    .await
+                    .context("Failed to get pods")?;
+
+                display_images(&aggregate_images(&pods), &output)?;
+            }
+            GetResources::Nodes {
+                node_name,
+                output,
+                labels,
+                annotations,
+                events,
+                ..
+            } => {
+                debug!(
+                    node = ?node_name,
+                    output = ?output,
+                    labels = %labels,
+                    annotations = %annotations,
+                    "Processing..."
+                );
+
+                let mut nodes = client
+                    .get_nodes(node_name.as_deref())
+                    .await
+                    .context("Failed to get nodes")?;
+
+                if events {
+                    client
+                        .attach_node_events(&mut nodes)
+                        .await
+                        .context("Failed to get node events")?;
+                }
+
+                display_nodes(&nodes, &output, labels, annotations, events)?;
+            }
+        },
+        Commands::Describe { resource } => match resource {
+            DescribeResources::Pod {
+                pod_name,
+                namespace,
+            } => {
+                debug!(namespace = %namespace, pod = %pod_name, "Processing...");
+
+                let mut pod = client
+                    .get_pod(&namespace, &pod_name)
+                    .await
+                    .context("Failed to get pod")?;
+
+                client
+                    .resolve_owner_chain(&namespace, &mut pod.owner_chain)
+                    .await;
+                client
+                    .resolve_pod_env(&mut pod)
+                    .await
+                    .context("Failed to resolve environment variables")?;
+                pod.events = client
+                    .get_events(
+                        &namespace,
+                        false,
+                        Some(&ResourceRef {
+                            kind: "Pod".to_string(),
+                            name: pod_name.clone(),
+                        }),
+                        false,
+                    )
+                    .await
+                    .context("Failed to get pod events")?;
+
+                describe_pod(&pod);
+            }
+            DescribeResources::Node { node_name } => {
+                debug!(node = %node_name, "Processing...");
+
+                let mut node = client
+                    .get_node(&node_name)
+                    .await
+                    .context("Failed to get node")?;
+
+                node.events = client
+                    .get_events(
+                        "default",
+                        false,
+                        Some(&ResourceRef {
+                            kind: "Node".to_string(),
+                            name: node_name.clone(),
+                        }),
+                        false,
+                    )
+                    .await
+                    .context("Failed to get node events")?;
+
+                describe_node(&node);
+            }
+        },
+        Commands::Tree {
+            resource,
+            namespace,
+        } => {
+            debug!(resource = %resource, namespace = %namespace, "Processing...");
+
+            let tree = client
+                .get_owner_tree(&namespace, &resource)
+                .await
+                .context("Failed to get ownership tree")?;
+
+            display_tree(&tree, &resource);
+        }
+        Commands::Events {
+            resource,
+            namespace,
+            all_namespaces,
+            include_pods,
+            watch,
+        } => {
+            debug!(
+                resource = ?resource,
+                namespace = %namespace,
+                all_namespaces = %all_namespaces,
+                include_pods = %include_pods,
+                watch = %watch,
+                "Processing..."
+            );
+
+            let events = client
+                .get_events(&namespace, all_namespaces, resource.as_ref(), include_pods)
+                .await
+                .context("Failed to get events")?;
+
+            display_events(&events, all_namespaces)?;
+
+            if watch {
+                let mut stream = std::pin::pin!(
+                    client
+                        .watch_events(
+                            &namespace,
+                            all_namespaces,
+                            resource.as_ref(),
+                            include_pods,
+                            &events,
+                        )
+                        .await
+                        .context("Failed to watch events")?
+                );
+
+                while let Some(event) = stream.next().await {
+                    print_event(&event?);
+                }
+            }
+        }
+        Commands::Audit { check } => match check {
+            AuditChecks::Security {
+                namespace,
+                all_namespaces,
+            } => {
+                debug!(
+                    namespace = %namespace,
+                    all_namespaces = %all_namespaces,
+                    "Processing..."
+                );
+
+                let pods = client
+                    .list_pods(&namespace, all_namespaces, None)
+                    .await
+                    .context("Failed to get pods")?;
+
+                // Namespace labels are only used for the summary, so a missing permission to read
+                // namespaces should not prevent the pod audit
+                let namespaces = match client.list_namespaces(&namespace, all_namespaces).await {
+                    Ok(namespaces) => namespaces,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to get namespaces, Pod Security labels will not be shown");
+                        Vec::new()
+                    }
+                };
+
+                let reports: Vec<_> = pods.iter().map(audit_pod_security).collect();
+
+                display_security_summary(&summarize_namespaces(&reports, &namespaces))?;
+                println!();
+                display_security_reports(&reports, all_namespaces)?;
+            }
+            AuditChecks::EnvSecrets {
+                namespace,
+                all_namespaces,
+            } => {
+                debug!(
+                    namespace = %namespace,
+                    all_namespaces = %all_namespaces,
+                    "Processing..."
+                );
+
+                let pods = client
+                    .list_pods(&namespace, all_namespaces, None)
+                    .await
+                    .context("Failed to get pods")?;
+
+                let findings: Vec<_> = pods.iter().flat_map(scan_pod_env_secrets).collect();
+
+                display_env_secret_findings(&findings, all_namespaces)?;
+            }
+        },
+        Commands::Lint { target } => match target {
+            LintTargets::Labels {
+                policy,
+                kinds,
+                namespace,
+                all_namespaces,
+                output,
+            } => {
+                debug!(
+                    policy = %policy.display(),
+                    kinds = ?kinds,
+                    namespace = %namespace,
+                    all_namespaces = %all_namespaces,
+                    output = ?output,
+                    "Processing..."
+                );
+
+                let policy = LabelPolicy::from_file(&policy)?;
+                let kinds = if kinds.is_empty() {
+                    policy.default_kinds()
+                } else {
+                    kinds
+                };
+
+                let mut violations = Vec::new();
+                for kind in &kinds {
+                    let objects = client
+                        .list_object_metadata(kind, &namespace, all_namespaces, None)
+                        .await
+                        .with_context(|| format!("Failed to get {}", kind))?;
+                    for object in &objects {
+                        violations.extend(lint_object(&policy, object));
+                    }
+                }
+
+                display_lint_violations(&violations, &output)?;
+
+                let errors = violations
+                    .iter()
+                    .filter(|v| v.severity == LintSeverity::Error)
+                    .count();
+                if errors > 0 {
+                    anyhow::bail!("{} label policy error(s) found", errors);
+                }
+            }
+        },
+        Commands::Diff {
+            left,
+            right,
+            namespace,
+            other_namespace,
+            other_context,
+        } => {
+            let other_namespace = other_namespace.unwrap_or_else(|| namespace.clone());
+            debug!(
+                left = %left,
+                right = %right,
+                namespace = %namespace,
+                other_namespace = %other_namespace,
+                other_context = ?other_context,
+                "Processing..."
+            );
+
+            // The traffic of the other context is recorded to and replayed from a subdirectory
+            let other_client = match &other_context {
+                Some(context) => Some(match &args.replay {
+                    Some(dir) => K8sClient::from_recording(&dir.join(context))
+                        .with_context(|| format!("Failed to load recording of {}", context))?,
+                    None => K8sClient::with_options(&ClientOptions {
+                        context: Some(context.clone()),
+                        record: args.record.as_ref().map(|dir| dir.join(context)),
+                        ..client_options.clone()
+                    })
+                    .await
+                    .with_context(|| format!("Failed to connect to context {}", context))?,
+                }),
+                None => None,
+            };
+            let right_client: &dyn ClusterSource = match &other_client {
+                Some(other_client) => other_client,
+                None => client,
+            };
+            let describe = |resource: &ResourceRef, namespace: &str, context: Option<&str>| {
+                let mut location = Vec::new();
+                if !resource.is_cluster_scoped() {
+                    location.push(namespace.to_string());
+                }
+                if let Some(context) = context {
+                    location.push(format!("context {}", context));
+                }
+                if location.is_empty() {
+                    resource.to_string()
+                } else {
+                    format!("{} ({})", resource, location.join(", "))
+                }
+            };
+
+            let sections = match (left.kind.as_str(), right.kind.as_str()) {
+                ("Pod", "Pod") => {
+                    let left_pod = client
+                        .get_pod(&namespace, &left.name)
+                        .await
+                        .context("Failed to get pod")?;
+                    let right_pod = right_client
+                        .get_pod(&other_namespace, &right.name)
+                        .await
+                        .context("Failed to get pod")?;
+                    diff_pods(&left_pod, &right_pod)
+                }
+                ("Node", "Node") => {
+                    let left_node = client
+                        .get_node(&left.name)
+                        .await
+                        .context("Failed to get node")?;
+                    let right_node = right_client
+                        .get_node(&right.name)
+                        .await
+                        .context("Failed to get node")?;
+                    diff_nodes(&left_node, &right_node)
+                }
+                _ => anyhow::bail!(
+                    "Can only compare two pods or two nodes, got {} and {}",
+                    left,
+                    right
+                ),
+            };
+
+            print_diff(
+                &describe(&left, &namespace, None),
+                &describe(&right, &other_namespace, other_context.as_deref()),
+                &sections,
+            );
+        }
+        Commands::Drift {
+            old,
+            new,
+            namespace,
+            all_namespaces,
+        } => {
+            debug!(
+                old = %old.display(),
+                new = ?new,
+                namespace = %namespace,
+                all_namespaces = %all_namespaces,
+                "Processing..."
+            );
+
+            let old_client = K8sClient::from_snapshot(&old)
+                .await
+                .with_context(|| format!("Failed to load snapshot {}", old.display()))?;
+            let new_name = match new.as_ref().or(args.from_snapshot.as_ref()) {
+                Some(path) => path.display().to_string(),
+                None => "live cluster".to_string(),
+            };
+
+            let old_inventory = collect_inventory(&old_client, &namespace, all_namespaces)
+                .await
+                .context("Failed to read old state")?;
+            let new_inventory = collect_inventory(client, &namespace, all_namespaces)
+                .await
+                .context("Failed to read new state")?;
+
+            let drifts = compare_inventories(&old_inventory, &new_inventory);
+            display_drift(&old.display().to_string(), &new_name, &drifts);
+        }
+        Commands::Node { action } => match action {
+            NodeActions::Cordon { nodes, dry_run } => {
+                set_unschedulable(client, &nodes, true, dry_run).await?;
+            }
+            NodeActions::Uncordon { nodes, dry_run } => {
+                set_unschedulable(client, &nodes, false, dry_run).await?;
+            }
+            NodeActions::Drain {
+                nodes,
+                grace_period,
+                timeout,
+                force,
+                delete_emptydir_data,
+                dry_run,
+            } => {
+                debug!(
+                    nodes = ?nodes,
+                    grace_period = ?grace_period,
+                    timeout = ?timeout,
+                    force = %force,
+                    delete_emptydir_data = %delete_emptydir_data,
+                    dry_run = %dry_run,
+                    "Processing..."
+                );
+
+                let options = DrainOptions {
+                    grace_period,
+                    timeout,
+                    force,
+                    delete_emptydir_data,
+                    server_dry_run: dry_run == DryRunMode::Server,
+                };
+
+                let mut failed = 0;
+                for node in client
+                    .select_nodes(nodes.node_name.as_deref(), nodes.selector.as_deref())
+                    .await
+                    .context("Failed to get nodes")?
+                {
+                    let plan = client
+                        .plan_drain(&node, &options)
+                        .await
+                        .with_context(|| format!("Failed to get pods on node {}", node))?;
+
+                    if dry_run == DryRunMode::Client {
+                        display_evictions(&node, &plan)?;
+                        continue;
+                    }
+
+                    if client
+                        .set_unschedulable(&node, true, options.server_dry_run)
+                        .await
+                        .with_context(|| format!("Failed to cordon node {}", node))?
+                    {
+                        println!("node/{} cordoned", node);
+                    }
+
+                    let progress = Box::new(EvictionProgress::new(&node));
+                    let result = client
+                        .evict_pods(plan, &options, progress)
+                        .await
+                        .with_context(|| format!("Failed to drain node {}", node))?;
+
+                    failed += result
+                        .iter()
+                        .filter(|pod| matches!(pod.state, EvictionState::Failed(_)))
+                        .count();
+                }
+
+                if failed > 0 {
+                    anyhow::bail!("Failed to evict {} pod(s)", failed);
+                }
+            }
+        },
+        Commands::Auth { action } => match action {
+            AuthActions::CanI {
+                namespaces,
+                all_namespaces,
+            } => {
+                debug!(
+                    namespaces = ?namespaces,
+                    all_namespaces = %all_namespaces,
+                    "Processing..."
+                );
+
+                let namespaces: Vec<Option<String>> = if all_namespaces {
+                    vec![None]
+                } else {
+                    namespaces.into_iter().map(Some).collect()
+                };
+                let accesses = review_access(client, &namespaces)
+                    .await
+                    .context("Failed to review permissions")?;
+                display_access(&accesses, &namespaces)?;
+            }
+        },
+        Commands::Cluster { action } => match action {
+            ClusterActions::Info => {
+                debug!("Processing...");
+
+                let info = client
+                    .cluster_info()
+                    .await
+                    .context("Failed to get cluster information")?;
+                describe_cluster(&info);
+            }
+        },
+        Commands::Snapshot { action } => match action {
+            SnapshotActions::Save {
+                file,
+                namespace,
+                all_namespaces,
+            } => {
+                debug!(
+                    file = %file.display(),
+                    namespace = %namespace,
+                    all_namespaces = %all_namespaces,
+                    "Processing..."
+                );
+
+                let snapshot = client
+                    .create_snapshot(&namespace, all_namespaces)
+                    .await
+                    .context("Failed to create snapshot")?;
+                snapshot.write(&file)?;
+
+                println!(
+                    "Saved {} objects to {}",
+                    snapshot.object_count(),
+                    file.display()
+                );
+            }
+        },
+        Commands::Label { update } => {
+            update_metadata(client, MetadataField::Labels, update).await?;
+        }
+        Commands::Annotate { update } => {
+            update_metadata(client, MetadataField::Annotations, update).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Cordon or uncordon all selected nodes
+#[instrument(skip(client), level = "debug")]
+async fn set_unschedulable(
+    client: &dyn ClusterSource,
+    nodes: &NodeSelection,
+    unschedulable: bool,
+    dry_run: DryRunMode,
+) -> FakosResult<()> {
+    let action = if unschedulable {
+        "cordoned"
+    } else {
+        "uncordoned"
+    };
+    for node in client
+        .select_nodes(nodes.node_name.as_deref(), nodes.selector.as_deref())
+        .await
+        .context("Failed to get nodes")?
+    {
+        if dry_run == DryRunMode::Client {
+            println!("node/{} {} (dry run)", node, action);
+            continue;
+        }
+
+        let changed = client
+            .set_unschedulable(&node, unschedulable, dry_run == DryRunMode::Server)
+            .await
+            .with_context(|| format!("Failed to update node {}", node))?;
+        match (changed, dry_run) {
+            (false, _) => println!("node/{} already {}", node, action),
+            (true, DryRunMode::Server) => println!("node/{} {} (server dry run)", node, action),
+            (true, _) => println!("node/{} {}", node, action),
+        }
+    }
+    Ok(())
+}
+
+/// Apply label or annotation changes to all objects matching the selector
+///
+/// The changes are planned and shown as a diff for every object before anything is modified,
+/// so a conflicting key on one object aborts the whole operation.
+#[instrument(skip(client), level = "debug")]
+async fn update_metadata(
+    client: &dyn ClusterSource,
+    field: MetadataField,
+    update: MetadataUpdateArgs,
+) -> FakosResult<()> {
+    let kind = update.resource.kind();
+    let objects = client
+        .list_object_metadata(
+            kind,
+            &update.namespace,
+            update.all_namespaces,
+            update.selector.as_deref(),
+        )
+        .await
+        .with_context(|| format!("Failed to get {}", kind))?;
+
+    let mut planned = Vec::new();
+    for object in &objects {
+        let after = plan_metadata_changes(field.values(object), &update.changes, update.overwrite)
+            .map_err(|e| anyhow::anyhow!("{}/{}: {}", kind.to_lowercase(), object.name, e))?;
+        if &after != field.values(object) {
+            planned.push((object, after));
+        }
+    }
+
+    if planned.is_empty() {
+        warn!("No objects need to be changed");
+        return Ok(());
+    }
+
+    for (object, after) in &planned {
+        print_metadata_diff(object, field.values(object), after);
+    }
+
+    if update.dry_run == DryRunMode::Client {
+        info!(objects = planned.len(), "Dry run, no changes applied");
+        return Ok(());
+    }
+
+    let server_dry_run = update.dry_run == DryRunMode::Server;
+    if !server_dry_run
+        && !update.yes
+        && planned.len() > update.confirm_threshold
+        && !confirm(&format!("Apply changes to {} objects?", planned.len()))?
+    {
+        return Err(K8sError::Aborted("no changes applied".to_string()).into());
+    }
+
+    let mut failed = 0;
+    for (object, after) in &planned {
+        match client
+            .patch_metadata(object, field, after, server_dry_run)
+            .await
+        {
+            Ok(result) if server_dry_run => {
+                if &result != after {
+                    warn!(
+                        object = %object.name,
+                        "API server would store different values than planned (e.g. due to an admission webhook)"
+                    );
+                    print_metadata_diff(object, after, &result);
+                }
+            }
+            Ok(_) => println!("{}/{} updated", kind.to_lowercase(), object.name),
+            Err(e) => {
+                error!(object = %object.name, error = %e, "Failed to update");
+                failed += 1;
+            }
+        }
+    }
+
+    if server_dry_run {
+        info!(
+            objects = planned.len(),
+            "Server dry run, no changes applied"
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("Failed to update {} of {} objects", failed, planned.len());
+    }
+    Ok(())
+}
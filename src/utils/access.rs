@@ -0,0 +1,60 @@
+use super::{TableDisplayError, create_table};
+use crate::k8s::{FarosAccess, required_permissions};
+use prettytable::{Cell, Row};
+
+/// Display a matrix of the permissions needed by fakos commands, allowed or denied per namespace
+///
+/// Cluster-scoped permissions do not depend on the namespace and are shown in every column.
+///
+/// # Arguments
+///
+/// * `accesses` - The checked permissions (see [`crate::review_access`])
+/// * `namespaces` - The namespaces that were checked, with None for all namespaces
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_access(
+    accesses: &[FarosAccess],
+    namespaces: &[Option<String>],
+) -> Result<(), TableDisplayError> {
+    let mut table = create_table()?;
+    let mut header_cells = vec![Cell::new("PERMISSION")];
+    header_cells.extend(namespaces.iter().map(|namespace| {
+        Cell::new(&match namespace {
+            Some(namespace) => namespace.clone(),
+            None => "ALL NAMESPACES".to_string(),
+        })
+    }));
+    header_cells.push(Cell::new("NEEDED BY"));
+    table.add_row(Row::new(header_cells));
+
+    for permission in required_permissions() {
+        let mut row_cells = vec![Cell::new(&permission.to_string())];
+        for namespace in namespaces {
+            let access = accesses.iter().find(|access| {
+                access.permission == *permission
+                    && (permission.cluster_scoped || access.namespace == *namespace)
+            });
+            row_cells.push(match access {
+                Some(access) if access.allowed => Cell::new("yes").style_spec("Fg"),
+                Some(_) => Cell::new("no").style_spec("Fr"),
+                None => Cell::new("<unknown>"),
+            });
+        }
+        row_cells.push(Cell::new(&permission.commands.join(", ")));
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.printstd();
+
+    let denied = accesses.iter().filter(|access| !access.allowed).count();
+    if denied > 0 {
+        println!(
+            "\n{} of {} checks denied; commands that need a denied permission fail with exit code 10",
+            denied,
+            accesses.len()
+        );
+    }
+    Ok(())
+}
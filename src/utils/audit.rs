@@ -0,0 +1,200 @@
+use super::{TableDisplayError, create_table};
+use crate::audit::{
+    EnvSecretFinding, NamespaceSecuritySummary, PodSecurityLevel, PodSecurityReport,
+    SecretConfidence,
+};
+use prettytable::{Cell, Row};
+use tracing::warn;
+
+/// Display per-pod security findings in a formatted table
+///
+/// # Arguments
+///
+/// * `reports` - The per-pod audit reports
+/// * `show_namespace` - Whether to show the namespace column
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_security_reports(
+    reports: &[PodSecurityReport],
+    show_namespace: bool,
+) -> Result<(), TableDisplayError> {
+    if reports.is_empty() {
+        warn!("No pods found matching criteria");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    let mut header_cells = Vec::new();
+    if show_namespace {
+        header_cells.push(Cell::new("NAMESPACE"));
+    }
+    header_cells.push(Cell::new("POD"));
+    header_cells.push(Cell::new("LEVEL"));
+    header_cells.push(Cell::new("FINDINGS"));
+    table.add_row(Row::new(header_cells));
+
+    for report in reports {
+        let findings = report
+            .findings
+            .iter()
+            .map(|finding| {
+                let scope = match finding.violates {
+                    Some(level) => level.to_string(),
+                    None => "advice".to_string(),
+                };
+                match &finding.container {
+                    Some(container) => format!("[{}] {}: {}", scope, container, finding.message),
+                    None => format!("[{}] {}", scope, finding.message),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut row_cells = Vec::new();
+        if show_namespace {
+            row_cells.push(Cell::new(&report.namespace));
+        }
+        row_cells.push(Cell::new(&report.name));
+        row_cells.push(Cell::new(&report.level.to_string()).style_spec(level_style(report.level)));
+        row_cells.push(Cell::new(&if findings.is_empty() {
+            "<none>".to_string()
+        } else {
+            findings.join("\n")
+        }));
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.printstd();
+    Ok(())
+}
+
+/// Display the per-namespace Pod Security Standards summary in a formatted table
+///
+/// # Arguments
+///
+/// * `summaries` - The per-namespace summaries
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_security_summary(
+    summaries: &[NamespaceSecuritySummary],
+) -> Result<(), TableDisplayError> {
+    if summaries.is_empty() {
+        warn!("No namespaces found matching criteria");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    table.add_row(Row::new(vec![
+        Cell::new("NAMESPACE"),
+        Cell::new("RESTRICTED"),
+        Cell::new("BASELINE"),
+        Cell::new("PRIVILEGED"),
+        Cell::new("ENFORCE"),
+        Cell::new("AUDIT"),
+        Cell::new("WARN"),
+        Cell::new("VIOLATING ENFORCE"),
+    ]));
+
+    for summary in summaries {
+        let count = |level| {
+            summary
+                .pods_per_level
+                .get(&level)
+                .copied()
+                .unwrap_or_default()
+                .to_string()
+        };
+        let label = |value: &Option<String>| value.clone().unwrap_or_else(|| "<none>".to_string());
+
+        table.add_row(Row::new(vec![
+            Cell::new(&summary.namespace),
+            Cell::new(&count(PodSecurityLevel::Restricted)),
+            Cell::new(&count(PodSecurityLevel::Baseline)),
+            Cell::new(&count(PodSecurityLevel::Privileged)),
+            Cell::new(&label(&summary.enforce)),
+            Cell::new(&label(&summary.audit)),
+            Cell::new(&label(&summary.warn)),
+            Cell::new(&summary.violating_enforce.to_string()).style_spec(
+                if summary.violating_enforce > 0 {
+                    "Fr"
+                } else {
+                    ""
+                },
+            ),
+        ]));
+    }
+
+    table.printstd();
+    Ok(())
+}
+
+fn level_style(level: PodSecurityLevel) -> &'static str {
+    match level {
+        PodSecurityLevel::Privileged => "Fr",
+        PodSecurityLevel::Baseline => "Fy",
+        PodSecurityLevel::Restricted => "Fg",
+    }
+}
+
+/// Display likely credentials found in literal environment variable values
+///
+/// # Arguments
+///
+/// * `findings` - The env-secrets findings, with values already masked
+/// * `show_namespace` - Whether to show the namespace column
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_env_secret_findings(
+    findings: &[EnvSecretFinding],
+    show_namespace: bool,
+) -> Result<(), TableDisplayError> {
+    if findings.is_empty() {
+        warn!("No plaintext secrets found in environment variables");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    let mut header_cells = Vec::new();
+    if show_namespace {
+        header_cells.push(Cell::new("NAMESPACE"));
+    }
+    header_cells.push(Cell::new("POD"));
+    header_cells.push(Cell::new("CONTAINER"));
+    header_cells.push(Cell::new("VARIABLE"));
+    header_cells.push(Cell::new("VALUE"));
+    header_cells.push(Cell::new("CONFIDENCE"));
+    header_cells.push(Cell::new("REASONS"));
+    table.add_row(Row::new(header_cells));
+
+    for finding in findings {
+        let mut row_cells = Vec::new();
+        if show_namespace {
+            row_cells.push(Cell::new(&finding.namespace));
+        }
+        row_cells.push(Cell::new(&finding.pod));
+        row_cells.push(Cell::new(&finding.container));
+        row_cells.push(Cell::new(&finding.variable));
+        row_cells.push(Cell::new(&finding.masked_value));
+        row_cells.push(Cell::new(&finding.confidence.to_string()).style_spec(
+            match finding.confidence {
+                SecretConfidence::High => "Fr",
+                SecretConfidence::Medium => "Fy",
+                SecretConfidence::Low => "",
+            },
+        ));
+        row_cells.push(Cell::new(&finding.reasons.join("\n")));
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.printstd();
+    println!(
+        "\n{} variable(s) should be moved to a Secret and referenced with valueFrom.secretKeyRef",
+        findings.len()
+    );
+    Ok(())
+}
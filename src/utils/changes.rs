@@ -0,0 +1,64 @@
+use crate::k8s::FarosObjectMeta;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+/// Print a coloured per-key diff of a label or annotation map of an object
+///
+/// # Arguments
+///
+/// * `object` - The object being changed
+/// * `before` - The current values
+/// * `after` - The values after the change
+pub fn print_metadata_diff(
+    object: &FarosObjectMeta,
+    before: &BTreeMap<String, String>,
+    after: &BTreeMap<String, String>,
+) {
+    let name = match &object.namespace {
+        Some(namespace) => format!(
+            "{}/{} ({})",
+            object.kind.to_lowercase(),
+            object.name,
+            namespace
+        ),
+        None => format!("{}/{}", object.kind.to_lowercase(), object.name),
+    };
+    println!("{}", name.bold());
+
+    for (key, value) in before {
+        match after.get(key) {
+            Some(new_value) if new_value == value => {}
+            _ => println!("{}", format!("  - {}={}", key, value).red()),
+        }
+    }
+    for (key, value) in after {
+        if before.get(key) != Some(value) {
+            println!("{}", format!("  + {}={}", key, value).green());
+        }
+    }
+}
+
+/// Ask the user to confirm an action on standard input
+///
+/// # Arguments
+///
+/// * `prompt` - The question to ask, without the `[y/N]` suffix
+///
+/// # Returns
+///
+/// * `Result<bool>` - True if the user answered yes; end of input counts as no
+pub fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    std::io::stdout()
+        .flush()
+        .context("Failed to write prompt")?;
+
+    let mut answer = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .context("Failed to read answer")?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,259 @@
+use super::events::format_age;
+use super::format_metadata;
+use crate::k8s::{
+    FarosClusterInfo, FarosCondition, FarosContainer, FarosEvent, FarosNode, FarosPod,
+};
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+/// Width of the label column in describe output
+const LABEL_WIDTH: usize = 18;
+
+/// Print a detailed, sectioned report of a single pod
+///
+/// # Arguments
+///
+/// * `pod` - The pod to describe, with owner chain, environment and events resolved as needed
+pub fn describe_pod(pod: &FarosPod) {
+    print_field(0, "Name", &pod.name);
+    print_field(0, "Namespace", &pod.namespace);
+    print_field(0, "Node", pod.node.as_deref().unwrap_or("<none>"));
+    print_field(0, "Phase", &pod.phase);
+    print_field(0, "IP", pod.pod_ip.as_deref().unwrap_or("<none>"));
+    print_field(
+        0,
+        "Service Account",
+        pod.service_account.as_deref().unwrap_or("<none>"),
+    );
+    print_field(0, "QoS Class", pod.qos_class.as_deref().unwrap_or("<none>"));
+    print_field(0, "Age", &format_age(pod.created));
+    print_field(0, "Labels", &format_metadata(&pod.labels));
+    print_field(0, "Annotations", &format_metadata(&pod.annotations));
+    print_field(0, "Node Selector", &format_metadata(&pod.node_selector));
+    print_field(
+        0,
+        "Owner Chain",
+        &or_none(
+            pod.owner_chain
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join(" -> "),
+        ),
+    );
+
+    print_section("Containers");
+    if pod.containers.is_empty() {
+        println!("  <none>");
+    }
+    for container in &pod.containers {
+        describe_container(container, pod.container_env_vars.get(&container.name));
+    }
+
+    print_section("Conditions");
+    print_conditions(&pod.conditions);
+
+    print_section("Tolerations");
+    print_list(&pod.tolerations);
+
+    print_section("Affinity");
+    print_list(&pod.affinity);
+
+    print_section("Volumes");
+    print_list(&pod.volumes);
+
+    print_section("Events");
+    print_events(&pod.events);
+}
+
+/// Print a detailed, sectioned report of a single node
+///
+/// # Arguments
+///
+/// * `node` - The node to describe, with events resolved as needed
+pub fn describe_node(node: &FarosNode) {
+    print_field(0, "Name", &node.name);
+    print_field(0, "Status", &node.status);
+    print_field(0, "Unschedulable", &node.unschedulable.to_string());
+    print_field(0, "Age", &format_age(node.created));
+    print_field(0, "Labels", &format_metadata(&node.labels));
+    print_field(0, "Annotations", &format_metadata(&node.annotations));
+    print_field(0, "Taints", &or_none(node.taints.join("\n")));
+    print_field(0, "Addresses", &or_none(node.addresses.join("\n")));
+
+    print_section("System Info");
+    for (key, value) in &node.system_info {
+        print_field(1, key, value);
+    }
+
+    print_section("Capacity");
+    print_list(&format_pairs(&node.capacity));
+
+    print_section("Allocatable");
+    print_list(&format_pairs(&node.allocatable));
+
+    print_section("Conditions");
+    print_conditions(&node.conditions);
+
+    print_section("Events");
+    print_events(&node.events);
+}
+
+/// Print the version, connection and identity of a cluster and the API groups it serves
+///
+/// # Arguments
+///
+/// * `info` - The cluster information, as seen by the current user
+pub fn describe_cluster(info: &FarosClusterInfo) {
+    print_field(
+        0,
+        "Server Version",
+        info.server_version.as_deref().unwrap_or("<unknown>"),
+    );
+    print_field(
+        0,
+        "Platform",
+        info.platform.as_deref().unwrap_or("<unknown>"),
+    );
+    print_field(
+        0,
+        "Endpoint",
+        info.endpoint.as_deref().unwrap_or("<not connected>"),
+    );
+    print_field(
+        0,
+        "Auth Method",
+        info.auth_method.as_deref().unwrap_or("<none>"),
+    );
+
+    print_section("Identity");
+    print_field(1, "User", info.username.as_deref().unwrap_or("<unknown>"));
+    print_field(1, "Groups", &or_none(info.groups.join("\n")));
+
+    print_section("API Groups");
+    print_list(&info.api_groups);
+}
+
+fn describe_container(container: &FarosContainer, env_vars: Option<&BTreeMap<String, String>>) {
+    println!("  {} ({}):", container.name.bold(), container.kind);
+    print_field(2, "Image", &container.image);
+    print_field(
+        2,
+        "Image ID",
+        container.image_id.as_deref().unwrap_or("<none>"),
+    );
+    print_field(2, "State", &container.state);
+    print_field(2, "Ready", &container.ready.to_string());
+    print_field(2, "Restart Count", &container.restart_count.to_string());
+    print_field(2, "Ports", &or_none(container.ports.join("\n")));
+    print_field(
+        2,
+        "Requests",
+        &or_none(format_pairs(&container.requests).join("\n")),
+    );
+    print_field(
+        2,
+        "Limits",
+        &or_none(format_pairs(&container.limits).join("\n")),
+    );
+    if let Some(probe) = &container.liveness_probe {
+        print_field(2, "Liveness", probe);
+    }
+    if let Some(probe) = &container.readiness_probe {
+        print_field(2, "Readiness", probe);
+    }
+    if let Some(probe) = &container.startup_probe {
+        print_field(2, "Startup", probe);
+    }
+    print_field(2, "Mounts", &or_none(container.mounts.join("\n")));
+    if !container.env_from.is_empty() {
+        print_field(2, "Environment From", &container.env_from.join("\n"));
+    }
+    print_field(
+        2,
+        "Environment",
+        &env_vars
+            .map(format_metadata)
+            .unwrap_or_else(|| "<none>".to_string()),
+    );
+}
+
+fn print_section(title: &str) {
+    println!("{}", format!("{}:", title).bold());
+}
+
+/// Print a `Label: value` line, aligning continuation lines of multi-line values
+fn print_field(indent: usize, label: &str, value: &str) {
+    let prefix = "  ".repeat(indent);
+    let label = format!("{}{}:", prefix, label);
+    let width = LABEL_WIDTH + prefix.len();
+    let mut lines = value.lines();
+    println!("{:<width$} {}", label, lines.next().unwrap_or_default());
+    for line in lines {
+        println!("{:<width$} {}", "", line);
+    }
+}
+
+fn print_list(items: &[String]) {
+    if items.is_empty() {
+        println!("  <none>");
+    }
+    for item in items {
+        println!("  {}", item);
+    }
+}
+
+fn print_conditions(conditions: &[FarosCondition]) {
+    if conditions.is_empty() {
+        println!("  <none>");
+        return;
+    }
+    println!("  {:<30} {:<8} {:<10} REASON", "TYPE", "STATUS", "AGE");
+    for condition in conditions {
+        println!(
+            "  {:<30} {:<8} {:<10} {}",
+            condition.condition_type,
+            condition.status,
+            format_age(condition.last_transition),
+            condition.reason.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+fn print_events(events: &[FarosEvent]) {
+    if events.is_empty() {
+        println!("  <none>");
+        return;
+    }
+    println!(
+        "  {:<10} {:<8} {:<24} {:<6} MESSAGE",
+        "LAST SEEN", "TYPE", "REASON", "COUNT"
+    );
+    for event in events {
+        let event_type = format!("{:<8}", event.event_type);
+        println!(
+            "  {:<10} {} {:<24} {:<6} {}",
+            format_age(event.last_seen),
+            if event.is_warning() {
+                event_type.yellow().bold()
+            } else {
+                event_type.green()
+            },
+            event.reason,
+            event.count,
+            event.message.replace('\n', " ")
+        );
+    }
+}
+
+fn format_pairs(map: &BTreeMap<String, String>) -> Vec<String> {
+    map.iter().map(|(k, v)| format!("{}={}", k, v)).collect()
+}
+
+fn or_none(value: String) -> String {
+    if value.is_empty() {
+        "<none>".to_string()
+    } else {
+        value
+    }
+}
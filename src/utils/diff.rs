@@ -0,0 +1,157 @@
+use crate::k8s::{FarosNode, FarosPod};
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A key whose value differs between two maps
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    /// The key
+    pub key: String,
+    /// Value in the first map, or None if the key is missing there
+    pub left: Option<String>,
+    /// Value in the second map, or None if the key is missing there
+    pub right: Option<String>,
+}
+
+/// Differences of one section (e.g. labels) between two objects
+#[derive(Debug, Clone)]
+pub struct DiffSection {
+    /// Title of the section (e.g. `Labels`, `Env (container app)`)
+    pub title: String,
+    /// Keys that differ
+    pub entries: Vec<DiffEntry>,
+    /// Number of keys with identical values
+    pub identical: usize,
+}
+
+/// Compare two string maps key by key
+///
+/// # Arguments
+///
+/// * `left` - The first map
+/// * `right` - The second map
+///
+/// # Returns
+///
+/// * `(Vec<DiffEntry>, usize)` - The differing keys, sorted, and the number of identical keys
+pub fn diff_maps(
+    left: &BTreeMap<String, String>,
+    right: &BTreeMap<String, String>,
+) -> (Vec<DiffEntry>, usize) {
+    let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    let mut entries = Vec::new();
+    let mut identical = 0;
+    for key in keys {
+        let (l, r) = (left.get(key), right.get(key));
+        if l == r {
+            identical += 1;
+        } else {
+            entries.push(DiffEntry {
+                key: key.clone(),
+                left: l.cloned(),
+                right: r.cloned(),
+            });
+        }
+    }
+    (entries, identical)
+}
+
+fn section(
+    title: &str,
+    left: &BTreeMap<String, String>,
+    right: &BTreeMap<String, String>,
+) -> DiffSection {
+    let (entries, identical) = diff_maps(left, right);
+    DiffSection {
+        title: title.to_string(),
+        entries,
+        identical,
+    }
+}
+
+/// Compare the labels, annotations and per-container environment variables of two pods
+///
+/// # Arguments
+///
+/// * `left` - The first pod
+/// * `right` - The second pod
+///
+/// # Returns
+///
+/// * `Vec<DiffSection>` - One section per compared map
+pub fn diff_pods(left: &FarosPod, right: &FarosPod) -> Vec<DiffSection> {
+    let mut sections = vec![
+        section("Labels", &left.labels, &right.labels),
+        section("Annotations", &left.annotations, &right.annotations),
+    ];
+
+    let empty = BTreeMap::new();
+    let containers: BTreeSet<&String> = left
+        .container_env_vars
+        .keys()
+        .chain(right.container_env_vars.keys())
+        .collect();
+    for container in containers {
+        sections.push(section(
+            &format!("Env (container {})", container),
+            left.container_env_vars.get(container).unwrap_or(&empty),
+            right.container_env_vars.get(container).unwrap_or(&empty),
+        ));
+    }
+    sections
+}
+
+/// Compare the labels, annotations and taints of two nodes
+///
+/// # Arguments
+///
+/// * `left` - The first node
+/// * `right` - The second node
+///
+/// # Returns
+///
+/// * `Vec<DiffSection>` - One section per compared map
+pub fn diff_nodes(left: &FarosNode, right: &FarosNode) -> Vec<DiffSection> {
+    vec![
+        section("Labels", &left.labels, &right.labels),
+        section("Annotations", &left.annotations, &right.annotations),
+        section("Taints", &left.taints_by_key(), &right.taints_by_key()),
+    ]
+}
+
+/// Print a coloured key-level diff between two objects
+///
+/// # Arguments
+///
+/// * `left_name` - Description of the first object (e.g. `pod/a (default)`)
+/// * `right_name` - Description of the second object
+/// * `sections` - The differences to print
+pub fn print_diff(left_name: &str, right_name: &str, sections: &[DiffSection]) {
+    println!("{}", format!("--- {}", left_name).red().bold());
+    println!("{}", format!("+++ {}", right_name).green().bold());
+
+    for section in sections {
+        if section.entries.is_empty() && section.identical == 0 {
+            continue;
+        }
+        println!("{}", format!("{}:", section.title).bold());
+        for entry in &section.entries {
+            if let Some(value) = &entry.left {
+                println!("{}", format!("  - {}={}", entry.key, value).red());
+            }
+            if let Some(value) = &entry.right {
+                println!("{}", format!("  + {}={}", entry.key, value).green());
+            }
+        }
+        if section.identical > 0 {
+            println!(
+                "{}",
+                format!("  ({} identical keys)", section.identical).dimmed()
+            );
+        }
+    }
+
+    if sections.iter().all(|s| s.entries.is_empty()) {
+        println!("No differences found");
+    }
+}
@@ -0,0 +1,135 @@
+use super::{TableDisplayError, create_table};
+use crate::k8s::{EvictionObserver, EvictionState, PodEviction};
+use prettytable::{Cell, Row, Table};
+use std::io::{IsTerminal, Write};
+use tracing::debug;
+
+/// Table of pod evictions on a node that is redrawn in place as evictions progress
+///
+/// When standard output is not a terminal, only the final state is printed.
+pub struct EvictionProgress {
+    node: String,
+    live: bool,
+    printed_lines: usize,
+}
+
+impl EvictionProgress {
+    /// Create a progress display for a node
+    ///
+    /// # Arguments
+    ///
+    /// * `node` - Name of the node being drained
+    pub fn new(node: &str) -> Self {
+        Self {
+            node: node.to_string(),
+            live: std::io::stdout().is_terminal(),
+            printed_lines: 0,
+        }
+    }
+
+    /// Redraw the table with the current state of all pods
+    ///
+    /// # Arguments
+    ///
+    /// * `pods` - The current state of all pods on the node
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or error
+    pub fn update(&mut self, pods: &[PodEviction]) -> Result<(), TableDisplayError> {
+        if self.live {
+            self.draw(pods)?;
+        }
+        Ok(())
+    }
+
+    /// Print the final state of all pods
+    ///
+    /// # Arguments
+    ///
+    /// * `pods` - The final state of all pods on the node
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Success or error
+    pub fn finish(&mut self, pods: &[PodEviction]) -> Result<(), TableDisplayError> {
+        self.draw(pods)
+    }
+
+    fn draw(&mut self, pods: &[PodEviction]) -> Result<(), TableDisplayError> {
+        let mut stdout = std::io::stdout();
+        if self.printed_lines > 0 {
+            // Move the cursor back to the start of the previous table and clear it
+            write!(stdout, "\x1b[{}A\x1b[J", self.printed_lines)
+                .map_err(|e| TableDisplayError::new(&e.to_string()))?;
+        }
+
+        let done = pods
+            .iter()
+            .filter(|p| matches!(p.state, EvictionState::Evicted))
+            .count();
+        let total = pods
+            .iter()
+            .filter(|p| !matches!(p.state, EvictionState::Skipped(_)))
+            .count();
+        println!("node/{} ({}/{} pods evicted)", self.node, done, total);
+
+        let lines = eviction_table(pods)?
+            .print_tty(false)
+            .map_err(|e| TableDisplayError::new(&e.to_string()))?;
+        self.printed_lines = lines + 1;
+        Ok(())
+    }
+}
+
+impl EvictionObserver for EvictionProgress {
+    fn on_progress(&mut self, pods: &[PodEviction]) {
+        if let Err(e) = self.update(pods) {
+            debug!(error = %e, "Failed to update progress");
+        }
+    }
+
+    fn on_finish(&mut self, pods: &[PodEviction]) -> anyhow::Result<()> {
+        Ok(self.finish(pods)?)
+    }
+}
+
+/// Display the pods of a node and what a drain would do with them
+///
+/// # Arguments
+///
+/// * `node` - Name of the node
+/// * `pods` - The drain plan of the node
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_evictions(node: &str, pods: &[PodEviction]) -> Result<(), TableDisplayError> {
+    println!("node/{}", node);
+    eviction_table(pods)?.printstd();
+    Ok(())
+}
+
+fn eviction_table(pods: &[PodEviction]) -> Result<Table, TableDisplayError> {
+    let mut table = create_table()?;
+    table.add_row(Row::new(vec![
+        Cell::new("NAMESPACE"),
+        Cell::new("POD"),
+        Cell::new("STATUS"),
+    ]));
+
+    for pod in pods {
+        let style = match pod.state {
+            EvictionState::Evicted => "Fg",
+            EvictionState::Failed(_) | EvictionState::Blocked(_) => "Fr",
+            EvictionState::DisruptionBudget | EvictionState::Terminating => "Fy",
+            _ => "",
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&pod.namespace),
+            Cell::new(&pod.name),
+            Cell::new(&pod.state.to_string()).style_spec(style),
+        ]));
+    }
+    Ok(table)
+}
@@ -0,0 +1,85 @@
+use crate::drift::{DriftChange, ObjectDrift};
+use colored::Colorize;
+use std::collections::BTreeMap;
+
+fn object_name(drift: &ObjectDrift) -> String {
+    match &drift.namespace {
+        Some(namespace) => format!(
+            "{}/{} ({})",
+            drift.kind.to_lowercase(),
+            drift.name,
+            namespace
+        ),
+        None => format!("{}/{}", drift.kind.to_lowercase(), drift.name),
+    }
+}
+
+/// Print a coloured report of added, removed and changed objects followed by a summary per kind
+///
+/// # Arguments
+///
+/// * `old_name` - Description of the old state (e.g. the snapshot file)
+/// * `new_name` - Description of the new state
+/// * `drifts` - The differences to print
+pub fn display_drift(old_name: &str, new_name: &str, drifts: &[ObjectDrift]) {
+    println!("{}", format!("--- {}", old_name).red().bold());
+    println!("{}", format!("+++ {}", new_name).green().bold());
+
+    if drifts.is_empty() {
+        println!("No drift found");
+        return;
+    }
+
+    // Kind -> (added, removed, changed)
+    let mut summary: BTreeMap<&str, (usize, usize, usize)> = BTreeMap::new();
+    for drift in drifts {
+        let counts = summary.entry(drift.kind.as_str()).or_default();
+        match &drift.change {
+            DriftChange::Added => {
+                counts.0 += 1;
+                println!("{}", format!("+ {}", object_name(drift)).green());
+            }
+            DriftChange::Removed => {
+                counts.1 += 1;
+                println!("{}", format!("- {}", object_name(drift)).red());
+            }
+            DriftChange::Changed(changes) => {
+                counts.2 += 1;
+                println!("{}", format!("~ {}", object_name(drift)).yellow());
+                let mut section = None;
+                for change in changes {
+                    if section != Some(&change.section) {
+                        println!("    {}", format!("{}:", change.section).bold());
+                        section = Some(&change.section);
+                    }
+                    let entry = &change.entry;
+                    match (&entry.left, &entry.right) {
+                        (Some(old), Some(new)) => println!(
+                            "{}",
+                            format!("      ~ {}: {} -> {}", entry.key, old, new).yellow()
+                        ),
+                        (Some(old), None) => {
+                            println!("{}", format!("      - {}={}", entry.key, old).red())
+                        }
+                        (None, Some(new)) => {
+                            println!("{}", format!("      + {}={}", entry.key, new).green())
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{}", "Summary:".bold());
+    for (kind, (added, removed, changed)) in summary {
+        println!(
+            "  {}: {} added, {} removed, {} changed",
+            kind,
+            added.to_string().green(),
+            removed.to_string().red(),
+            changed.to_string().yellow()
+        );
+    }
+}
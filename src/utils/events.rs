@@ -0,0 +1,162 @@
+use super::{TableDisplayError, create_table};
+use crate::k8s::FarosEvent;
+use colored::Colorize;
+use k8s_openapi::chrono::{DateTime, Utc};
+use prettytable::{Cell, Row};
+use tracing::warn;
+
+/// Maximum number of events shown per object in the EVENTS column
+const MAX_EVENTS_PER_OBJECT: usize = 3;
+
+/// Maximum length of an event message shown in the EVENTS column
+const MAX_EVENT_MESSAGE_LEN: usize = 80;
+
+/// Display events as a timeline in a formatted table
+///
+/// # Arguments
+///
+/// * `events` - List of deduplicated events to display, oldest first
+/// * `all_namespaces` - Whether to show namespace column (only when querying all namespaces)
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_events(
+    events: &[FarosEvent],
+    all_namespaces: bool,
+) -> Result<(), TableDisplayError> {
+    if events.is_empty() {
+        warn!("No events found matching criteria");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    let mut header_cells = Vec::new();
+
+    if all_namespaces {
+        header_cells.push(Cell::new("NAMESPACE"));
+    }
+    header_cells.push(Cell::new("LAST SEEN"));
+    header_cells.push(Cell::new("FIRST SEEN"));
+    header_cells.push(Cell::new("TYPE"));
+    header_cells.push(Cell::new("REASON"));
+    header_cells.push(Cell::new("OBJECT"));
+    header_cells.push(Cell::new("COUNT"));
+    header_cells.push(Cell::new("MESSAGE"));
+    table.add_row(Row::new(header_cells));
+
+    for event in events {
+        let mut row_cells = Vec::new();
+
+        if all_namespaces {
+            row_cells.push(Cell::new(&event.namespace));
+        }
+        row_cells.push(Cell::new(&format_age(event.last_seen)));
+        row_cells.push(Cell::new(&format_age(event.first_seen)));
+        row_cells.push(Cell::new(&event.event_type).style_spec(event_style(event)));
+        row_cells.push(Cell::new(&event.reason));
+        row_cells.push(Cell::new(&event.object()));
+        row_cells.push(Cell::new(&event.count.to_string()));
+        row_cells.push(Cell::new(&event.message));
+
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.printstd();
+    Ok(())
+}
+
+/// Print a single event as one colour-coded line, used when watching for new events
+///
+/// # Arguments
+///
+/// * `event` - The event to print
+pub fn print_event(event: &FarosEvent) {
+    let timestamp = event
+        .last_seen
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| "<unknown>".to_string());
+    let event_type = if event.is_warning() {
+        event.event_type.yellow().bold()
+    } else {
+        event.event_type.green()
+    };
+    let namespace = if event.namespace.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", event.namespace)
+    };
+
+    println!(
+        "{} {} {}{} {}: {}",
+        timestamp,
+        event_type,
+        namespace,
+        event.object(),
+        event.reason.bold(),
+        event.message
+    );
+}
+
+/// Format the events of a single object for the EVENTS column, most recent first
+///
+/// # Arguments
+///
+/// * `events` - The events attached to the object
+///
+/// # Returns
+///
+/// * `String` - One line per event or `<none>`
+pub(crate) fn format_event_summary(events: &[FarosEvent]) -> String {
+    if events.is_empty() {
+        return "<none>".to_string();
+    }
+
+    events
+        .iter()
+        .rev()
+        .take(MAX_EVENTS_PER_OBJECT)
+        .map(|event| {
+            let mut message: String = event.message.chars().take(MAX_EVENT_MESSAGE_LEN).collect();
+            if event.message.chars().count() > MAX_EVENT_MESSAGE_LEN {
+                message.push_str("...");
+            }
+            format!(
+                "{} x{} ({} ago): {}",
+                event.reason,
+                event.count,
+                format_age(event.last_seen),
+                message.replace('\n', " ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Format a timestamp as a short, kubectl-style age (e.g. 45s, 12m, 3h, 5d)
+///
+/// # Arguments
+///
+/// * `timestamp` - The timestamp to format
+///
+/// # Returns
+///
+/// * `String` - The age relative to now or `<unknown>`
+pub(crate) fn format_age(timestamp: Option<DateTime<Utc>>) -> String {
+    let Some(timestamp) = timestamp else {
+        return "<unknown>".to_string();
+    };
+
+    let seconds = (Utc::now() - timestamp).num_seconds().max(0);
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+/// Table cell style for the event type column
+fn event_style(event: &FarosEvent) -> &'static str {
+    if event.is_warning() { "Fyb" } else { "Fg" }
+}
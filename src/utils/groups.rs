@@ -0,0 +1,168 @@
+use super::TableDisplayError;
+use crate::GroupBy;
+use crate::k8s::quantity::{format_cpu, format_memory};
+use crate::k8s::{FarosNode, FarosPod};
+use colored::Colorize;
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Display pods in sections grouped by node, namespace, owner or label
+///
+/// Each section starts with a summary of the pods in it (counts and summed resource requests).
+/// When grouping by node, requests are compared with the node's allocatable resources and
+/// overcommitted resources are highlighted.
+///
+/// # Arguments
+///
+/// * `pods` - List of pods to display
+/// * `nodes` - Nodes to report capacity for (only used when grouping by node)
+/// * `group_by` - How to group the pods
+/// * `display` - Function displaying the pods of a single group
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_pod_groups<F>(
+    pods: &[FarosPod],
+    nodes: &[FarosNode],
+    group_by: &GroupBy,
+    mut display: F,
+) -> Result<(), TableDisplayError>
+where
+    F: FnMut(&[FarosPod]) -> Result<(), TableDisplayError>,
+{
+    let mut groups: BTreeMap<String, Vec<FarosPod>> = BTreeMap::new();
+    if matches!(group_by, GroupBy::Node) {
+        for node in nodes {
+            groups.entry(node.name.clone()).or_default();
+        }
+    }
+    for pod in pods {
+        groups
+            .entry(group_key(pod, group_by))
+            .or_default()
+            .push(pod.clone());
+    }
+
+    if groups.is_empty() {
+        warn!("No pods found matching criteria");
+        return Ok(());
+    }
+
+    for (i, (key, group)) in groups.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        let node = matches!(group_by, GroupBy::Node)
+            .then(|| nodes.iter().find(|n| &n.name == key))
+            .flatten();
+        print_summary(group_by, key, group, node);
+
+        if !group.is_empty() {
+            println!();
+            display(group)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn group_key(pod: &FarosPod, group_by: &GroupBy) -> String {
+    match group_by {
+        GroupBy::Node => pod
+            .node
+            .clone()
+            .unwrap_or_else(|| "<unscheduled>".to_string()),
+        GroupBy::Namespace => pod.namespace.clone(),
+        GroupBy::Label(key) => pod
+            .labels
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| "<none>".to_string()),
+        GroupBy::Owner => pod
+            .controller
+            .as_ref()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "<none>".to_string()),
+    }
+}
+
+fn print_summary(group_by: &GroupBy, key: &str, pods: &[FarosPod], node: Option<&FarosNode>) {
+    let title = match (group_by, node) {
+        (GroupBy::Node, Some(node)) => {
+            let mut status = node.status.clone();
+            if node.unschedulable {
+                status.push_str(",SchedulingDisabled");
+            }
+            format!("node/{} ({})", key, status)
+        }
+        (GroupBy::Label(label), _) => format!("{}={}", label, key),
+        (group_by, _) => format!("{}: {}", group_by, key),
+    };
+    println!("{}", title.bold());
+
+    // Terminated pods no longer hold on to node resources
+    let active: Vec<&FarosPod> = pods.iter().filter(|p| !p.is_terminated()).collect();
+    let daemonset_pods = active.iter().filter(|p| p.is_daemonset_pod()).count();
+    let counts = format!(
+        "({} DaemonSet, {} workload)",
+        daemonset_pods,
+        active.len() - daemonset_pods
+    );
+
+    let cpu: f64 = active.iter().map(|p| p.resource_request("cpu")).sum();
+    let memory: f64 = active.iter().map(|p| p.resource_request("memory")).sum();
+
+    match node {
+        Some(node) => {
+            println!(
+                "  {:<8} {}",
+                "Pods:",
+                usage(
+                    active.len() as f64,
+                    node.allocatable_quantity("pods"),
+                    |v| { format!("{}", v as i64) }
+                ) + " "
+                    + &counts
+            );
+            println!(
+                "  {:<8} {}",
+                "CPU:",
+                usage(cpu, node.allocatable_quantity("cpu"), format_cpu)
+            );
+            println!(
+                "  {:<8} {}",
+                "Memory:",
+                usage(memory, node.allocatable_quantity("memory"), format_memory)
+            );
+        }
+        None => {
+            println!("  {:<8} {} {}", "Pods:", active.len(), counts);
+            println!("  {:<8} {} requested", "CPU:", format_cpu(cpu));
+            println!("  {:<8} {} requested", "Memory:", format_memory(memory));
+        }
+    }
+}
+
+/// Format requested vs allocatable, highlighting overcommitted resources
+fn usage(requested: f64, allocatable: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    let Some(allocatable) = allocatable.filter(|a| *a > 0.0) else {
+        return format!("{} requested", format(requested));
+    };
+
+    let percent = requested / allocatable * 100.0;
+    let text = format!(
+        "{}/{} requested ({:.0}%)",
+        format(requested),
+        format(allocatable),
+        percent
+    );
+    if percent > 100.0 {
+        format!("{} {}", text.red().bold(), "OVERCOMMITTED".red().bold())
+    } else if percent >= 90.0 {
+        text.yellow().to_string()
+    } else {
+        text
+    }
+}
@@ -0,0 +1,102 @@
+use super::{TableDisplayError, create_table};
+use crate::OutputFormat;
+use crate::k8s::FarosImage;
+use prettytable::{Cell, Row};
+use std::collections::BTreeSet;
+use tracing::warn;
+
+/// Display container images in a formatted table
+///
+/// # Arguments
+///
+/// * `images` - List of aggregated images to display
+/// * `output_format` - Format to use for displaying the images
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_images(
+    images: &[FarosImage],
+    output_format: &OutputFormat,
+) -> Result<(), TableDisplayError> {
+    if images.is_empty() {
+        warn!("No images found matching criteria");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    let mut header_cells = vec![
+        Cell::new("REPOSITORY"),
+        Cell::new("TAG"),
+        Cell::new("DIGEST"),
+        Cell::new("PODS"),
+        Cell::new("FLAGS"),
+    ];
+    if matches!(output_format, OutputFormat::Wide) {
+        header_cells.push(Cell::new("CONTAINER KINDS"));
+        header_cells.push(Cell::new("NAMESPACES"));
+    }
+    table.add_row(Row::new(header_cells));
+
+    for image in images {
+        let digests = if image.digests.is_empty() {
+            "<none>".to_string()
+        } else {
+            image.digests.iter().cloned().collect::<Vec<_>>().join("\n")
+        };
+
+        let mut flags = Vec::new();
+        if image.is_latest() {
+            flags.push("latest-tag".to_string());
+        }
+        if image.has_digest_mismatch() {
+            flags.push("digest-mismatch".to_string());
+        }
+        if !image.pull_errors.is_empty() {
+            flags.push(format!("pull-error ({} pods)", image.pull_errors.len()));
+        }
+        let flag_style = if !image.pull_errors.is_empty() || image.has_digest_mismatch() {
+            "Fr"
+        } else if image.is_latest() {
+            "Fy"
+        } else {
+            ""
+        };
+
+        let mut row_cells = vec![
+            Cell::new(&image.repository),
+            Cell::new(image.tag.as_deref().unwrap_or("<none>")),
+            Cell::new(&digests),
+            Cell::new(&image.pods.len().to_string()),
+            Cell::new(&if flags.is_empty() {
+                "<none>".to_string()
+            } else {
+                flags.join("\n")
+            })
+            .style_spec(flag_style),
+        ];
+
+        if matches!(output_format, OutputFormat::Wide) {
+            let kinds = image
+                .container_kinds
+                .iter()
+                .map(|k| k.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let namespaces: BTreeSet<&str> = image
+                .pods
+                .iter()
+                .filter_map(|p| p.split_once('/').map(|(ns, _)| ns))
+                .collect();
+            row_cells.push(Cell::new(&kinds));
+            row_cells.push(Cell::new(
+                &namespaces.into_iter().collect::<Vec<_>>().join("\n"),
+            ));
+        }
+
+        table.add_row(Row::new(row_cells));
+    }
+
+    table.printstd();
+    Ok(())
+}
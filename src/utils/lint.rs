@@ -0,0 +1,118 @@
+use super::{TableDisplayError, create_table};
+use crate::LintOutputFormat;
+use crate::lint::{LintSeverity, LintViolation};
+use prettytable::{Cell, Row};
+use serde_json::json;
+use std::collections::BTreeSet;
+use tracing::warn;
+
+/// Display lint violations as a table, JSON or SARIF
+///
+/// # Arguments
+///
+/// * `violations` - The violations to display
+/// * `output_format` - Format to use for displaying the violations
+///
+/// # Returns
+///
+/// * `Result<()>` - Success or error
+pub fn display_lint_violations(
+    violations: &[LintViolation],
+    output_format: &LintOutputFormat,
+) -> Result<(), TableDisplayError> {
+    match output_format {
+        LintOutputFormat::Table => display_table(violations),
+        LintOutputFormat::Json => print_json(&json!(violations)),
+        LintOutputFormat::Sarif => print_json(&sarif_log(violations)),
+    }
+}
+
+fn display_table(violations: &[LintViolation]) -> Result<(), TableDisplayError> {
+    if violations.is_empty() {
+        warn!("No policy violations found");
+        return Ok(());
+    }
+
+    let mut table = create_table()?;
+    table.add_row(Row::new(vec![
+        Cell::new("KIND"),
+        Cell::new("NAMESPACE"),
+        Cell::new("NAME"),
+        Cell::new("RULE"),
+        Cell::new("SEVERITY"),
+        Cell::new("MESSAGE"),
+    ]));
+
+    for violation in violations {
+        table.add_row(Row::new(vec![
+            Cell::new(&violation.kind),
+            Cell::new(violation.namespace.as_deref().unwrap_or("<none>")),
+            Cell::new(&violation.name),
+            Cell::new(&violation.rule),
+            Cell::new(&violation.severity.to_string()).style_spec(match violation.severity {
+                LintSeverity::Error => "Fr",
+                LintSeverity::Warning => "Fy",
+            }),
+            Cell::new(&violation.message),
+        ]));
+    }
+
+    table.printstd();
+    Ok(())
+}
+
+fn print_json(value: &serde_json::Value) -> Result<(), TableDisplayError> {
+    let output = serde_json::to_string_pretty(value)
+        .map_err(|e| TableDisplayError::new(&format!("Failed to serialize output: {}", e)))?;
+    println!("{}", output);
+    Ok(())
+}
+
+/// Build a SARIF 2.1.0 log with one result per violation, located at the offending object
+fn sarif_log(violations: &[LintViolation]) -> serde_json::Value {
+    let checks: BTreeSet<&str> = violations.iter().map(|violation| violation.check).collect();
+    let rules: Vec<_> = checks
+        .iter()
+        .map(
+            |check| json!({ "id": check, "shortDescription": { "text": check.replace('-', " ") } }),
+        )
+        .collect();
+
+    let results: Vec<_> = violations
+        .iter()
+        .map(|violation| {
+            let location = match &violation.namespace {
+                Some(namespace) => format!("{}/{}/{}", violation.kind, namespace, violation.name),
+                None => format!("{}/{}", violation.kind, violation.name),
+            };
+            json!({
+                "ruleId": violation.check,
+                "level": violation.severity.to_string(),
+                "message": { "text": format!("{} ({})", violation.message, violation.rule) },
+                "locations": [{
+                    "logicalLocations": [{
+                        "name": violation.name,
+                        "fullyQualifiedName": location,
+                        "kind": "resource",
+                    }]
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
@@ -7,7 +7,32 @@ use prettytable::{Cell, Row, Table, format::FormatBuilder};
 use regex::Regex;
 use tracing::warn;
 
+mod access;
+mod audit;
+mod changes;
+mod describe;
+mod diff;
+mod drain;
+mod drift;
+mod events;
+mod groups;
+mod images;
+mod lint;
 pub mod logging;
+mod tree;
+
+pub use access::display_access;
+pub use audit::{display_env_secret_findings, display_security_reports, display_security_summary};
+pub use changes::{confirm, print_metadata_diff};
+pub use describe::{describe_cluster, describe_node, describe_pod};
+pub use diff::{DiffEntry, DiffSection, diff_maps, diff_nodes, diff_pods, print_diff};
+pub use drain::{EvictionProgress, display_evictions};
+pub use drift::display_drift;
+pub use events::{display_events, print_event};
+pub use groups::display_pod_groups;
+pub use images::display_images;
+pub use lint::display_lint_violations;
+pub use tree::display_tree;
 
 /// Filter configuration for environment variables
 #[derive(Debug, Clone)]
@@ -74,6 +99,8 @@ impl TableDisplayError {
 /// * `show_labels` - Whether to include labels in the output
 /// * `show_annotations` - Whether to include annotations in the output
 /// * `all_namespaces` - Whether to show namespace column (only when querying all namespaces)
+/// * `env_vars_filter` - Filter for containers whose environment variables are shown, if any
+/// * `show_events` - Whether to include recent warning events in the output
 ///
 /// # Returns
 ///
@@ -85,6 +112,7 @@ pub fn display_pods(
     show_annotations: bool,
     all_namespaces: bool,
     env_vars_filter: Option<&EnvVarsFilter>,
+    show_events: bool,
 ) -> Result<(), TableDisplayError> {
     if pods.is_empty() {
         warn!("No pods found matching criteria");
@@ -112,8 +140,13 @@ pub fn display_pods(
         header_cells.push(Cell::new("ANNOTATIONS"));
     }
 
+    if show_events {
+        header_cells.push(Cell::new("EVENTS"));
+    }
+
     if matches!(output_format, OutputFormat::Wide) {
         header_cells.push(Cell::new("NODE"));
+        header_cells.push(Cell::new("CONTROLLER"));
     }
 
     let header_row = Row::new(header_cells);
@@ -142,9 +175,19 @@ pub fn display_pods(
             row_cells.push(Cell::new(&format_metadata(&pod.annotations)));
         }
 
+        if show_events {
+            row_cells.push(Cell::new(&events::format_event_summary(&pod.events)));
+        }
+
         if matches!(output_format, OutputFormat::Wide) {
             let node_display = pod.node.as_deref().unwrap_or("<none>");
             row_cells.push(Cell::new(node_display));
+            let controller_display = pod
+                .controller
+                .as_ref()
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "<none>".to_string());
+            row_cells.push(Cell::new(&controller_display));
         }
 
         table.add_row(Row::new(row_cells));
@@ -257,6 +300,7 @@ fn format_container_and_env_vars(
 /// * `output_format` - Format to use for displaying the nodes
 /// * `show_labels` - Whether to include labels in the output
 /// * `show_annotations` - Whether to include annotations in the output
+/// * `show_events` - Whether to include recent warning events in the output
 ///
 /// # Returns
 ///
@@ -266,6 +310,7 @@ pub fn display_nodes(
     _output_format: &OutputFormat,
     show_labels: bool,
     show_annotations: bool,
+    show_events: bool,
 ) -> Result<(), TableDisplayError> {
     if nodes.is_empty() {
         warn!("No nodes found matching criteria");
@@ -286,6 +331,10 @@ pub fn display_nodes(
         header_cells.push(Cell::new("ANNOTATIONS"));
     }
 
+    if show_events {
+        header_cells.push(Cell::new("EVENTS"));
+    }
+
     let header_row = Row::new(header_cells);
     table.add_row(header_row);
 
@@ -303,6 +352,10 @@ pub fn display_nodes(
             row_cells.push(Cell::new(&format_metadata(&node.annotations)));
         }
 
+        if show_events {
+            row_cells.push(Cell::new(&events::format_event_summary(&node.events)));
+        }
+
         table.add_row(Row::new(row_cells));
     }
 
@@ -0,0 +1,45 @@
+use crate::k8s::{FarosTreeNode, ResourceRef};
+use colored::Colorize;
+
+/// Display an ownership tree with one object per line
+///
+/// # Arguments
+///
+/// * `root` - The root of the tree to display
+/// * `highlight` - The object the tree was requested for, shown in bold
+pub fn display_tree(root: &FarosTreeNode, highlight: &ResourceRef) {
+    println!("{}", format_node(root, highlight));
+    print_children(&root.children, highlight, "");
+}
+
+fn print_children(children: &[FarosTreeNode], highlight: &ResourceRef, prefix: &str) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i == children.len() - 1;
+        let (branch, continuation) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        println!("{}{}{}", prefix, branch, format_node(child, highlight));
+        print_children(
+            &child.children,
+            highlight,
+            &format!("{}{}", prefix, continuation),
+        );
+    }
+}
+
+fn format_node(node: &FarosTreeNode, highlight: &ResourceRef) -> String {
+    let object = format!("{}/{}", node.kind, node.name);
+    let object = if node.kind.eq_ignore_ascii_case(&highlight.kind) && node.name == highlight.name {
+        object.bold().to_string()
+    } else {
+        object
+    };
+    let status = match node.healthy {
+        Some(true) => node.status.green(),
+        Some(false) => node.status.red(),
+        None => node.status.dimmed(),
+    };
+    format!("{} {}", object, status)
+}
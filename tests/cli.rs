@@ -0,0 +1,314 @@
+//! End-to-end tests running the fakos binary offline against snapshots of fixture clusters
+
+mod common;
+
+use common::{
+    fakos, fakos_against, fakos_ok, fixture, large_cluster, overloaded_api_server, write_snapshot,
+};
+use std::process::Command;
+
+#[test]
+fn get_pods_shows_controllers() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-pods");
+
+    let output = fakos_ok(&snapshot, &["get", "pods", "-n", "shop", "-o", "wide"]);
+    assert!(output.contains("web-7d9f8b6c5-abcde"), "{}", output);
+    assert!(output.contains("web-7d9f8b6c5-fghij"), "{}", output);
+    assert!(output.contains("Deployment/web"), "{}", output);
+
+    let output = fakos_ok(&snapshot, &["get", "pods"]);
+    assert!(!output.contains("web-"), "{}", output);
+}
+
+#[test]
+fn get_pods_groups_by_node() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-group");
+
+    let output = fakos_ok(&snapshot, &["get", "pods", "-A", "--group-by", "node"]);
+    assert!(output.contains("node/node-a (Ready)"), "{}", output);
+    assert!(
+        output.contains("CPU:     100m/4000m requested"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn get_nodes_reports_not_ready() {
+    let snapshot = write_snapshot(&fixture("degraded"), "cli-degraded-nodes");
+
+    let output = fakos_ok(&snapshot, &["get", "nodes"]);
+    let down = output
+        .lines()
+        .find(|line| line.starts_with("node-down"))
+        .unwrap_or_else(|| panic!("node-down missing: {}", output));
+    assert!(down.contains("NotReady"), "{}", output);
+}
+
+#[test]
+fn get_pods_shows_warning_events() {
+    let snapshot = write_snapshot(&fixture("degraded"), "cli-degraded-events");
+
+    let output = fakos_ok(&snapshot, &["get", "pods", "--events"]);
+    assert!(output.contains("BackOff"), "{}", output);
+    assert!(output.contains("FailedScheduling"), "{}", output);
+}
+
+#[test]
+fn describe_node_shows_gpus() {
+    let snapshot = write_snapshot(&fixture("gpu"), "cli-gpu-describe");
+
+    let output = fakos_ok(&snapshot, &["describe", "node", "gpu-node-1"]);
+    assert!(output.contains("nvidia.com/gpu=8"), "{}", output);
+    assert!(
+        output.contains("nvidia.com/gpu=present:NoSchedule"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn get_pods_in_large_cluster() {
+    let snapshot = write_snapshot(&large_cluster(100, 20), "cli-large-pods");
+
+    let output = fakos_ok(&snapshot, &["get", "pods", "-A"]);
+    assert_eq!(output.lines().filter(|l| l.contains("app-")).count(), 2000);
+
+    let output = fakos_ok(
+        &snapshot,
+        &["get", "pods", "-n", "team-4", "-N", "node-0042"],
+    );
+    assert_eq!(output.lines().filter(|l| l.contains("app-")).count(), 2);
+}
+
+#[test]
+fn changes_to_snapshots_are_refused() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-cordon");
+
+    let output = fakos(&snapshot, &["node", "cordon", "node-a"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("snapshots are read-only"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn replay_serves_recorded_responses() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-replay");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("000001.json"),
+        r#"{"method": "GET", "uri": "/api/v1/nodes?", "status": 200}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("000001.body"),
+        r#"{"apiVersion": "v1", "kind": "NodeList", "metadata": {}, "items": [
+            {"metadata": {"name": "recorded-node"}, "status": {"conditions": [{"type": "Ready", "status": "True"}]}}
+        ]}"#,
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .arg("--replay")
+        .arg(&dir)
+        .args(["get", "nodes"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("recorded-node"));
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .arg("--replay")
+        .arg(&dir)
+        .args(["get", "pods"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("was not recorded"));
+}
+
+#[test]
+fn forbidden_errors_exit_with_rbac_hint() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-forbidden");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("000001.json"),
+        r#"{"method": "GET", "uri": "/api/v1/namespaces/shop/pods?", "status": 403}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("000001.body"),
+        r#"{"kind": "Status", "apiVersion": "v1", "status": "Failure", "reason": "Forbidden", "code": 403,
+            "message": "pods is forbidden: User \"dev\" cannot list resource \"pods\" in API group \"\" in the namespace \"shop\""}"#,
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .arg("--replay")
+        .arg(&dir)
+        .args(["get", "pods", "-n", "shop"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(10), "{}", stderr);
+    assert!(
+        stderr.contains("kubectl auth can-i list pods -n shop"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn missing_resources_exit_with_not_found() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-missing");
+
+    let output = fakos(&snapshot, &["describe", "pod", "missing", "-n", "shop"]);
+    assert_eq!(
+        output.status.code(),
+        Some(11),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn declined_confirmation_exits_with_aborted() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-label-abort");
+
+    // The prompt reads end of input, which counts as no
+    let output = fakos(
+        &snapshot,
+        &["label", "nodes", "pool=gpu", "--confirm-threshold", "0"],
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(13), "{}", stderr);
+    assert!(stderr.contains("no changes applied"), "{}", stderr);
+}
+
+#[test]
+fn manifests_cannot_be_shown_with_events() {
+    let manifest = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("cli-events-pod.yaml");
+    std::fs::write(
+        &manifest,
+        "apiVersion: v1\nkind: Pod\nmetadata:\n  name: web\nspec:\n  containers:\n    - name: web\n      image: nginx\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .args(["get", "pods", "--events", "-f"])
+        .arg(&manifest)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr);
+    assert!(
+        stderr.contains("cannot be used with") && stderr.contains("--filename"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn cluster_info_reports_version_and_api_groups() {
+    let snapshot = write_snapshot(&fixture("healthy"), "cli-healthy-cluster-info");
+
+    let output = fakos_ok(&snapshot, &["cluster", "info"]);
+    assert!(output.contains("v1.32.0-fakos"), "{}", output);
+    assert!(output.contains("<not connected>"), "{}", output);
+    assert!(output.contains("  apps/v1"), "{}", output);
+}
+
+#[test]
+fn invalid_server_url_is_a_configuration_error() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .args([
+            "--server",
+            "not a url",
+            "--token",
+            "secret-token",
+            "get",
+            "pods",
+        ])
+        .env_remove("KUBECONFIG")
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(stderr.contains("Invalid --server"), "{}", stderr);
+    assert!(!stderr.contains("secret-token"), "{}", stderr);
+}
+
+#[test]
+fn in_cluster_outside_a_pod_is_a_configuration_error() {
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .args(["--in-cluster", "get", "pods"])
+        .env_remove("KUBERNETES_SERVICE_HOST")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr);
+    assert!(stderr.contains("in-cluster configuration"), "{}", stderr);
+}
+
+#[test]
+fn overloaded_api_server_is_retried_with_backoff() {
+    let (server, requests) = overloaded_api_server(3);
+
+    let output = fakos_against(&server, &["--max-retries", "2", "-vv", "get", "nodes"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let logs = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(5), "{}", stderr);
+    assert_eq!(logs.matches("Retrying API request").count(), 2, "{}", logs);
+    assert!(logs.contains("retries=2"), "{}", logs);
+
+    let requests = requests.join().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert!(
+        requests.iter().all(|r| r.starts_with("GET /api/v1/nodes")),
+        "{:?}",
+        requests
+    );
+}
+
+#[test]
+fn retries_wait_for_the_client_rate_limit() {
+    let (server, requests) = overloaded_api_server(3);
+
+    let started = std::time::Instant::now();
+    let output = fakos_against(
+        &server,
+        &[
+            "--max-retries",
+            "2",
+            "--qps",
+            "10",
+            "--burst",
+            "1",
+            "-vvv",
+            "get",
+            "nodes",
+        ],
+    );
+    let logs = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(5), "{}", logs);
+    assert_eq!(
+        logs.matches("API request waited for the client rate limit")
+            .count(),
+        2,
+        "{}",
+        logs
+    );
+    assert!(logs.contains("queued_requests=2"), "{}", logs);
+    assert!(started.elapsed() >= std::time::Duration::from_millis(150));
+    assert_eq!(requests.join().unwrap().len(), 3);
+}
@@ -0,0 +1,149 @@
+//! Shared helpers for integration tests running against the in-process fake API server
+
+#![allow(dead_code)]
+
+use fakos::FakeApiServer;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::thread::JoinHandle;
+
+/// Path of a fixture cluster in `tests/fixtures`
+pub fn fixture_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// Serve a fixture cluster from `tests/fixtures`
+pub fn fixture(name: &str) -> FakeApiServer {
+    FakeApiServer::from_fixture(&fixture_path(name))
+        .unwrap_or_else(|e| panic!("Failed to load fixture {}: {:#}", name, e))
+}
+
+/// Serve a generated cluster with `nodes` ready nodes, each running `pods_per_node` pods spread
+/// over ten namespaces
+pub fn large_cluster(nodes: usize, pods_per_node: usize) -> FakeApiServer {
+    let server = fixture("empty");
+    for namespace in 0..10 {
+        server
+            .apply(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": { "name": format!("team-{}", namespace) },
+            }))
+            .unwrap();
+    }
+    for node in 0..nodes {
+        let node_name = format!("node-{:04}", node);
+        server
+            .apply(json!({
+                "apiVersion": "v1",
+                "kind": "Node",
+                "metadata": {
+                    "name": node_name,
+                    "labels": { "pool": format!("pool-{}", node % 4) },
+                },
+                "status": {
+                    "conditions": [{ "type": "Ready", "status": "True" }],
+                    "capacity": { "cpu": "16", "memory": "64Gi", "pods": "110" },
+                    "allocatable": { "cpu": "16", "memory": "62Gi", "pods": "110" },
+                },
+            }))
+            .unwrap();
+        for pod in 0..pods_per_node {
+            server
+                .apply(json!({
+                    "apiVersion": "v1",
+                    "kind": "Pod",
+                    "metadata": {
+                        "name": format!("app-{:04}-{:03}", node, pod),
+                        "namespace": format!("team-{}", (node * pods_per_node + pod) % 10),
+                        "labels": { "app": format!("app-{}", pod % 7) },
+                    },
+                    "spec": {
+                        "nodeName": node_name,
+                        "containers": [{ "name": "app", "image": "example.com/app:1.0" }],
+                    },
+                    "status": { "phase": "Running" },
+                }))
+                .unwrap();
+        }
+    }
+    server
+}
+
+/// Write the objects served by a fake API server to a snapshot file for `--from-snapshot`
+pub fn write_snapshot(server: &FakeApiServer, name: &str) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.snapshot.json", name));
+    server.to_snapshot().write(&path).unwrap();
+    path
+}
+
+/// Run the fakos binary against a snapshot file
+pub fn fakos(snapshot: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .arg("--from-snapshot")
+        .arg(snapshot)
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to run fakos")
+}
+
+/// Run the fakos binary against a snapshot file, returning stdout and failing on errors
+pub fn fakos_ok(snapshot: &Path, args: &[&str]) -> String {
+    let output = fakos(snapshot, args);
+    assert!(
+        output.status.success(),
+        "fakos {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Start an API server that answers requests with 503 and asks to retry immediately
+///
+/// Returns the URL of the server, and a thread returning the request lines of the first
+/// `requests` requests, after which the server stops.
+pub fn overloaded_api_server(requests: usize) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let body = r#"{"kind":"Status","apiVersion":"v1","status":"Failure","message":"overloaded","reason":"ServiceUnavailable","code":503}"#;
+        let mut lines = Vec::new();
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            while reader.read_line(&mut String::new()).unwrap() > 2 {}
+            lines.push(line.trim().to_string());
+            write!(
+                stream,
+                "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        lines
+    });
+    (url, handle)
+}
+
+/// Run the fakos binary against an API server without a kubeconfig, skipping the preflight check
+pub fn fakos_against(server: &str, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fakos"))
+        .args(["--server", server, "--no-preflight"])
+        .args(args)
+        .env_remove("KUBECONFIG")
+        .env("HOME", env!("CARGO_TARGET_TMPDIR"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to run fakos")
+}
@@ -0,0 +1,668 @@
+//! Tests of `K8sClient` against the in-process fake API server
+
+mod common;
+
+use clap::Parser;
+use common::{fixture, large_cluster};
+use fakos::{
+    Args, ClusterSource, ResourceRef, process_commands, required_permissions, review_access,
+};
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+
+#[tokio::test]
+async fn get_pods_resolves_deployment_owner() {
+    let client = fixture("healthy").client();
+
+    let pods = client.get_pods("shop", false, None, None).await.unwrap();
+    assert_eq!(pods.len(), 2);
+    for pod in &pods {
+        assert_eq!(pod.phase, "Running");
+        let controller = pod.controller.as_ref().unwrap();
+        assert_eq!(
+            (controller.kind.as_str(), controller.name.as_str()),
+            ("Deployment", "web")
+        );
+    }
+
+    let pod = client.get_pod("shop", "web-7d9f8b6c5-abcde").await.unwrap();
+    assert_eq!(pod.node.as_deref(), Some("node-a"));
+    assert_eq!(
+        pod.container_env_vars["web"]
+            .get("POD_NAME")
+            .map(String::as_str),
+        Some("web-7d9f8b6c5-abcde")
+    );
+}
+
+#[tokio::test]
+async fn resolve_pod_env_reads_config_maps_referenced_by_all_containers() {
+    let server = fixture("empty");
+    server
+        .apply(json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": { "name": "settings", "namespace": "default" },
+            "data": { "level": "debug", "region": "eu" },
+        }))
+        .unwrap();
+    server
+        .apply(json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": { "name": "app", "namespace": "default" },
+            "spec": {
+                "initContainers": [{
+                    "name": "migrate",
+                    "image": "migrate:1",
+                    "env": [{
+                        "name": "LOG_LEVEL",
+                        "valueFrom": { "configMapKeyRef": { "name": "settings", "key": "level" } },
+                    }],
+                }],
+                "containers": [{
+                    "name": "app",
+                    "image": "app:1",
+                    "env": [{ "name": "LITERAL", "value": "configmap[settings:level]" }],
+                    "envFrom": [{ "prefix": "CFG_", "configMapRef": { "name": "settings" } }],
+                }],
+                "ephemeralContainers": [{
+                    "name": "debug",
+                    "image": "busybox:1",
+                    "envFrom": [{ "configMapRef": { "name": "settings" } }],
+                }],
+            },
+        }))
+        .unwrap();
+    let client = server.client();
+
+    let mut pod = client.get_pod("default", "app").await.unwrap();
+    client.resolve_pod_env(&mut pod).await.unwrap();
+    let env = &pod.container_env_vars;
+    assert_eq!(env["migrate"]["LOG_LEVEL"], "debug");
+    // Literal values that look like references are kept as they are
+    assert_eq!(env["app"]["LITERAL"], "configmap[settings:level]");
+    assert_eq!(env["app"]["CFG_region"], "eu");
+    assert_eq!(env["debug"]["level"], "debug");
+}
+
+#[tokio::test]
+async fn get_pods_filters_by_namespace_and_node() {
+    let client = fixture("healthy").client();
+
+    assert!(
+        client
+            .get_pods("default", false, None, None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let pods = client
+        .get_pods("default", true, Some("node-b"), None)
+        .await
+        .unwrap();
+    assert_eq!(pods.len(), 1);
+    assert_eq!(pods[0].name, "web-7d9f8b6c5-fghij");
+}
+
+#[tokio::test]
+async fn get_pod_missing_is_not_found() {
+    let client = fixture("healthy").client();
+
+    let error = client.get_pod("shop", "missing").await.unwrap_err();
+    assert!(error.to_string().contains("missing"), "{:#}", error);
+}
+
+#[tokio::test]
+async fn get_nodes_reports_readiness_and_taints() {
+    let client = fixture("degraded").client();
+
+    let nodes = client.get_nodes(None).await.unwrap();
+    assert_eq!(nodes.len(), 2);
+
+    let down = client.get_node("node-down").await.unwrap();
+    assert_eq!(down.status, "NotReady");
+    assert!(down.unschedulable);
+    assert!(
+        down.taints
+            .contains(&"node.kubernetes.io/unreachable:NoExecute".to_string())
+    );
+}
+
+#[tokio::test]
+async fn get_nodes_reports_extended_resources() {
+    let client = fixture("gpu").client();
+
+    let node = client.get_node("gpu-node-1").await.unwrap();
+    assert_eq!(
+        node.capacity.get("nvidia.com/gpu").map(String::as_str),
+        Some("8")
+    );
+    assert_eq!(
+        node.taints,
+        vec!["nvidia.com/gpu=present:NoSchedule".to_string()]
+    );
+
+    let pods = client
+        .get_pods("ml", false, Some("gpu-node-1"), None)
+        .await
+        .unwrap();
+    assert_eq!(pods.len(), 1);
+    assert!(
+        pods[0]
+            .tolerations
+            .iter()
+            .any(|t| t.contains("nvidia.com/gpu"))
+    );
+}
+
+#[tokio::test]
+async fn get_events_of_pod() {
+    let client = fixture("degraded").client();
+
+    let resource = "pod/api-0".parse::<ResourceRef>().unwrap();
+    let events = client
+        .get_events("default", false, Some(&resource), false)
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].reason, "BackOff");
+    assert!(events[0].is_warning());
+}
+
+#[tokio::test]
+async fn large_cluster_lists_all_objects() {
+    let client = large_cluster(200, 25).client();
+
+    assert_eq!(client.get_nodes(None).await.unwrap().len(), 200);
+    assert_eq!(
+        client
+            .get_pods("default", true, None, None)
+            .await
+            .unwrap()
+            .len(),
+        5000
+    );
+    assert_eq!(
+        client
+            .get_pods("team-5", false, Some("node-0007"), None)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn watch_events_yields_applied_events() {
+    let server = fixture("degraded");
+    let client = server.client();
+
+    // The watch is only established once the stream is polled
+    let listed = ClusterSource::get_events(&client, "default", false, None, false)
+        .await
+        .unwrap();
+    let mut events = ClusterSource::watch_events(&client, "default", false, None, false, &listed)
+        .await
+        .unwrap();
+    let next_event = tokio::spawn(async move { events.next().await });
+
+    // Give the watcher time to list and start watching before the event is created
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    server
+        .apply(json!({
+            "apiVersion": "events.k8s.io/v1",
+            "kind": "Event",
+            "metadata": { "name": "worker-0.scheduled", "namespace": "default" },
+            "eventTime": "2026-01-01T00:01:00.000000Z",
+            "reportingController": "default-scheduler",
+            "reportingInstance": "default-scheduler",
+            "action": "Binding",
+            "reason": "Scheduled",
+            "type": "Normal",
+            "note": "Successfully assigned default/worker-0 to node-ok",
+            "regarding": { "apiVersion": "v1", "kind": "Pod", "name": "worker-0", "namespace": "default" },
+        }))
+        .unwrap();
+
+    let event = tokio::time::timeout(Duration::from_secs(5), next_event)
+        .await
+        .expect("no event received")
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        (event.name.as_str(), event.reason.as_str()),
+        ("worker-0", "Scheduled")
+    );
+}
+
+/// A `Scheduled` event about a pod in the default namespace
+fn scheduled_event(pod: &str) -> serde_json::Value {
+    json!({
+        "apiVersion": "events.k8s.io/v1",
+        "kind": "Event",
+        "metadata": { "name": format!("{}.scheduled", pod), "namespace": "default" },
+        "eventTime": "2026-01-01T00:01:00.000000Z",
+        "reportingController": "default-scheduler",
+        "reportingInstance": "default-scheduler",
+        "action": "Binding",
+        "reason": "Scheduled",
+        "type": "Normal",
+        "note": format!("Successfully assigned default/{} to node-ok", pod),
+        "regarding": { "apiVersion": "v1", "kind": "Pod", "name": pod, "namespace": "default" },
+    })
+}
+
+#[tokio::test]
+async fn watch_events_shows_events_missed_before_it_started_and_while_it_reconnected() {
+    let server = fixture("degraded");
+    let client = server.client();
+    let listed = ClusterSource::get_events(&client, "default", false, None, false)
+        .await
+        .unwrap();
+    assert!(!listed.is_empty());
+
+    // Created after the events were listed, but before the watch started
+    server.apply(scheduled_event("worker-1")).unwrap();
+    let mut events = ClusterSource::watch_events(&client, "default", false, None, false, &listed)
+        .await
+        .unwrap();
+    let mut next = async || {
+        tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .expect("no event received")
+            .expect("watch ended")
+            .expect("watch failed")
+            .name
+    };
+    assert_eq!(next().await, "worker-1");
+    server.apply(scheduled_event("worker-2")).unwrap();
+    assert_eq!(next().await, "worker-2");
+
+    // The API server expires the watch, and an event is created before it is re-established
+    server.expire_watches();
+    server.apply(scheduled_event("worker-3")).unwrap();
+    assert_eq!(next().await, "worker-3");
+
+    server.apply(scheduled_event("worker-4")).unwrap();
+    assert_eq!(next().await, "worker-4");
+}
+
+#[tokio::test]
+async fn watch_events_includes_pods_created_after_the_watch_started() {
+    let server = fixture("healthy");
+    let client = server.client();
+    let deployment = ResourceRef {
+        kind: "Deployment".to_string(),
+        name: "web".to_string(),
+    };
+
+    let listed = ClusterSource::get_events(&client, "shop", false, Some(&deployment), true)
+        .await
+        .unwrap();
+    let mut events =
+        ClusterSource::watch_events(&client, "shop", false, Some(&deployment), true, &listed)
+            .await
+            .unwrap();
+    let next_event = tokio::spawn(async move { events.next().await });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    // A rollout creates a new ReplicaSet with a new pod, next to a pod of another owner
+    let owner = |kind: &str, name: &str| json!([{ "apiVersion": "apps/v1", "kind": kind, "name": name, "uid": name, "controller": true }]);
+    server
+        .apply(json!({
+            "apiVersion": "apps/v1",
+            "kind": "ReplicaSet",
+            "metadata": { "name": "web-5c6d7e8f9", "namespace": "shop", "ownerReferences": owner("Deployment", "web") },
+        }))
+        .unwrap();
+    for (pod, replica_set) in [
+        ("other-abcde", "other-1"),
+        ("web-5c6d7e8f9-xyz12", "web-5c6d7e8f9"),
+    ] {
+        server
+            .apply(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": { "name": pod, "namespace": "shop", "ownerReferences": owner("ReplicaSet", replica_set) },
+                "spec": { "containers": [{ "name": "web", "image": "web:2" }] },
+            }))
+            .unwrap();
+        server
+            .apply(json!({
+                "apiVersion": "events.k8s.io/v1",
+                "kind": "Event",
+                "metadata": { "name": format!("{}.scheduled", pod), "namespace": "shop" },
+                "eventTime": "2026-01-01T00:01:00.000000Z",
+                "reportingController": "default-scheduler",
+                "reportingInstance": "default-scheduler",
+                "action": "Binding",
+                "reason": "Scheduled",
+                "type": "Normal",
+                "note": format!("Successfully assigned shop/{} to node-a", pod),
+                "regarding": { "apiVersion": "v1", "kind": "Pod", "name": pod, "namespace": "shop" },
+            }))
+            .unwrap();
+    }
+
+    let event = tokio::time::timeout(Duration::from_secs(5), next_event)
+        .await
+        .expect("no event received")
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(event.name, "web-5c6d7e8f9-xyz12");
+}
+
+#[tokio::test]
+async fn review_access_checks_namespaced_permissions_per_namespace() {
+    let client = fixture("healthy").client();
+
+    let namespaces = [Some("shop".to_string()), Some("default".to_string())];
+    let accesses = review_access(&client, &namespaces).await.unwrap();
+
+    let (cluster_scoped, namespaced): (Vec<_>, Vec<_>) = required_permissions()
+        .iter()
+        .partition(|permission| permission.cluster_scoped);
+    assert_eq!(accesses.len(), cluster_scoped.len() + 2 * namespaced.len());
+    assert!(accesses.iter().all(|access| access.allowed));
+    assert!(
+        accesses
+            .iter()
+            .filter(|access| access.permission.cluster_scoped)
+            .all(|access| access.namespace.is_none())
+    );
+}
+
+/// Get the verb, API group, resource and subresource a request is authorized as
+///
+/// Returns None for discovery requests, which every user may send.
+fn request_attributes(request: &str) -> Option<(String, String, String, Option<String>)> {
+    let (method, uri) = request.split_once(' ')?;
+    let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let (group, rest) = match segments.as_slice() {
+        ["api", _, rest @ ..] if !rest.is_empty() => ("", rest),
+        ["apis", group, _, rest @ ..] if !rest.is_empty() => (*group, rest),
+        _ => return None,
+    };
+    let (resource, name, subresource) = match rest {
+        ["namespaces", _, resource, tail @ ..] => (*resource, tail.first(), tail.get(1)),
+        [resource, tail @ ..] => (*resource, tail.first(), tail.get(1)),
+        [] => return None,
+    };
+    let verb = match (method, name) {
+        ("GET", _) if query.split('&').any(|p| p == "watch=true") => "watch",
+        ("GET", Some(_)) => "get",
+        ("GET", None) => "list",
+        ("POST", _) => "create",
+        ("PATCH", _) => "patch",
+        ("PUT", _) => "update",
+        ("DELETE", _) => "delete",
+        _ => return None,
+    };
+    Some((
+        verb.to_string(),
+        group.to_string(),
+        resource.to_string(),
+        subresource.map(|s| s.to_string()),
+    ))
+}
+
+/// Check if a command of the required permissions (e.g. `audit -A`) names a command line, i.e.
+/// it starts with the same subcommand and its other words appear in the command line in the
+/// same order
+fn names_command(name: &str, command: &[&str]) -> bool {
+    let mut words = name.split(' ');
+    let Some((subcommand, rest)) = command.split_first() else {
+        return false;
+    };
+    let mut rest = rest.iter();
+    words.next() == Some(*subcommand) && words.all(|word| rest.any(|w| w == &word))
+}
+
+#[tokio::test]
+async fn required_permissions_cover_the_requests_of_each_command() {
+    let policy = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("permissions-policy.yaml");
+    std::fs::write(&policy, "rules: []\n").unwrap();
+    let snapshot = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("permissions.json");
+    let before = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("permissions-before.json");
+    fixture("healthy").to_snapshot().write(&before).unwrap();
+    let (policy, snapshot, before) = (
+        policy.to_str().unwrap(),
+        snapshot.to_str().unwrap(),
+        before.to_str().unwrap(),
+    );
+    let pod = "web-7d9f8b6c5-abcde";
+    // Requests after a refused change (e.g. evictions of `node drain`) and watches are not sent
+    // to the fake API server, so they are not checked here
+    let commands: &[&[&str]] = &[
+        &["get", "pods", "-n", "shop"],
+        &["get", "pods", "--events", "-n", "shop"],
+        &["get", "pods", "-A", "--group-by", "node"],
+        &["get", "images", "-n", "shop"],
+        &["get", "nodes"],
+        &["get", "nodes", "--events"],
+        &["describe", "pod", pod, "-n", "shop"],
+        &["describe", "pod", "report", "-n", "shop"],
+        &["describe", "node", "node-a"],
+        &["tree", "deploy/web", "-n", "shop"],
+        &["tree", &format!("pod/{}", pod), "-n", "shop"],
+        &["events", "-n", "shop"],
+        &["events", "deploy/web", "--include-pods", "-n", "shop"],
+        &["events", "node/node-a", "--include-pods"],
+        &["events", "cronjob/report", "--include-pods", "-n", "shop"],
+        &["events", &format!("pod/{}", pod), "-n", "shop"],
+        &["audit", "security", "-n", "shop"],
+        &["audit", "security", "-A"],
+        &["audit", "env-secrets", "-n", "shop"],
+        &["audit", "env-secrets", "-A"],
+        &[
+            "lint",
+            "labels",
+            "--policy",
+            policy,
+            "-k",
+            "pods,nodes,deployments,statefulsets,daemonsets,cronjobs",
+            "-n",
+            "shop",
+        ],
+        &[
+            "diff",
+            &format!("pod/{}", pod),
+            "pod/web-7d9f8b6c5-fghij",
+            "-n",
+            "shop",
+        ],
+        &["diff", "node/node-a", "node/node-b"],
+        &[
+            "label", "pods", "tier=web", "-l", "app=web", "-n", "shop", "-y",
+        ],
+        &["label", "nodes", "team=infra", "-y"],
+        &["annotate", "pods", "owner=shop", "-n", "shop", "-y"],
+        &["annotate", "nodes", "owner=infra", "-y"],
+        &["node", "cordon", "node-a"],
+        &["node", "uncordon", "node-a"],
+        &["node", "drain", "node-a", "--dry-run", "client"],
+        &["node", "drain", "node-a"],
+        &["snapshot", "save", snapshot, "-A"],
+        &["drift", before, "-n", "shop"],
+        &["drift", before, "-A"],
+    ];
+
+    let mut missing = Vec::new();
+    for command in commands {
+        let server = fixture("healthy");
+        // A pod reading a ConfigMap, owned by a Job of a CronJob
+        server
+            .apply(json!({
+                "apiVersion": "batch/v1",
+                "kind": "CronJob",
+                "metadata": { "name": "report", "namespace": "shop", "uid": "cronjob-uid" },
+                "spec": { "schedule": "@daily", "jobTemplate": {} },
+            }))
+            .unwrap();
+        server
+            .apply(json!({
+                "apiVersion": "batch/v1",
+                "kind": "Job",
+                "metadata": {
+                    "name": "report-1",
+                    "namespace": "shop",
+                    "uid": "job-uid",
+                    "ownerReferences": [{
+                        "apiVersion": "batch/v1",
+                        "kind": "CronJob",
+                        "name": "report",
+                        "uid": "cronjob-uid",
+                        "controller": true,
+                    }],
+                },
+                "spec": { "template": {} },
+            }))
+            .unwrap();
+        server
+            .apply(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": {
+                    "name": "report",
+                    "namespace": "shop",
+                    "ownerReferences": [{
+                        "apiVersion": "batch/v1",
+                        "kind": "Job",
+                        "name": "report-1",
+                        "uid": "job-uid",
+                        "controller": true,
+                    }],
+                },
+                "spec": {
+                    "containers": [{
+                        "name": "report",
+                        "image": "report:1",
+                        "env": [{
+                            "name": "LEVEL",
+                            "valueFrom": { "configMapKeyRef": { "name": "settings", "key": "level" } },
+                        }],
+                    }],
+                },
+            }))
+            .unwrap();
+        let args = Args::parse_from(std::iter::once("fakos").chain(command.iter().copied()));
+        // Changes are refused by the fake API server, so commands that change objects fail
+        let _ = process_commands(args, &server.client()).await;
+
+        let line = command.join(" ");
+        for request in server.requests() {
+            let Some((verb, group, resource, subresource)) = request_attributes(&request) else {
+                continue;
+            };
+            let covered = required_permissions().iter().any(|permission| {
+                permission.verb == verb
+                    && permission.group == group
+                    && permission.resource == resource
+                    && permission.subresource == subresource.as_deref()
+                    && permission
+                        .commands
+                        .iter()
+                        .any(|c| names_command(c, command))
+            });
+            if !covered {
+                missing.push(format!("`fakos {}` sends {}", line, request));
+            }
+        }
+    }
+    assert!(
+        missing.is_empty(),
+        "Requests not covered by the required permissions:\n{}",
+        missing.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn snapshot_servers_refuse_watches_and_changes() {
+    let snapshot = fixture("healthy").to_snapshot();
+    let server = fakos::FakeApiServer::from_snapshot(snapshot);
+
+    assert!(
+        server
+            .apply(json!({ "apiVersion": "v1", "kind": "Namespace", "metadata": { "name": "x" } }))
+            .is_err()
+    );
+    assert!(
+        server
+            .client()
+            .set_unschedulable("node-a", true, false)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn snapshots_scrub_secrets_and_literal_credentials() {
+    let server = fixture("credentials");
+    let snapshot = server
+        .client()
+        .create_snapshot("default", false)
+        .await
+        .unwrap();
+
+    let written = serde_json::to_string(&snapshot).unwrap();
+    for kept in ["api-config", "LOG_LEVEL", "production"] {
+        assert!(written.contains(kept), "{} missing: {}", kept, written);
+    }
+    for secret in [
+        "c3VwZXItc2VjcmV0LXBhc3N3b3Jk",
+        "hunter2-literal-password",
+        "tok-live-0123456789abcdef",
+    ] {
+        assert!(!written.contains(secret), "{} leaked: {}", secret, written);
+    }
+}
+
+#[tokio::test]
+async fn recordings_scrub_secrets_and_literal_credentials() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("fake-api-record");
+    let _ = std::fs::remove_dir_all(&dir);
+    let server = fixture("credentials");
+    let client = server.recording_client(&dir).unwrap();
+
+    client.create_snapshot("default", false).await.unwrap();
+    let secret = ResourceRef {
+        kind: "Secret".to_string(),
+        name: "db-credentials".to_string(),
+    };
+    ClusterSource::get_owner_tree(&client, "default", &secret)
+        .await
+        .unwrap();
+    let pod = ClusterSource::get_pod(&client, "default", "api-0")
+        .await
+        .unwrap();
+    assert_eq!(pod.name, "api-0");
+
+    let mut recorded = String::new();
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        recorded.push_str(&std::fs::read_to_string(entry.unwrap().path()).unwrap());
+    }
+    assert!(recorded.contains("/secrets/db-credentials"), "{}", recorded);
+    assert!(
+        recorded.contains("last-applied-configuration"),
+        "{}",
+        recorded
+    );
+    assert!(recorded.contains("LOG_LEVEL"), "{}", recorded);
+    for secret in [
+        "c3VwZXItc2VjcmV0LXBhc3N3b3Jk",
+        "hunter2-literal-password",
+        "tok-live-0123456789abcdef",
+    ] {
+        assert!(
+            !recorded.contains(secret),
+            "{} leaked: {}",
+            secret,
+            recorded
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! Tests running commands through `process_commands` against `MockClusterSource`
+//!
+//! Requires the `mock` feature: `cargo test --features mock`
+
+use clap::Parser;
+use fakos::{
+    Args, EvictionState, ExtractOptions, FarosPod, MockClusterSource, PodEviction, process_commands,
+};
+use k8s_openapi::api::core::v1::Pod;
+use mockall::predicate::eq;
+use serde_json::json;
+
+/// Build a pod as the commands see it
+fn pod(namespace: &str, name: &str, node: &str) -> FarosPod {
+    let pod: Pod = serde_json::from_value(json!({
+        "apiVersion": "v1",
+        "kind": "Pod",
+        "metadata": {"name": name, "namespace": namespace, "labels": {"app": "web"}},
+        "spec": {
+            "nodeName": node,
+            "containers": [{
+                "name": "web",
+                "image": "nginx:1.27",
+                "env": [{"name": "MODE", "value": "production"}]
+            }]
+        },
+        "status": {"phase": "Running"}
+    }))
+    .unwrap();
+    FarosPod::from_pod(&pod, &ExtractOptions::default())
+}
+
+/// Parse a command line as the fakos binary does
+fn args(command: &[&str]) -> Args {
+    Args::parse_from(std::iter::once("fakos").chain(command.iter().copied()))
+}
+
+#[tokio::test]
+async fn get_pods_lists_pods_of_the_namespace() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_get_pods()
+        .withf(|namespace, all_namespaces, node, name| {
+            namespace == "shop" && !all_namespaces && node.is_none() && name.is_none()
+        })
+        .times(1)
+        .returning(|_, _, _, _| Ok(vec![pod("shop", "web-1", "worker-1")]));
+
+    process_commands(args(&["get", "pods", "-n", "shop"]), &source)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn get_pods_reports_failures_of_the_source() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_get_pods()
+        .returning(|_, _, _, _| Err(anyhow::anyhow!("connection refused")));
+
+    let error = process_commands(args(&["get", "pods"]), &source)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Failed to get pods");
+}
+
+#[tokio::test]
+async fn describe_pod_resolves_owners_env_and_events() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_get_pod()
+        .with(eq("shop"), eq("web-1"))
+        .times(1)
+        .returning(|namespace, name| Ok(pod(namespace, name, "worker-1")));
+    source
+        .expect_resolve_owner_chain()
+        .times(1)
+        .returning(|_, _| ());
+    source
+        .expect_resolve_pod_env()
+        .withf(|pod| pod.name == "web-1")
+        .times(1)
+        .returning(|_| Ok(()));
+    source
+        .expect_get_events()
+        .withf(|namespace, _, resource, include_pods| {
+            namespace == "shop"
+                && resource.is_some_and(|r| r.kind == "Pod" && r.name == "web-1")
+                && !include_pods
+        })
+        .times(1)
+        .returning(|_, _, _, _| Ok(Vec::new()));
+
+    process_commands(args(&["describe", "pod", "web-1", "-n", "shop"]), &source)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn node_drain_cordons_and_evicts_the_planned_pods() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_select_nodes()
+        .withf(|name, selector| *name == Some("worker-1") && selector.is_none())
+        .returning(|_, _| Ok(vec!["worker-1".to_string()]));
+    source
+        .expect_plan_drain()
+        .withf(|node, _| node == "worker-1")
+        .times(1)
+        .returning(|_, _| {
+            Ok(vec![PodEviction {
+                namespace: "shop".to_string(),
+                name: "web-1".to_string(),
+                uid: None,
+                state: EvictionState::Pending,
+            }])
+        });
+    source
+        .expect_set_unschedulable()
+        .with(eq("worker-1"), eq(true), eq(false))
+        .times(1)
+        .returning(|_, _, _| Ok(true));
+    source
+        .expect_evict_pods()
+        .withf(|pods, _, _| pods.len() == 1 && pods[0].name == "web-1")
+        .times(1)
+        .returning(|pods, _, _| {
+            Ok(pods
+                .into_iter()
+                .map(|pod| PodEviction {
+                    state: EvictionState::Evicted,
+                    ..pod
+                })
+                .collect())
+        });
+
+    process_commands(args(&["node", "drain", "worker-1"]), &source)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn node_drain_fails_if_an_eviction_fails() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_select_nodes()
+        .returning(|_, _| Ok(vec!["worker-1".to_string()]));
+    source.expect_plan_drain().returning(|_, _| {
+        Ok(vec![PodEviction {
+            namespace: "shop".to_string(),
+            name: "web-1".to_string(),
+            uid: None,
+            state: EvictionState::Pending,
+        }])
+    });
+    source
+        .expect_set_unschedulable()
+        .returning(|_, _, _| Ok(true));
+    source.expect_evict_pods().returning(|pods, _, _| {
+        Ok(pods
+            .into_iter()
+            .map(|pod| PodEviction {
+                state: EvictionState::Failed("timed out".to_string()),
+                ..pod
+            })
+            .collect())
+    });
+
+    let error = process_commands(args(&["node", "drain", "worker-1"]), &source)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Failed to evict 1 pod(s)");
+}
+
+#[tokio::test]
+async fn node_drain_client_dry_run_changes_nothing() {
+    let mut source = MockClusterSource::new();
+    source
+        .expect_select_nodes()
+        .returning(|_, _| Ok(vec!["worker-1".to_string()]));
+    source
+        .expect_plan_drain()
+        .times(1)
+        .returning(|_, _| Ok(Vec::new()));
+    source.expect_set_unschedulable().never();
+    source.expect_evict_pods().never();
+
+    process_commands(
+        args(&["node", "drain", "worker-1", "--dry-run", "client"]),
+        &source,
+    )
+    .await
+    .unwrap();
+}